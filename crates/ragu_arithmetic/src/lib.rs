@@ -191,6 +191,15 @@ pub trait FixedGenerators<C: CurveAffine>: Send + Sync + 'static {
     }
 }
 
+/// Fixed generators that can also be used to open commitments with an inner
+/// product argument, which requires one further generator with an unknown
+/// discrete logarithm relationship to the others.
+pub trait OpeningGenerators<C: CurveAffine>: FixedGenerators<C> {
+    /// Generator used to bind the claimed value of an inner product, such as
+    /// in polynomial evaluation (opening) arguments.
+    fn q(&self) -> &C;
+}
+
 /// Specification for a [Poseidon](https://eprint.iacr.org/2019/458) permutation over a field $\mathbb{F}$.
 pub trait PoseidonPermutation<F: Field>: Send + Sync + 'static {
     /// The size of the state.
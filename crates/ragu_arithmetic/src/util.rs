@@ -151,6 +151,9 @@ fn test_bucket_lookup_thresholds() {
 ///
 /// Ensure that the provided iterators have the same length, or this function may not
 /// behave properly or could even panic.
+///
+/// With the `parallel` feature enabled, large multiscalar multiplications are
+/// split into chunks that are computed on separate threads.
 pub fn mul<
     'a,
     C: CurveAffine,
@@ -162,6 +165,34 @@ pub fn mul<
 ) -> C::Curve {
     let coeffs: Vec<_> = coeffs.into_iter().map(|a| a.to_repr()).collect();
 
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        /// The smallest number of terms worth handing to a separate thread.
+        const MIN_CHUNK_SIZE: usize = 1 << 10;
+
+        let num_chunks = rayon::current_num_threads().min(coeffs.len() / MIN_CHUNK_SIZE);
+        if num_chunks > 1 {
+            let bases: Vec<&C> = bases.into_iter().take(coeffs.len()).collect();
+            let chunk_size = coeffs.len().div_ceil(num_chunks);
+            return coeffs
+                .par_chunks(chunk_size)
+                .zip(bases.par_chunks(chunk_size))
+                .map(|(coeffs, bases)| multiexp::<C>(coeffs, bases.iter().copied()))
+                .reduce(C::Curve::identity, |a, b| a + b);
+        }
+    }
+
+    multiexp::<C>(&coeffs, bases)
+}
+
+/// Computes a multiscalar multiplication over scalars that have already been
+/// converted to their byte representations, using the bucket method.
+fn multiexp<'a, C: CurveAffine>(
+    coeffs: &[<C::Scalar as PrimeField>::Repr],
+    bases: impl IntoIterator<Item = &'a C> + Clone,
+) -> C::Curve {
     let c = bucket_lookup(coeffs.len());
 
     fn get_at<F: PrimeField>(segment: usize, c: usize, bytes: &F::Repr) -> usize {
@@ -461,6 +492,29 @@ fn test_mul() {
     assert_eq!(mul(coeffs.iter(), bases.iter()), expected);
 }
 
+#[test]
+fn test_mul_large() {
+    use pasta_curves::group::{Curve, prime::PrimeCurveAffine};
+
+    // Large enough to be split into chunks with the `parallel` feature.
+    let n = 3000;
+    let coeffs: Vec<_> = (0..n)
+        .map(|i| pasta_curves::Fp::from(i) * pasta_curves::Fp::DELTA)
+        .collect();
+    let bases: Vec<_> = (0..n)
+        .map(|i| (pasta_curves::EqAffine::generator() * pasta_curves::Fp::from(i + 7)).to_affine())
+        .collect();
+
+    let expected = coeffs
+        .iter()
+        .zip(bases.iter())
+        .fold(pasta_curves::Eq::identity(), |acc, (scalar, point)| {
+            acc + point * scalar
+        });
+
+    assert_eq!(mul(coeffs.iter(), bases.iter()), expected);
+}
+
 #[test]
 fn test_dot() {
     use pasta_curves::Fp as F;
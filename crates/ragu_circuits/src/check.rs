@@ -0,0 +1,488 @@
+//! Checking that a circuit is satisfied by its witness.
+//!
+//! Drivers that synthesize polynomials never see witness values, and so an
+//! unsatisfying witness only surfaces as a proof that fails to verify. The
+//! [`Checker`] driver instead executes circuit code with a known witness and
+//! describes each constraint that is not satisfied as a [`Violation`], naming
+//! its index along with the [namespace](Driver::namespace) and routines it was
+//! enforced within.
+//!
+//! [`check_circuit`] checks the synthesis of a [`Circuit`] and fails with the
+//! first violation, while [`check_circuit_all`] collects every violation.
+//! [`check_routine`] checks a single [`Routine`], and other circuit code can be
+//! checked by running it on a [`Checker`] directly.
+//!
+//! ## Constraint Indices
+//!
+//! Constraints are numbered as they are in the
+//! [`Wiring`](crate::wiring::Wiring) of a circuit, so that a violation can be
+//! located in its exported wiring. Allocations are packed into multiplication
+//! gates in the same way, and so consume gate indices. When a circuit is
+//! checked, gate $0$ and linear constraint $0$ bind the registry key and are
+//! not part of the circuit's synthesis, so that the first constraint of each
+//! kind enforced by the circuit has index $1$. Otherwise, such as for
+//! [`check_routine`], indices count from $0$ at the start of checking.
+
+use arithmetic::Coeff;
+use ff::Field;
+use ragu_core::{
+    Error, Result,
+    drivers::{DirectSum, Driver, DriverTypes, emulator::Emulator},
+    gadgets::GadgetKind,
+    maybe::{Always, Maybe, MaybeKind},
+    routines::{Prediction, Routine},
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{any::type_name, error, fmt};
+
+use crate::Circuit;
+
+/// The kind of constraint described by a [`Violation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// A multiplication constraint $a \cdot b = c$ enforced by
+    /// [`Driver::mul`].
+    Multiplication,
+    /// A linear constraint enforced by [`Driver::enforce_zero`].
+    Linear,
+}
+
+impl fmt::Display for ConstraintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ConstraintKind::Multiplication => "multiplication",
+            ConstraintKind::Linear => "linear",
+        })
+    }
+}
+
+/// A constraint that is not satisfied by a witness, as found by a [`Checker`].
+///
+/// Checking fails with an [`Error::InvalidWitness`] that holds the first
+/// violation, which can be recovered by downcasting the boxed error.
+#[derive(Clone, Debug)]
+pub struct Violation<F: Field> {
+    /// The kind of constraint.
+    pub kind: ConstraintKind,
+    /// The index of the constraint among those of the same kind, as described
+    /// in the [module documentation](self#constraint-indices).
+    pub index: usize,
+    /// The value that should have been zero: $a \cdot b - c$ for a
+    /// multiplication constraint, or the value of the linear combination for a
+    /// linear constraint.
+    pub value: F,
+    /// The names of the [namespaces](Driver::namespace) the constraint was
+    /// enforced within, outermost first.
+    pub namespaces: Vec<String>,
+    /// The type names of the [`Routine`]s the constraint was enforced within,
+    /// outermost first.
+    pub routines: Vec<&'static str>,
+}
+
+impl<F: Field> Violation<F> {
+    /// Returns the path of the namespace the constraint was enforced within,
+    /// with the name of each namespace separated by `/`.
+    pub fn path(&self) -> String {
+        self.namespaces.join("/")
+    }
+}
+
+impl<F: Field> fmt::Display for Violation<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} constraint {} is not satisfied",
+            self.kind, self.index
+        )?;
+        if !self.namespaces.is_empty() {
+            write!(f, " at {}", self.path())?;
+        }
+        if !self.routines.is_empty() {
+            write!(f, " in routine {}", self.routines.join(" > "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Field> error::Error for Violation<F> {}
+
+/// A driver that executes circuit code with a known witness and checks every
+/// constraint it enforces.
+///
+/// By default, the checker fails with an [`Error::InvalidWitness`] holding the
+/// [`Violation`] of the first constraint that is not satisfied. A checker
+/// created with [`Checker::collect_all`] instead records every violation,
+/// which can be retrieved with [`Checker::violations`]. Routines are always
+/// executed, rather than short-circuited by their prediction, so that their
+/// constraints are checked.
+pub struct Checker<F: Field> {
+    collect_all: bool,
+    num_multiplications: usize,
+    available_b: bool,
+    num_linear_constraints: usize,
+    namespaces: Vec<String>,
+    routines: Vec<&'static str>,
+    violations: Vec<Violation<F>>,
+}
+
+impl<F: Field> Default for Checker<F> {
+    fn default() -> Self {
+        Checker::new()
+    }
+}
+
+impl<F: Field> Checker<F> {
+    /// Creates a checker that fails on the first violated constraint.
+    pub fn new() -> Self {
+        Checker {
+            collect_all: false,
+            num_multiplications: 0,
+            available_b: false,
+            num_linear_constraints: 0,
+            namespaces: Vec::new(),
+            routines: Vec::new(),
+            violations: Vec::new(),
+        }
+    }
+
+    /// Creates a checker that records every violated constraint and continues.
+    pub fn collect_all() -> Self {
+        Checker {
+            collect_all: true,
+            ..Checker::new()
+        }
+    }
+
+    /// Numbers constraints as they are numbered in the wiring of a circuit,
+    /// which begins with the gate and linear constraint for the registry key.
+    fn for_circuit(self) -> Self {
+        Checker {
+            num_multiplications: 1,
+            num_linear_constraints: 1,
+            ..self
+        }
+    }
+
+    /// Returns the violations recorded so far.
+    pub fn violations(&self) -> &[Violation<F>] {
+        &self.violations
+    }
+
+    /// Consumes the checker, returning the violations it recorded.
+    pub fn into_violations(self) -> Vec<Violation<F>> {
+        self.violations
+    }
+
+    fn check(&mut self, kind: ConstraintKind, index: usize, value: F) -> Result<()> {
+        if value == F::ZERO {
+            return Ok(());
+        }
+
+        let violation = Violation {
+            kind,
+            index,
+            value,
+            namespaces: self.namespaces.clone(),
+            routines: self.routines.clone(),
+        };
+        if !self.collect_all {
+            return Err(Error::InvalidWitness(Box::new(violation)));
+        }
+        self.violations.push(violation);
+
+        Ok(())
+    }
+}
+
+impl<F: Field> DriverTypes for Checker<F> {
+    type ImplField = F;
+    type ImplWire = F;
+    type MaybeKind = Always<()>;
+    type LCadd = DirectSum<F>;
+    type LCenforce = DirectSum<F>;
+}
+
+impl<'dr, F: Field> Driver<'dr> for Checker<F> {
+    type F = F;
+    type Wire = F;
+    const ONE: Self::Wire = F::ONE;
+
+    fn alloc(&mut self, value: impl Fn() -> Result<Coeff<Self::F>>) -> Result<Self::Wire> {
+        // Two consecutive allocations share a multiplication gate, which is
+        // unconstrained until it is fully assigned.
+        if self.available_b {
+            self.available_b = false;
+        } else {
+            self.num_multiplications += 1;
+            self.available_b = true;
+        }
+
+        Ok(value()?.value())
+    }
+
+    fn constant(&mut self, value: Coeff<Self::F>) -> Self::Wire {
+        value.value()
+    }
+
+    fn mul(
+        &mut self,
+        values: impl Fn() -> Result<(Coeff<Self::F>, Coeff<Self::F>, Coeff<Self::F>)>,
+    ) -> Result<(Self::Wire, Self::Wire, Self::Wire)> {
+        let (a, b, c) = values()?;
+        let (a, b, c) = (a.value(), b.value(), c.value());
+
+        let index = self.num_multiplications;
+        self.num_multiplications += 1;
+        self.check(ConstraintKind::Multiplication, index, a * b - c)?;
+
+        Ok((a, b, c))
+    }
+
+    fn add(&mut self, lc: impl Fn(Self::LCadd) -> Self::LCadd) -> Self::Wire {
+        lc(DirectSum::default()).value
+    }
+
+    fn enforce_zero(&mut self, lc: impl Fn(Self::LCenforce) -> Self::LCenforce) -> Result<()> {
+        let value = lc(DirectSum::default()).value;
+
+        let index = self.num_linear_constraints;
+        self.num_linear_constraints += 1;
+        self.check(ConstraintKind::Linear, index, value)
+    }
+
+    fn push_namespace<N: Into<String>>(&mut self, name: impl FnOnce() -> N) {
+        self.namespaces.push(name().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        self.namespaces.pop();
+    }
+
+    fn routine<R: Routine<Self::F> + 'dr>(
+        &mut self,
+        routine: R,
+        input: <R::Input as GadgetKind<Self::F>>::Rebind<'dr, Self>,
+    ) -> Result<<R::Output as GadgetKind<Self::F>>::Rebind<'dr, Self>> {
+        let mut dummy = Emulator::wireless();
+        let dummy_input = R::Input::map_gadget(&input, &mut dummy)?;
+        let aux = match routine.predict(&mut dummy, &dummy_input)? {
+            Prediction::Known(_, aux) | Prediction::Unknown(aux) => aux,
+        };
+
+        // Allocations within a routine do not share gates with those outside.
+        let available_b = core::mem::take(&mut self.available_b);
+        self.routines.push(type_name::<R>());
+        let output = routine.execute(self, input, aux);
+        self.routines.pop();
+        self.available_b = available_b;
+
+        output
+    }
+}
+
+/// Checks that `witness` satisfies every constraint enforced by the
+/// [`witness`](Circuit::witness) method of `circuit`, returning its auxiliary
+/// data.
+///
+/// Fails with an [`Error::InvalidWitness`] holding the [`Violation`] of the
+/// first constraint that is not satisfied. The public outputs of the circuit
+/// are not checked, as they are only constrained against an instance.
+pub fn check_circuit<'source, F: Field, C: Circuit<F>>(
+    circuit: &C,
+    witness: C::Witness<'source>,
+) -> Result<C::Aux<'source>> {
+    let mut checker = Checker::new().for_circuit();
+    let (_, aux) = circuit.witness(&mut checker, Always::maybe_just(|| witness))?;
+
+    Ok(aux.take())
+}
+
+/// Checks `witness` against `circuit` as [`check_circuit`] does, but returns
+/// every [`Violation`] rather than failing on the first.
+///
+/// Errors other than violations, such as those returned by the circuit code
+/// itself, are still propagated.
+pub fn check_circuit_all<'source, F: Field, C: Circuit<F>>(
+    circuit: &C,
+    witness: C::Witness<'source>,
+) -> Result<Vec<Violation<F>>> {
+    let mut checker = Checker::collect_all().for_circuit();
+    circuit.witness(&mut checker, Always::maybe_just(|| witness))?;
+
+    Ok(checker.into_violations())
+}
+
+/// Checks that `routine` satisfies every constraint it enforces when executed
+/// on the input created by `input`, returning its output.
+///
+/// The input is created on the [`Checker`], so that the routine can be checked
+/// in isolation from any circuit. Constraints enforced while creating it are
+/// also checked. Fails with an [`Error::InvalidWitness`] holding the
+/// [`Violation`] of the first constraint that is not satisfied.
+pub fn check_routine<'dr, F: Field, Ro: Routine<F> + 'dr>(
+    routine: Ro,
+    input: impl FnOnce(&mut Checker<F>) -> Result<<Ro::Input as GadgetKind<F>>::Rebind<'dr, Checker<F>>>,
+) -> Result<<Ro::Output as GadgetKind<F>>::Rebind<'dr, Checker<F>>> {
+    let mut checker = Checker::new();
+    let input = input(&mut checker)?;
+
+    checker.routine(routine, input)
+}
+
+#[cfg(test)]
+mod tests {
+    use arithmetic::Coeff;
+    use ff::Field;
+    use ragu_core::{
+        Error, Result,
+        drivers::{Driver, DriverValue},
+        gadgets::Kind,
+        maybe::{Always, Maybe, MaybeKind},
+        routines::{Prediction, Routine},
+    };
+    use ragu_pasta::Fp;
+    use ragu_primitives::Element;
+
+    use alloc::{format, vec, vec::Vec};
+    use core::any::type_name;
+
+    use super::{ConstraintKind, Violation, check_circuit, check_circuit_all, check_routine};
+    use crate::{Circuit, polynomials::R, wiring::Wiring};
+
+    /// Claims that each witness pair is `(x, x^2)`.
+    struct Squares;
+
+    impl Circuit<Fp> for Squares {
+        type Instance<'source> = ();
+        type Witness<'source> = Vec<(Fp, Fp)>;
+        type Output = Kind![Fp; ()];
+        type Aux<'source> = usize;
+
+        fn instance<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            _: &mut D,
+            _: DriverValue<D, ()>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            dr: &mut D,
+            witness: DriverValue<D, Vec<(Fp, Fp)>>,
+        ) -> Result<((), DriverValue<D, usize>)> {
+            let pairs = witness.view().map(|pairs| pairs.len());
+            for i in 0..2 {
+                dr.namespace(
+                    || format!("square {i}"),
+                    |dr| {
+                        dr.mul(|| {
+                            let (x, square) = witness.view().take()[i];
+                            Ok((
+                                Coeff::Arbitrary(x),
+                                Coeff::Arbitrary(x),
+                                Coeff::Arbitrary(square),
+                            ))
+                        })
+                    },
+                )?;
+            }
+
+            Ok(((), pairs))
+        }
+    }
+
+    #[test]
+    fn test_check_circuit() -> Result<()> {
+        let pair = |x: u64, square: u64| (Fp::from(x), Fp::from(square));
+
+        assert_eq!(check_circuit(&Squares, vec![pair(2, 4), pair(3, 9)])?, 2);
+        assert!(check_circuit_all(&Squares, vec![pair(2, 4), pair(3, 9)])?.is_empty());
+
+        let violation = match check_circuit(&Squares, vec![pair(2, 4), pair(3, 10)]) {
+            Err(Error::InvalidWitness(error)) => error
+                .downcast::<Violation<Fp>>()
+                .expect("checking should fail with a violation"),
+            _ => panic!("an unsatisfying witness should be rejected"),
+        };
+        assert_eq!(violation.kind, ConstraintKind::Multiplication);
+        assert_eq!(violation.index, 2);
+        assert_eq!(violation.value, -Fp::from(1));
+        assert_eq!(violation.path(), "square 1");
+        assert_eq!(
+            violation.to_string(),
+            "multiplication constraint 2 is not satisfied at square 1"
+        );
+
+        // Gate indices follow the wiring of the circuit, in which the last
+        // square is the last gate.
+        let wiring = Wiring::<Fp, R<5>>::record(&Squares)?;
+        assert_eq!(violation.index, wiring.num_multiplications() - 1);
+
+        let violations = check_circuit_all(&Squares, vec![pair(2, 5), pair(3, 10)])?;
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| violation.index)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        Ok(())
+    }
+
+    /// Allocates its input and claims that the input is its own square.
+    #[derive(Clone)]
+    struct Idempotent;
+
+    impl Routine<Fp> for Idempotent {
+        type Input = Kind![Fp; Element<'_, _>];
+        type Output = Kind![Fp; Element<'_, _>];
+        type Aux<'dr> = ();
+
+        fn execute<'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            dr: &mut D,
+            input: Element<'dr, D>,
+            _: DriverValue<D, ()>,
+        ) -> Result<Element<'dr, D>> {
+            let square = input.square(dr)?;
+            dr.enforce_equal(input.wire(), square.wire())?;
+
+            Ok(square)
+        }
+
+        fn predict<'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            _: &mut D,
+            _: &Element<'dr, D>,
+        ) -> Result<Prediction<Element<'dr, D>, DriverValue<D, ()>>> {
+            Ok(Prediction::Unknown(D::just(|| ())))
+        }
+    }
+
+    #[test]
+    fn test_check_routine() -> Result<()> {
+        let output = check_routine(Idempotent, |dr| {
+            Element::alloc(dr, Always::maybe_just(|| Fp::ONE))
+        })?;
+        assert_eq!(*output.value().take(), Fp::ONE);
+
+        let violation = match check_routine(Idempotent, |dr| {
+            Element::alloc(dr, Always::maybe_just(|| Fp::from(2)))
+        }) {
+            Err(Error::InvalidWitness(error)) => error
+                .downcast::<Violation<Fp>>()
+                .expect("checking should fail with a violation"),
+            _ => panic!("an unsatisfying witness should be rejected"),
+        };
+        // Squaring enforces two linear constraints before the equality.
+        assert_eq!(violation.kind, ConstraintKind::Linear);
+        assert_eq!(violation.index, 2);
+        assert_eq!(violation.routines, [type_name::<Idempotent>()]);
+
+        Ok(())
+    }
+}
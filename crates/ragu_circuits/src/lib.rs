@@ -14,14 +14,16 @@
 
 extern crate alloc;
 
+pub mod check;
 mod ky;
-mod metrics;
+pub mod metrics;
 pub mod polynomials;
 pub mod registry;
 mod rx;
 mod s;
 pub mod staging;
 mod trivial;
+pub mod wiring;
 
 #[cfg(test)]
 mod tests;
@@ -36,7 +38,9 @@ use ragu_primitives::io::Write;
 
 use alloc::{boxed::Box, vec::Vec};
 
+use metrics::CircuitMetrics;
 use polynomials::{Rank, structured, unstructured};
+use wiring::Wiring;
 
 /// Core trait for arithmetic circuits.
 pub trait Circuit<F: Field>: Sized + Send + Sync {
@@ -97,44 +101,11 @@ pub trait CircuitExt<F: Field>: Circuit<F> {
     {
         let metrics = metrics::eval(&self)?;
 
-        if metrics.num_linear_constraints > R::num_coeffs() {
-            return Err(Error::LinearBoundExceeded(R::num_coeffs()));
-        }
-
-        if metrics.num_multiplication_constraints > R::n() {
-            return Err(Error::MultiplicationBoundExceeded(R::n()));
-        }
-
-        struct ProcessedCircuit<C> {
-            circuit: C,
-            metrics: metrics::CircuitMetrics,
-        }
-
-        impl<F: Field, C: Circuit<F>, R: Rank> CircuitObject<F, R> for ProcessedCircuit<C> {
-            fn sxy(&self, x: F, y: F, key: F) -> F {
-                s::sxy::eval::<_, _, R>(&self.circuit, x, y, key)
-                    .expect("should succeed if metrics succeeded")
-            }
-            fn sx(&self, x: F, key: F) -> unstructured::Polynomial<F, R> {
-                s::sx::eval(&self.circuit, x, key).expect("should succeed if metrics succeeded")
-            }
-            fn sy(&self, y: F, key: F) -> structured::Polynomial<F, R> {
-                s::sy::eval(&self.circuit, y, key, self.metrics.num_linear_constraints)
-                    .expect("should succeed if metrics succeeded")
-            }
-            fn constraint_counts(&self) -> (usize, usize) {
-                (
-                    self.metrics.num_multiplication_constraints,
-                    self.metrics.num_linear_constraints,
-                )
-            }
-        }
-
-        let circuit = ProcessedCircuit {
-            circuit: self,
-            metrics,
-        };
-        Ok(Box::new(circuit))
+        processed(
+            self,
+            metrics.num_multiplication_constraints,
+            metrics.num_linear_constraints,
+        )
     }
 
     /// Computes the witness polynomial $r(X)$ given a witness for the circuit.
@@ -154,6 +125,60 @@ pub trait CircuitExt<F: Field>: Circuit<F> {
 
 impl<F: Field, C: Circuit<F>> CircuitExt<F> for C {}
 
+/// Converts `circuit` into a [`CircuitObject`] given its constraint counts,
+/// which must be those computed by [`metrics::eval`].
+pub(crate) fn processed<'a, F: Field, C: Circuit<F> + 'a, R: Rank>(
+    circuit: C,
+    num_multiplication_constraints: usize,
+    num_linear_constraints: usize,
+) -> Result<Box<dyn CircuitObject<F, R> + 'a>> {
+    if num_linear_constraints > R::num_coeffs() {
+        return Err(Error::LinearBoundExceeded(R::num_coeffs()));
+    }
+
+    if num_multiplication_constraints > R::n() {
+        return Err(Error::MultiplicationBoundExceeded(R::n()));
+    }
+
+    struct ProcessedCircuit<C> {
+        circuit: C,
+        num_multiplication_constraints: usize,
+        num_linear_constraints: usize,
+    }
+
+    impl<F: Field, C: Circuit<F>, R: Rank> CircuitObject<F, R> for ProcessedCircuit<C> {
+        fn sxy(&self, x: F, y: F, key: F) -> F {
+            s::sxy::eval::<_, _, R>(&self.circuit, x, y, key)
+                .expect("should succeed if metrics succeeded")
+        }
+        fn sx(&self, x: F, key: F) -> unstructured::Polynomial<F, R> {
+            s::sx::eval(&self.circuit, x, key).expect("should succeed if metrics succeeded")
+        }
+        fn sy(&self, y: F, key: F) -> structured::Polynomial<F, R> {
+            s::sy::eval(&self.circuit, y, key, self.num_linear_constraints)
+                .expect("should succeed if metrics succeeded")
+        }
+        fn constraint_counts(&self) -> (usize, usize) {
+            (
+                self.num_multiplication_constraints,
+                self.num_linear_constraints,
+            )
+        }
+        fn metrics(&self) -> Option<CircuitMetrics> {
+            Some(metrics::eval(&self.circuit).expect("should succeed if metrics succeeded"))
+        }
+        fn wiring(&self) -> Result<Wiring<F, R>> {
+            Wiring::record(&self.circuit)
+        }
+    }
+
+    Ok(Box::new(ProcessedCircuit {
+        circuit,
+        num_multiplication_constraints,
+        num_linear_constraints,
+    }))
+}
+
 /// A trait for (partially) evaluating $s(X, Y)$ for some circuit.
 ///
 /// See [`CircuitExt::into_object`].
@@ -169,4 +194,22 @@ pub trait CircuitObject<F: Field, R: Rank>: Send + Sync {
 
     /// Returns the number of constraints: `(multiplication, linear)`.
     fn constraint_counts(&self) -> (usize, usize);
+
+    /// Synthesizes the circuit again to compute its [`CircuitMetrics`], or
+    /// returns `None` if the circuit's synthesis code is not available (such
+    /// as for a [`Wiring`]).
+    fn metrics(&self) -> Option<CircuitMetrics> {
+        None
+    }
+
+    /// Returns an explicit [`Wiring`] representation of $s(X, Y)$ that can be
+    /// evaluated without this object.
+    ///
+    /// Returns [`Error::Unsupported`] by default, for objects that cannot
+    /// describe their wiring.
+    fn wiring(&self) -> Result<Wiring<F, R>> {
+        Err(Error::Unsupported(
+            "this circuit object does not describe its wiring".into(),
+        ))
+    }
 }
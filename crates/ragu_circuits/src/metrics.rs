@@ -1,3 +1,16 @@
+//! Constraint metrics of circuits.
+//!
+//! [`eval`] synthesizes a circuit with a driver that only counts constraints,
+//! producing the [`CircuitMetrics`] that determine whether it fits within a
+//! [`Rank`](crate::polynomials::Rank). Alongside the totals, every invocation
+//! of [`Driver::routine`] is recorded in a tree of [`RoutineMetrics`] so that
+//! the cost of a circuit can be attributed to the routines it uses. Every
+//! [namespace](Driver::namespace) entered is likewise recorded as
+//! [`NamespaceMetrics`] by its path, such as
+//! `hashes_1/sponge/permutation/round 3`. Circuit objects only retain the
+//! totals, and so the metrics of a registered circuit are computed again by
+//! [`CircuitObject::metrics`](crate::CircuitObject::metrics).
+
 use arithmetic::Coeff;
 use ff::Field;
 use ragu_core::{
@@ -9,7 +22,8 @@ use ragu_core::{
 };
 use ragu_primitives::GadgetExt;
 
-use core::marker::PhantomData;
+use alloc::{format, string::String, vec::Vec};
+use core::{any::type_name, fmt, marker::PhantomData};
 
 use super::Circuit;
 
@@ -25,12 +39,142 @@ pub struct CircuitMetrics {
     // TODO(ebfull): not sure if we'll need this later
     #[allow(dead_code)]
     pub degree_ky: usize,
+
+    /// The metrics of each routine invoked directly by the circuit, in the
+    /// order they were first invoked.
+    pub routines: Vec<RoutineMetrics>,
+
+    /// The metrics of each namespace entered by the circuit, in the order they
+    /// were first entered.
+    pub namespaces: Vec<NamespaceMetrics>,
+}
+
+/// The constraints enforced within a [namespace](Driver::namespace) of a
+/// circuit.
+///
+/// Every entry into a namespace with the same path is merged, so that the
+/// counts are totals over all of them and include the constraints of any
+/// nested namespaces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamespaceMetrics {
+    /// The path of the namespace, with the name of each enclosing namespace
+    /// separated by `/`.
+    pub path: String,
+
+    /// The number of times the namespace was entered.
+    pub calls: usize,
+
+    /// The number of multiplication constraints, including those used for
+    /// allocations.
+    pub num_multiplication_constraints: usize,
+
+    /// The number of linear constraints.
+    pub num_linear_constraints: usize,
+}
+
+impl fmt::Display for NamespaceMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} calls, {} multiplication and {} linear constraints",
+            self.path, self.calls, self.num_multiplication_constraints, self.num_linear_constraints,
+        )
+    }
+}
+
+/// The constraints enforced by a [`Routine`] within a circuit, as a node in a
+/// tree of the routines it invokes.
+///
+/// Every invocation of the same routine type by the same parent is merged into
+/// a single node, whose counts are totals over all of its invocations and
+/// include the constraints of any nested routines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoutineMetrics {
+    /// The type name of the routine.
+    pub name: &'static str,
+
+    /// The number of times the routine was invoked.
+    pub calls: usize,
+
+    /// The number of multiplication constraints, including those used for
+    /// allocations.
+    pub num_multiplication_constraints: usize,
+
+    /// The number of linear constraints.
+    pub num_linear_constraints: usize,
+
+    /// The metrics of each routine invoked by this routine, in the order they
+    /// were first invoked.
+    pub children: Vec<RoutineMetrics>,
+}
+
+impl RoutineMetrics {
+    fn new(name: &'static str) -> Self {
+        RoutineMetrics {
+            name,
+            calls: 0,
+            num_multiplication_constraints: 0,
+            num_linear_constraints: 0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Merges `node` into `nodes`, combining it with a node for the same
+    /// routine if there is one.
+    fn merge_into(nodes: &mut Vec<Self>, node: Self) {
+        match nodes.iter_mut().find(|existing| existing.name == node.name) {
+            Some(existing) => {
+                existing.calls += node.calls;
+                existing.num_multiplication_constraints += node.num_multiplication_constraints;
+                existing.num_linear_constraints += node.num_linear_constraints;
+                for child in node.children {
+                    Self::merge_into(&mut existing.children, child);
+                }
+            }
+            None => nodes.push(node),
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{}: {} calls, {} multiplication and {} linear constraints",
+            "",
+            self.name,
+            self.calls,
+            self.num_multiplication_constraints,
+            self.num_linear_constraints,
+            indent = 2 * depth,
+        )?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.fmt_indented(f, depth + 1))
+    }
+}
+
+impl fmt::Display for RoutineMetrics {
+    /// Writes one line for this routine and each of its descendants, indented
+    /// by their depth in the tree.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
 }
 
 struct Counter<F> {
     available_b: bool,
     num_linear_constraints: usize,
     num_multiplication_constraints: usize,
+    /// The routines currently being executed, outermost first, along with
+    /// the constraint counts when each was entered.
+    routines: Vec<(RoutineMetrics, usize, usize)>,
+    /// The routines invoked directly by the circuit.
+    roots: Vec<RoutineMetrics>,
+    /// The namespaces currently entered, outermost first, as the index of
+    /// their entry in `namespaces` along with the constraint counts when each
+    /// was entered.
+    namespace_stack: Vec<(usize, usize, usize)>,
+    /// Every namespace entered so far.
+    namespaces: Vec<NamespaceMetrics>,
     _marker: PhantomData<F>,
 }
 
@@ -75,6 +219,44 @@ impl<'dr, F: Field> Driver<'dr> for Counter<F> {
         Ok(())
     }
 
+    fn push_namespace<N: Into<String>>(&mut self, name: impl FnOnce() -> N) {
+        let name = name().into();
+        let path = match self.namespace_stack.last() {
+            Some(&(parent, _, _)) => format!("{}/{name}", self.namespaces[parent].path),
+            None => name,
+        };
+        let index = match self.namespaces.iter().position(|node| node.path == path) {
+            Some(index) => index,
+            None => {
+                self.namespaces.push(NamespaceMetrics {
+                    path,
+                    calls: 0,
+                    num_multiplication_constraints: 0,
+                    num_linear_constraints: 0,
+                });
+                self.namespaces.len() - 1
+            }
+        };
+        self.namespace_stack.push((
+            index,
+            self.num_multiplication_constraints,
+            self.num_linear_constraints,
+        ));
+    }
+
+    fn pop_namespace(&mut self) {
+        // Attribute the constraints enforced since entering the namespace to it.
+        let (index, num_multiplication_constraints, num_linear_constraints) = self
+            .namespace_stack
+            .pop()
+            .expect("namespaces should be balanced");
+        let node = &mut self.namespaces[index];
+        node.calls += 1;
+        node.num_multiplication_constraints +=
+            self.num_multiplication_constraints - num_multiplication_constraints;
+        node.num_linear_constraints += self.num_linear_constraints - num_linear_constraints;
+    }
+
     fn routine<Ro: Routine<Self::F> + 'dr>(
         &mut self,
         routine: Ro,
@@ -84,6 +266,11 @@ impl<'dr, F: Field> Driver<'dr> for Counter<F> {
         // logic within the routine.
         let tmp = self.available_b;
         self.available_b = false;
+        self.routines.push((
+            RoutineMetrics::new(type_name::<Ro>()),
+            self.num_multiplication_constraints,
+            self.num_linear_constraints,
+        ));
         let mut dummy = Emulator::wireless();
         let dummy_input = Ro::Input::map_gadget(&input, &mut dummy)?;
         let result = match routine.predict(&mut dummy, &dummy_input)? {
@@ -91,6 +278,18 @@ impl<'dr, F: Field> Driver<'dr> for Counter<F> {
                 routine.execute(self, input, aux)?
             }
         };
+        // Attribute the constraints enforced since entering the routine to it.
+        let (mut node, num_multiplication_constraints, num_linear_constraints) =
+            self.routines.pop().expect("entered above");
+        node.calls = 1;
+        node.num_multiplication_constraints =
+            self.num_multiplication_constraints - num_multiplication_constraints;
+        node.num_linear_constraints = self.num_linear_constraints - num_linear_constraints;
+        let parent = match self.routines.last_mut() {
+            Some((parent, _, _)) => &mut parent.children,
+            None => &mut self.roots,
+        };
+        RoutineMetrics::merge_into(parent, node);
         // Restore the allocation logic state, discarding the state from within
         // the routine.
         self.available_b = tmp;
@@ -98,11 +297,16 @@ impl<'dr, F: Field> Driver<'dr> for Counter<F> {
     }
 }
 
+/// Synthesizes `circuit` to compute its [`CircuitMetrics`].
 pub fn eval<F: Field, C: Circuit<F>>(circuit: &C) -> Result<CircuitMetrics> {
     let mut collector = Counter {
         available_b: false,
         num_linear_constraints: 0,
         num_multiplication_constraints: 0,
+        routines: Vec::new(),
+        roots: Vec::new(),
+        namespace_stack: Vec::new(),
+        namespaces: Vec::new(),
         _marker: PhantomData,
     };
     let mut degree_ky = 0usize;
@@ -114,5 +318,7 @@ pub fn eval<F: Field, C: Circuit<F>>(circuit: &C) -> Result<CircuitMetrics> {
         num_linear_constraints: collector.num_linear_constraints + degree_ky + 2,
         num_multiplication_constraints: collector.num_multiplication_constraints,
         degree_ky,
+        routines: collector.roots,
+        namespaces: collector.namespaces,
     })
 }
@@ -258,6 +258,13 @@ impl<F: Field, R: Rank> Polynomial<F, R> {
         .into() // TODO(ebfull)
     }
 
+    /// Returns the (sparse) coefficient vectors $\mathbf{a}, \mathbf{b},
+    /// \mathbf{c}, \mathbf{d}$ of this polynomial, in that order, from the
+    /// forward perspective.
+    pub fn coeff_vectors(&self) -> [&[F]; 4] {
+        [&self.u, &self.v, &self.w, &self.d]
+    }
+
     /// Reduce this polynomial into its unstructured representation,
     pub fn unstructured(&self) -> super::unstructured::Polynomial<F, R> {
         super::unstructured::Polynomial {
@@ -14,17 +14,29 @@
 //! inserting circuits and performing a [`finalize`](RegistryBuilder::finalize) step
 //! to compile the added circuits into a registry polynomial representation that can
 //! be efficiently evaluated at different restrictions.
+//!
+//! Circuits registered with [`RegistryBuilder::register_circuit`] are not
+//! synthesized until the registry is finalized. Finalization then synthesizes
+//! every circuit to compute its constraint counts, and computes a digest of
+//! the registry polynomial, which requires evaluating every circuit several
+//! times. The [`RegistryCache`] of a finalized [`Registry`] records this
+//! digest along with the constraint counts, the $\omega^j$ lookup and a
+//! fingerprint of each circuit, and can be persisted so that
+//! [`RegistryBuilder::finalize_cached`] only needs to evaluate each circuit
+//! once (to compute its fingerprint) for a builder whose circuits are
+//! unchanged.
 
 use arithmetic::{Domain, PoseidonPermutation, bitreverse};
 use ff::PrimeField;
 use ragu_core::{Error, Result, drivers::emulator::Emulator, maybe::Maybe};
 use ragu_primitives::{Element, poseidon::Sponge};
 
-use alloc::{boxed::Box, collections::btree_map::BTreeMap, vec::Vec};
+use alloc::{boxed::Box, collections::btree_map::BTreeMap, vec, vec::Vec};
 
 use crate::{
-    Circuit, CircuitExt, CircuitObject,
+    Circuit, CircuitObject, metrics,
     polynomials::{Rank, structured, unstructured},
+    processed, s,
 };
 
 /// Represents a simple numeric index of a circuit in the registry.
@@ -38,6 +50,11 @@ impl CircuitIndex {
         Self(index.try_into().unwrap())
     }
 
+    /// Returns the numeric value of this circuit index.
+    pub fn get(self) -> usize {
+        self.0 as usize
+    }
+
     /// Returns $\omega^j$ field element that corresponds to this $i$th circuit index.
     ///
     /// The $i$th circuit added to any [`Registry`] (for a given [`PrimeField`] `F`) is
@@ -57,7 +74,7 @@ impl CircuitIndex {
 
 /// Builder for constructing a new [`Registry`].
 pub struct RegistryBuilder<'params, F: PrimeField, R: Rank> {
-    circuits: Vec<Box<dyn CircuitObject<F, R> + 'params>>,
+    circuits: Vec<Registered<'params, F, R>>,
 }
 
 impl<F: PrimeField, R: Rank> Default for RegistryBuilder<'_, F, R> {
@@ -85,18 +102,26 @@ impl<'params, F: PrimeField, R: Rank> RegistryBuilder<'params, F, R> {
     }
 
     /// Registers a new circuit.
+    ///
+    /// The circuit is not synthesized until the registry is finalized, and so
+    /// an error due to the circuit not fitting within the [`Rank`] is returned
+    /// by finalization instead.
     pub fn register_circuit<C>(self, circuit: C) -> Result<Self>
     where
         C: Circuit<F> + 'params,
     {
-        self.register_circuit_object(circuit.into_object()?)
+        self.register(Registered::Deferred(Box::new(circuit)))
     }
 
     /// Registers a new circuit using a bare circuit object.
     pub fn register_circuit_object(
-        mut self,
+        self,
         circuit: Box<dyn CircuitObject<F, R> + 'params>,
     ) -> Result<Self> {
+        self.register(Registered::Object(circuit))
+    }
+
+    fn register(mut self, circuit: Registered<'params, F, R>) -> Result<Self> {
         let id = self.circuits.len();
         if id >= R::num_coeffs() {
             return Err(Error::CircuitBoundExceeded(id));
@@ -111,6 +136,114 @@ impl<'params, F: PrimeField, R: Rank> RegistryBuilder<'params, F, R> {
     pub fn finalize<P: PoseidonPermutation<F>>(
         self,
         poseidon: &P,
+    ) -> Result<Registry<'params, F, R>> {
+        let fingerprints = RegistryCache::fingerprints(poseidon, &self.circuits)?;
+
+        self.finalize_with(poseidon, fingerprints)
+    }
+
+    /// Builds the final [`Registry`], reusing the data recorded in `cache`
+    /// instead of computing it.
+    ///
+    /// The cache is only used if it was created for a registry with the same
+    /// circuits as this builder, which is checked by comparing the
+    /// fingerprint of each circuit. Otherwise, this falls back to
+    /// [`RegistryBuilder::finalize`]. See [`RegistryCache`] for the extent to
+    /// which the cache is trusted.
+    pub fn finalize_cached<P: PoseidonPermutation<F>>(
+        self,
+        poseidon: &P,
+        cache: &RegistryCache<F>,
+    ) -> Result<Registry<'params, F, R>> {
+        let fingerprints = RegistryCache::fingerprints(poseidon, &self.circuits)?;
+        if !cache
+            .circuits
+            .iter()
+            .map(|cached| cached.fingerprint)
+            .eq(fingerprints.iter().copied())
+        {
+            return self.finalize_with(poseidon, fingerprints);
+        }
+
+        // The circuits are not synthesized again to compute their constraint
+        // counts.
+        let log2_circuits = self.log2_circuits();
+        let circuits = self
+            .circuits
+            .into_iter()
+            .zip(&cache.circuits)
+            .map(|(circuit, cached)| {
+                circuit.into_object(Some((
+                    cached.num_multiplication_constraints,
+                    cached.num_linear_constraints,
+                )))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let omega_lookup = cache
+            .circuits
+            .iter()
+            .enumerate()
+            .map(|(i, cached)| (cached.omega_key, i))
+            .collect();
+
+        Ok(Registry {
+            domain: Domain::new(log2_circuits),
+            circuits,
+            omega_lookup,
+            key: cache.key,
+            fingerprints: Some(fingerprints),
+        })
+    }
+
+    /// Builds the final [`Registry`] from its key and the constraint counts
+    /// `(multiplication, linear)` of each of its circuits, without evaluating
+    /// or synthesizing any circuit.
+    ///
+    /// Neither the key nor the constraint counts are checked against the
+    /// circuits, so they must be authenticated by the caller, such as by
+    /// comparing a digest of them to a trusted value. The counts of circuits
+    /// registered with [`RegistryBuilder::register_circuit_object`] are
+    /// ignored. The resulting registry has no [`RegistryCache`].
+    ///
+    /// Returns [`Error::VectorLengthMismatch`] if the number of counts is not
+    /// the number of circuits.
+    pub fn finalize_with_key(
+        self,
+        key: F,
+        counts: &[(usize, usize)],
+    ) -> Result<Registry<'params, F, R>> {
+        if counts.len() != self.circuits.len() {
+            return Err(Error::VectorLengthMismatch {
+                expected: self.circuits.len(),
+                actual: counts.len(),
+            });
+        }
+
+        self.build(key, None, Some(counts))
+    }
+
+    /// Builds the final [`Registry`] given the fingerprints of its circuits.
+    fn finalize_with<P: PoseidonPermutation<F>>(
+        self,
+        poseidon: &P,
+        fingerprints: Vec<F>,
+    ) -> Result<Registry<'params, F, R>> {
+        // Create provisional registry (circuits still have placeholder K).
+        let mut registry = self.build(F::ONE, Some(fingerprints), None)?;
+
+        // Set registry key to H(M(w, x, y))
+        registry.key = registry.compute_registry_digest(poseidon);
+
+        Ok(registry)
+    }
+
+    /// Creates a registry with the given key, synthesizing each deferred
+    /// circuit to compute its constraint counts unless they are provided.
+    fn build(
+        self,
+        key: F,
+        fingerprints: Option<Vec<F>>,
+        counts: Option<&[(usize, usize)]>,
     ) -> Result<Registry<'params, F, R>> {
         let log2_circuits = self.log2_circuits();
         let domain = Domain::<F>::new(log2_circuits);
@@ -132,18 +265,93 @@ impl<'params, F: PrimeField, R: Rank> RegistryBuilder<'params, F, R> {
             omega_lookup.insert(omega_j, i);
         }
 
-        // Create provisional registry (circuits still have placeholder K).
-        let mut registry = Registry {
+        let circuits = self
+            .circuits
+            .into_iter()
+            .enumerate()
+            .map(|(i, circuit)| circuit.into_object(counts.map(|counts| counts[i])))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Registry {
             domain,
-            circuits: self.circuits,
+            circuits,
             omega_lookup,
-            key: F::ONE,
-        };
+            key,
+            fingerprints,
+        })
+    }
+}
 
-        // Set registry key to H(M(w, x, y))
-        registry.key = registry.compute_registry_digest(poseidon);
+/// A circuit registered with a [`RegistryBuilder`].
+enum Registered<'params, F: PrimeField, R: Rank> {
+    /// A circuit registered with [`RegistryBuilder::register_circuit`], which
+    /// has not yet been synthesized.
+    Deferred(Box<dyn Deferred<'params, F, R> + 'params>),
+    /// A circuit object registered with
+    /// [`RegistryBuilder::register_circuit_object`].
+    Object(Box<dyn CircuitObject<F, R> + 'params>),
+}
 
-        Ok(registry)
+impl<'params, F: PrimeField, R: Rank> Registered<'params, F, R> {
+    fn sxy(&self, x: F, y: F, key: F) -> Result<F> {
+        match self {
+            Registered::Deferred(circuit) => circuit.sxy(x, y, key),
+            Registered::Object(circuit) => Ok(circuit.sxy(x, y, key)),
+        }
+    }
+
+    /// Converts this into a circuit object, given the constraint counts of a
+    /// deferred circuit if they are known.
+    fn into_object(
+        self,
+        counts: Option<(usize, usize)>,
+    ) -> Result<Box<dyn CircuitObject<F, R> + 'params>> {
+        match self {
+            Registered::Deferred(circuit) => circuit.into_object(counts),
+            Registered::Object(circuit) => Ok(circuit),
+        }
+    }
+}
+
+/// A [`Circuit`] whose [`CircuitObject`] is created when the registry is
+/// finalized.
+trait Deferred<'params, F: PrimeField, R: Rank>: Send + Sync {
+    /// Evaluates $s(x, y)$ without computing the metrics of the circuit.
+    fn sxy(&self, x: F, y: F, key: F) -> Result<F>;
+
+    /// Creates the circuit object, synthesizing the circuit to compute its
+    /// constraint counts unless they are provided.
+    fn into_object(
+        self: Box<Self>,
+        counts: Option<(usize, usize)>,
+    ) -> Result<Box<dyn CircuitObject<F, R> + 'params>>;
+}
+
+impl<'params, F: PrimeField, R: Rank, C: Circuit<F> + 'params> Deferred<'params, F, R> for C {
+    fn sxy(&self, x: F, y: F, key: F) -> Result<F> {
+        s::sxy::eval::<_, _, R>(self, x, y, key)
+    }
+
+    fn into_object(
+        self: Box<Self>,
+        counts: Option<(usize, usize)>,
+    ) -> Result<Box<dyn CircuitObject<F, R> + 'params>> {
+        let (num_multiplication_constraints, num_linear_constraints) = match counts {
+            Some(counts) => counts,
+            None => {
+                let metrics = metrics::eval(&*self)?;
+                (
+                    metrics.num_multiplication_constraints,
+                    metrics.num_linear_constraints,
+                )
+            }
+        };
+
+        processed(
+            *self,
+            num_multiplication_constraints,
+            num_linear_constraints,
+        )
     }
 }
 
@@ -162,13 +370,159 @@ pub struct Registry<'params, F: PrimeField, R: Rank> {
     /// Key used to unpredictably change the registry polynomial's evaluation at
     /// non-trivial points.
     key: F,
+
+    /// The fingerprint of each circuit, recorded in the [`RegistryCache`], if
+    /// they were computed.
+    fingerprints: Option<Vec<F>>,
 }
 
 /// Represents a key for identifying a unique $\omega^j$ value where $\omega$ is
 /// a $2^k$-th root of unity.
-#[derive(Ord, PartialOrd, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, PartialEq, Eq)]
 struct OmegaKey(u64);
 
+/// The data derived when finalizing a [`Registry`], which can be persisted with
+/// [`RegistryCache::to_bytes`] and reused by
+/// [`RegistryBuilder::finalize_cached`].
+///
+/// A cache records the registry's key and, for each circuit, its constraint
+/// counts, the key of its $\omega^j$ in the lookup table and a fingerprint.
+/// The fingerprints ensure that the cache is not used for a registry whose
+/// circuits have changed, such as after upgrading an application, but they
+/// are evaluated at a point that anyone can compute, and so they do not
+/// prevent a cache from being crafted for different circuits. The rest of the
+/// cache is trusted, and so a cache must come from a trusted source, such as
+/// storage that only the application that created it can modify.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryCache<F: PrimeField> {
+    key: F,
+    circuits: Vec<CachedCircuit<F>>,
+}
+
+/// The data that a [`RegistryCache`] records for each circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CachedCircuit<F> {
+    fingerprint: F,
+    omega_key: OmegaKey,
+    num_multiplication_constraints: usize,
+    num_linear_constraints: usize,
+}
+
+impl<F: PrimeField> RegistryCache<F> {
+    /// Returns the fingerprint of each circuit, which is the evaluation of its
+    /// $s(X, Y)$ with the placeholder key used while computing the registry
+    /// digest.
+    ///
+    /// The evaluation point is derived by hashing the number of circuits and
+    /// the rank. It is therefore fixed for registries of a given shape, so
+    /// that fingerprints detect changes to the circuits but are not binding
+    /// against circuits constructed to agree at this point.
+    fn fingerprints<P: PoseidonPermutation<F>, R: Rank>(
+        poseidon: &P,
+        circuits: &[Registered<'_, F, R>],
+    ) -> Result<Vec<F>> {
+        let (x, y) = Emulator::emulate_wireless((), |dr, _| {
+            let mut sponge = Sponge::<'_, _, P>::new(dr, poseidon);
+            for value in [circuits.len() as u64, R::RANK.into()] {
+                let value = Element::constant(dr, F::from(value));
+                sponge.absorb(dr, &value)?;
+            }
+            let x = *sponge.squeeze(dr)?.value().take();
+            let y = *sponge.squeeze(dr)?.value().take();
+
+            Ok((x, y))
+        })
+        .expect("fingerprint point computation should always succeed");
+
+        circuits
+            .iter()
+            .map(|circuit| circuit.sxy(x, y, F::ONE))
+            .collect()
+    }
+
+    /// Serializes this cache.
+    ///
+    /// The encoding is the key and the number of circuits, followed by the
+    /// fingerprint, the $\omega^j$ lookup key and the numbers of multiplication
+    /// and linear constraints of each circuit. The number of circuits and the
+    /// numbers of constraints are little-endian `u32`s, lookup keys are
+    /// little-endian `u64`s and field elements use their canonical
+    /// [`PrimeField::Repr`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(self.key.to_repr().as_ref());
+        bytes.extend_from_slice(&(self.circuits.len() as u32).to_le_bytes());
+        for circuit in &self.circuits {
+            bytes.extend_from_slice(circuit.fingerprint.to_repr().as_ref());
+            bytes.extend_from_slice(&circuit.omega_key.0.to_le_bytes());
+            bytes.extend_from_slice(&(circuit.num_multiplication_constraints as u32).to_le_bytes());
+            bytes.extend_from_slice(&(circuit.num_linear_constraints as u32).to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Deserializes a cache produced by [`RegistryCache::to_bytes`].
+    ///
+    /// Returns [`Error::MalformedEncoding`] if the input is not the encoding of
+    /// a cache.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+            if bytes.len() < len {
+                return Err(Error::MalformedEncoding(
+                    "unexpected end of registry cache".into(),
+                ));
+            }
+            let (head, tail) = bytes.split_at(len);
+            *bytes = tail;
+
+            Ok(head)
+        }
+        fn read_u32(bytes: &mut &[u8]) -> Result<u32> {
+            let head = take(bytes, size_of::<u32>())?;
+            Ok(u32::from_le_bytes(head.try_into().expect("took 4 bytes")))
+        }
+        fn read_u64(bytes: &mut &[u8]) -> Result<u64> {
+            let head = take(bytes, size_of::<u64>())?;
+            Ok(u64::from_le_bytes(head.try_into().expect("took 8 bytes")))
+        }
+        fn read_field<F: PrimeField>(bytes: &mut &[u8]) -> Result<F> {
+            let mut repr = F::Repr::default();
+            let len = repr.as_ref().len();
+            repr.as_mut().copy_from_slice(take(bytes, len)?);
+
+            Option::from(F::from_repr(repr)).ok_or_else(|| {
+                Error::MalformedEncoding("non-canonical field element in registry cache".into())
+            })
+        }
+
+        let key = read_field(&mut bytes)?;
+        let num_circuits = read_u32(&mut bytes)? as usize;
+        if num_circuits > bytes.len() {
+            return Err(Error::MalformedEncoding(
+                "unexpected end of registry cache".into(),
+            ));
+        }
+        let circuits = (0..num_circuits)
+            .map(|_| {
+                Ok(CachedCircuit {
+                    fingerprint: read_field(&mut bytes)?,
+                    omega_key: OmegaKey(read_u64(&mut bytes)?),
+                    num_multiplication_constraints: read_u32(&mut bytes)? as usize,
+                    num_linear_constraints: read_u32(&mut bytes)? as usize,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !bytes.is_empty() {
+            return Err(Error::MalformedEncoding(
+                "trailing bytes after registry cache".into(),
+            ));
+        }
+
+        Ok(RegistryCache { key, circuits })
+    }
+}
+
 impl<F: PrimeField> From<F> for OmegaKey {
     fn from(f: F) -> Self {
         // Multiplication by 5 ensures the least significant 64 bits of the
@@ -188,6 +542,39 @@ impl<F: PrimeField> From<F> for OmegaKey {
 }
 
 impl<F: PrimeField, R: Rank> Registry<'_, F, R> {
+    /// Returns the [`RegistryCache`] for this registry, which can be used to
+    /// finalize a builder with the same circuits without recomputing its
+    /// key, or `None` if the registry was built by
+    /// [`RegistryBuilder::finalize_with_key`].
+    pub fn cache(&self) -> Option<RegistryCache<F>> {
+        let fingerprints = self.fingerprints.as_ref()?;
+        let mut omega_keys = vec![OmegaKey(0); self.circuits.len()];
+        for (&omega_key, &i) in &self.omega_lookup {
+            omega_keys[i] = omega_key;
+        }
+        let circuits = self
+            .circuits
+            .iter()
+            .zip(fingerprints)
+            .zip(omega_keys)
+            .map(|((circuit, &fingerprint), omega_key)| {
+                let (num_multiplication_constraints, num_linear_constraints) =
+                    circuit.constraint_counts();
+                CachedCircuit {
+                    fingerprint,
+                    omega_key,
+                    num_multiplication_constraints,
+                    num_linear_constraints,
+                }
+            })
+            .collect();
+
+        Some(RegistryCache {
+            key: self.key,
+            circuits,
+        })
+    }
+
     /// Return the constraint system key for this registry, used by the proof
     /// generator.
     // TODO(ebfull): We should ensure that this detail is not leaked outside of the Registry.
@@ -331,15 +718,21 @@ impl<F: PrimeField, R: Rank> Registry<'_, F, R> {
 
 #[cfg(test)]
 mod tests {
-    use super::{CircuitIndex, OmegaKey, RegistryBuilder};
+    use super::{CircuitIndex, OmegaKey, RegistryBuilder, RegistryCache};
+    use crate::Circuit;
     use crate::polynomials::R;
     use crate::tests::SquareCircuit;
     use alloc::collections::BTreeSet;
     use alloc::collections::btree_map::BTreeMap;
     use arithmetic::{Cycle, Domain, bitreverse};
+    use core::sync::atomic::{AtomicUsize, Ordering};
     use ff::Field;
     use ff::PrimeField;
-    use ragu_core::Result;
+    use ragu_core::{
+        Error, Result,
+        drivers::{Driver, DriverValue},
+        gadgets::GadgetKind,
+    };
     use ragu_pasta::{Fp, Pasta};
     use rand::thread_rng;
 
@@ -415,6 +808,147 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_registry_cache() -> Result<()> {
+        let poseidon = Pasta::circuit_poseidon(Pasta::baked());
+        let builder = |last| {
+            RegistryBuilder::<Fp, TestRank>::new()
+                .register_circuit(SquareCircuit { times: 2 })?
+                .register_circuit(SquareCircuit { times: 5 })?
+                .register_circuit(SquareCircuit { times: last })
+        };
+
+        let registry = builder(10)?.finalize(poseidon)?;
+        let cache = registry.cache().expect("registry was finalized");
+        assert_eq!(RegistryCache::from_bytes(&cache.to_bytes())?, cache);
+
+        // An unchanged builder reuses the cache.
+        let cached = builder(10)?.finalize_cached(poseidon, &cache)?;
+        assert_eq!(cached.get_key(), registry.get_key());
+        assert!(cached.omega_lookup == registry.omega_lookup);
+        assert_eq!(cached.domain.n(), registry.domain.n());
+
+        assert_eq!(cached.cache(), Some(cache.clone()));
+        for (cached, circuit) in cached.circuits().iter().zip(registry.circuits()) {
+            assert_eq!(cached.constraint_counts(), circuit.constraint_counts());
+        }
+
+        // A changed circuit falls back to full finalization.
+        let changed = builder(11)?;
+        assert!(
+            !RegistryCache::fingerprints(poseidon, &changed.circuits)?
+                .into_iter()
+                .eq(cache.circuits.iter().map(|cached| cached.fingerprint))
+        );
+        let changed = changed.finalize_cached(poseidon, &cache)?;
+        assert_eq!(
+            changed.get_key(),
+            builder(11)?.finalize(poseidon)?.get_key()
+        );
+        assert_ne!(changed.get_key(), registry.get_key());
+
+        let bytes = cache.to_bytes();
+        assert!(RegistryCache::<Fp>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(RegistryCache::<Fp>::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalize_with_key() -> Result<()> {
+        let poseidon = Pasta::circuit_poseidon(Pasta::baked());
+        let builder = || {
+            RegistryBuilder::<Fp, TestRank>::new()
+                .register_circuit(SquareCircuit { times: 2 })?
+                .register_circuit(SquareCircuit { times: 5 })
+        };
+
+        let registry = builder()?.finalize(poseidon)?;
+        let counts = registry
+            .circuits()
+            .iter()
+            .map(|circuit| circuit.constraint_counts())
+            .collect::<alloc::vec::Vec<_>>();
+
+        let keyed = builder()?.finalize_with_key(registry.get_key(), &counts)?;
+        assert_eq!(keyed.get_key(), registry.get_key());
+        assert!(keyed.omega_lookup == registry.omega_lookup);
+        assert!(keyed.cache().is_none());
+        let (w, x, y) = (
+            Fp::random(thread_rng()),
+            Fp::random(thread_rng()),
+            Fp::random(thread_rng()),
+        );
+        assert_eq!(keyed.wxy(w, x, y), registry.wxy(w, x, y));
+        assert_eq!(
+            keyed.circuit_y(CircuitIndex::new(1), y).eval(x),
+            registry.circuit_y(CircuitIndex::new(1), y).eval(x)
+        );
+
+        assert!(matches!(
+            builder()?.finalize_with_key(registry.get_key(), &counts[1..]),
+            Err(Error::VectorLengthMismatch { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_cache_synthesizes_once() -> Result<()> {
+        static SYNTHESES: AtomicUsize = AtomicUsize::new(0);
+
+        /// A [`SquareCircuit`] that counts the number of times it has been
+        /// synthesized.
+        struct CountedCircuit;
+
+        impl Circuit<Fp> for CountedCircuit {
+            type Instance<'instance> = Fp;
+            type Output = <SquareCircuit as Circuit<Fp>>::Output;
+            type Witness<'witness> = Fp;
+            type Aux<'witness> = ();
+
+            fn instance<'dr, 'instance: 'dr, D: Driver<'dr, F = Fp>>(
+                &self,
+                dr: &mut D,
+                instance: DriverValue<D, Self::Instance<'instance>>,
+            ) -> Result<<Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>> {
+                SquareCircuit { times: 3 }.instance(dr, instance)
+            }
+
+            fn witness<'dr, 'witness: 'dr, D: Driver<'dr, F = Fp>>(
+                &self,
+                dr: &mut D,
+                witness: DriverValue<D, Self::Witness<'witness>>,
+            ) -> Result<(
+                <Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>,
+                DriverValue<D, Self::Aux<'witness>>,
+            )> {
+                SYNTHESES.fetch_add(1, Ordering::Relaxed);
+                SquareCircuit { times: 3 }.witness(dr, witness)
+            }
+        }
+
+        let poseidon = Pasta::circuit_poseidon(Pasta::baked());
+        let builder = || RegistryBuilder::<Fp, TestRank>::new().register_circuit(CountedCircuit);
+
+        // Registration does not synthesize the circuit.
+        let builder_for_cache = builder()?;
+        assert_eq!(SYNTHESES.load(Ordering::Relaxed), 0);
+        let cache = builder_for_cache
+            .finalize(poseidon)?
+            .cache()
+            .expect("registry was finalized");
+        assert!(SYNTHESES.load(Ordering::Relaxed) > 1);
+
+        // Finalizing from the cache only synthesizes the circuit to compute its
+        // fingerprint.
+        SYNTHESES.store(0, Ordering::Relaxed);
+        builder()?.finalize_cached(poseidon, &cache)?;
+        assert_eq!(SYNTHESES.load(Ordering::Relaxed), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_omega_lookup_correctness() -> Result<()> {
         let log2_circuits = 8;
@@ -1,11 +1,12 @@
 use ff::Field;
 use ragu_core::Result;
 
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 use crate::{
     CircuitObject,
     polynomials::{Rank, structured, unstructured},
+    wiring::{Wire, Wiring},
 };
 
 #[derive(Clone)]
@@ -211,6 +212,20 @@ impl<F: Field, R: Rank> CircuitObject<F, R> for StageMask<R> {
         let num_linear_constraints = 3 * (R::n() - self.num_multiplications - 1) + 2;
         (num_multiplication_constraints, num_linear_constraints)
     }
+
+    fn wiring(&self) -> Result<Wiring<F, R>> {
+        let first_reserved = 1 + self.skip_multiplications + self.num_multiplications;
+
+        // Placeholder constraint, which binds the registry key.
+        let mut constraints = vec![vec![(Wire::A(0), F::ONE)]];
+        for i in (1..=self.skip_multiplications).chain(first_reserved..R::n()) {
+            constraints.extend([Wire::A(i), Wire::B(i), Wire::C(i)].map(|w| vec![(w, F::ONE)]));
+        }
+        // The constant term is always zero.
+        constraints.push(vec![]);
+
+        Wiring::new(R::n(), vec![], constraints)
+    }
 }
 
 #[cfg(test)]
@@ -509,6 +524,7 @@ mod tests {
 
             let stage_mask = StageMask::<R>::new(skip, num).unwrap();
             let comparison_mask = stage_mask.clone().into_object::<R>().unwrap();
+            let wiring = <StageMask<R> as CircuitObject<Fp, R>>::wiring(&stage_mask).unwrap();
 
             let k = Fp::random(thread_rng());
 
@@ -533,6 +549,9 @@ mod tests {
                 prop_assert_eq!(stage_mask.sxy(x, y, k), sxy);
                 prop_assert_eq!(stage_mask.sx(x, k).eval(y), sxy);
                 prop_assert_eq!(stage_mask.sy(y, k).eval(x), sxy);
+                prop_assert_eq!(wiring.sxy(x, y, k), sxy);
+                prop_assert_eq!(wiring.sx(x, k).eval(y), sxy);
+                prop_assert_eq!(wiring.sy(y, k).eval(x), sxy);
 
                 Ok(())
             };
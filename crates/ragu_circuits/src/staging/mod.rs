@@ -356,6 +356,31 @@ pub trait StageExt<F: Field, R: Rank>: Stage<F, R> {
         Ok(rx)
     }
 
+    /// Returns the `index`th value allocated by this stage, as recorded in its
+    /// (partial) witness polynomial `rx`.
+    ///
+    /// Values are recorded in pairs on the $a$ and $b$ wires of consecutive
+    /// multiplication gates, following the gates of the parent stages.
+    fn value(rx: &structured::Polynomial<F, R>, index: usize) -> F {
+        let gate = 1 + Self::skip_multiplications() + index / 2;
+        let [a, b, _, _] = rx.coeff_vectors();
+        let wires = if index.is_multiple_of(2) { a } else { b };
+
+        wires.get(gate).copied().unwrap_or(F::ZERO)
+    }
+
+    /// Returns the degree of the coefficient of the witness polynomial $r(X)$
+    /// in which the `index`th value allocated by this stage is recorded (see
+    /// [`StageExt::value`]).
+    fn value_degree(index: usize) -> usize {
+        let gate = 1 + Self::skip_multiplications() + index / 2;
+        if index.is_multiple_of(2) {
+            2 * R::n() + gate
+        } else {
+            2 * R::n() - 1 - gate
+        }
+    }
+
     /// Compute the (partial) witness polynomial $r(X)$ for this stage, using a
     /// default implementation.
     fn rx(witness: Self::Witness<'_>) -> Result<structured::Polynomial<F, R>>
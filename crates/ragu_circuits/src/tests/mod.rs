@@ -6,14 +6,20 @@ use ragu_core::{
     drivers::{Driver, DriverValue, LinearExpression},
     gadgets::{GadgetKind, Kind},
     maybe::Maybe,
+    routines::{Prediction, Routine},
 };
 use ragu_pasta::Fp;
 use ragu_primitives::Element;
 use rand::thread_rng;
 
+use alloc::{string::ToString, vec};
+use core::any::type_name;
+
 use crate::{
     Circuit, CircuitExt, CircuitObject,
+    metrics::{self, NamespaceMetrics, RoutineMetrics},
     polynomials::{R, Rank},
+    wiring::{Wire, Wiring},
 };
 
 /// Dummy circuit.
@@ -76,6 +82,16 @@ fn consistency_checks<R: Rank>(circuit: &dyn CircuitObject<Fp, R>) {
     assert_eq!(sx0_eval, arithmetic::eval(&sX0_poly[..], x));
     assert_eq!(s00_eval, arithmetic::eval(&s0Y_poly[..], Fp::ZERO));
     assert_eq!(s00_eval, arithmetic::eval(&sX0_poly[..], Fp::ZERO));
+
+    // The recorded wiring must describe the same polynomial.
+    let wiring = circuit.wiring().expect("circuit should be recorded");
+    assert_eq!(sxy_eval, wiring.sxy(x, y, k));
+    assert_eq!(s0y_eval, wiring.sxy(Fp::ZERO, y, k));
+    assert_eq!(sx0_eval, wiring.sxy(x, Fp::ZERO, k));
+    assert_eq!(sxY_poly[..], wiring.sx(x, k)[..]);
+    assert_eq!(sXy_poly[..], wiring.sy(y, k).unstructured()[..]);
+    assert_eq!(s0Y_poly[..], wiring.sx(Fp::ZERO, k)[..]);
+    assert_eq!(sX0_poly[..], wiring.sy(Fp::ZERO, k).unstructured()[..]);
 }
 
 #[test]
@@ -188,3 +204,189 @@ fn test_simple_circuit() {
 
     assert_eq!(expected, arithmetic::dot(a.iter(), b.iter().rev()),);
 }
+
+#[test]
+fn test_wiring_export() -> Result<()> {
+    let wiring = Wiring::<Fp, R<5>>::record(&SquareCircuit { times: 1 })?;
+    assert_eq!(
+        wiring.to_string(),
+        "ragu wiring v1\n\
+         field 0x40000000000000000000000000000000224698fc094cf91b992d30ed00000001\n\
+         gates 3\n\
+         sums 0\n\
+         constraints 5\n\
+         0 = 1*a0\n\
+         0 = 1*a2 + -1*a1\n\
+         0 = 1*b2 + -1*a1\n\
+         0 = 1*c2\n\
+         0 = 1*c0\n"
+    );
+
+    let wiring = Wiring::<Fp, R<5>>::new(
+        2,
+        vec![vec![(Wire::A(1), Fp::from(3)), (Wire::C(0), -Fp::from(5))]],
+        vec![vec![(Wire::Sum(0), Fp::from(1 << 32).square())], vec![]],
+    )?;
+    assert_eq!(
+        wiring.to_string(),
+        "ragu wiring v1\n\
+         field 0x40000000000000000000000000000000224698fc094cf91b992d30ed00000001\n\
+         gates 2\n\
+         sums 1\n\
+         constraints 2\n\
+         s0 = 3*a1 + -5*c0\n\
+         0 = 0x0000000000000000000000000000000000000000000000010000000000000000*s0\n\
+         0 = 0\n"
+    );
+
+    Ok(())
+}
+
+/// Squares an element.
+#[derive(Clone)]
+struct Square;
+
+impl Routine<Fp> for Square {
+    type Input = Kind![Fp; Element<'_, _>];
+    type Output = Kind![Fp; Element<'_, _>];
+    type Aux<'dr> = ();
+
+    fn execute<'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        dr: &mut D,
+        input: Element<'dr, D>,
+        _: DriverValue<D, ()>,
+    ) -> Result<Element<'dr, D>> {
+        input.square(dr)
+    }
+
+    fn predict<'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        _: &mut D,
+        _: &Element<'dr, D>,
+    ) -> Result<Prediction<Element<'dr, D>, DriverValue<D, ()>>> {
+        Ok(Prediction::Unknown(D::just(|| ())))
+    }
+}
+
+/// Raises an element to the fourth power by squaring it twice.
+#[derive(Clone)]
+struct Fourth;
+
+impl Routine<Fp> for Fourth {
+    type Input = Kind![Fp; Element<'_, _>];
+    type Output = Kind![Fp; Element<'_, _>];
+    type Aux<'dr> = ();
+
+    fn execute<'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        dr: &mut D,
+        input: Element<'dr, D>,
+        _: DriverValue<D, ()>,
+    ) -> Result<Element<'dr, D>> {
+        let square = dr.routine(Square, input)?;
+        dr.routine(Square, square)
+    }
+
+    fn predict<'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        _: &mut D,
+        _: &Element<'dr, D>,
+    ) -> Result<Prediction<Element<'dr, D>, DriverValue<D, ()>>> {
+        Ok(Prediction::Unknown(D::just(|| ())))
+    }
+}
+
+/// Computes `x^32` using the [`Fourth`] and [`Square`] routines.
+struct RoutineCircuit;
+
+impl Circuit<Fp> for RoutineCircuit {
+    type Instance<'instance> = Fp;
+    type Output = Kind![Fp; Element<'_, _>];
+    type Witness<'witness> = Fp;
+    type Aux<'witness> = ();
+
+    fn instance<'dr, 'instance: 'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        dr: &mut D,
+        instance: DriverValue<D, Self::Instance<'instance>>,
+    ) -> Result<<Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>> {
+        Element::alloc(dr, instance)
+    }
+
+    fn witness<'dr, 'witness: 'dr, D: Driver<'dr, F = Fp>>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'witness>>,
+    ) -> Result<(
+        <Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>,
+        DriverValue<D, Self::Aux<'witness>>,
+    )> {
+        let x = Element::alloc(dr, witness)?;
+        let x = dr.namespace(
+            || "x^16",
+            |dr| {
+                let x = dr.namespace(|| "fourth", |dr| dr.routine(Fourth, x))?;
+                dr.namespace(|| "fourth", |dr| dr.routine(Fourth, x))
+            },
+        )?;
+        let x = dr.routine(Square, x)?;
+
+        Ok((x, D::just(|| ())))
+    }
+}
+
+#[test]
+fn test_routine_metrics() -> Result<()> {
+    let metrics = metrics::eval(&RoutineCircuit)?;
+    let square = |calls| RoutineMetrics {
+        name: type_name::<Square>(),
+        calls,
+        num_multiplication_constraints: calls,
+        num_linear_constraints: 2 * calls,
+        children: vec![],
+    };
+
+    assert_eq!(
+        metrics.routines,
+        [
+            RoutineMetrics {
+                name: type_name::<Fourth>(),
+                calls: 2,
+                num_multiplication_constraints: 4,
+                num_linear_constraints: 8,
+                children: vec![square(4)],
+            },
+            square(1),
+        ]
+    );
+
+    assert_eq!(
+        metrics.routines[0].to_string(),
+        "ragu_circuits::tests::Fourth: 2 calls, 4 multiplication and 8 linear constraints\n  \
+         ragu_circuits::tests::Square: 4 calls, 4 multiplication and 8 linear constraints\n"
+    );
+
+    let namespace = |path: &str, calls| NamespaceMetrics {
+        path: path.into(),
+        calls,
+        num_multiplication_constraints: 4,
+        num_linear_constraints: 8,
+    };
+    assert_eq!(
+        metrics.namespaces,
+        [namespace("x^16", 1), namespace("x^16/fourth", 2)]
+    );
+    assert_eq!(
+        metrics.namespaces[1].to_string(),
+        "x^16/fourth: 2 calls, 4 multiplication and 8 linear constraints"
+    );
+
+    let circuit = RoutineCircuit.into_object::<R<5>>()?;
+    let object_metrics = circuit.metrics().expect("circuit can be synthesized");
+    assert_eq!(object_metrics.routines, metrics.routines);
+    assert_eq!(object_metrics.namespaces, metrics.namespaces);
+    assert!(circuit.wiring()?.metrics().is_none());
+
+    Ok(())
+}
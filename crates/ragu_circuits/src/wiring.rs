@@ -0,0 +1,525 @@
+//! Explicit representations of wiring polynomials.
+//!
+//! The [`CircuitObject`] produced by [`CircuitExt::into_object`] evaluates
+//! $s(X, Y)$ by interpreting the circuit's synthesis code, and so it cannot
+//! exist without that code. A [`Wiring`] instead records the sequence of
+//! linear constraints that synthesis produced, which is enough to evaluate
+//! $s(X, Y)$ at any restriction. This allows a party that only needs to
+//! _query_ the wiring polynomials, such as a verifier, to reconstruct a
+//! [`Registry`](crate::registry::Registry) from data alone.
+//!
+//! ## Structure
+//!
+//! The wires of the $i$th multiplication gate are represented by
+//! [`Wire::A`], [`Wire::B`] and [`Wire::C`], corresponding to the monomials
+//! $X^{2n - 1 - i}$, $X^{2n + i}$ and $X^{4n - 1 - i}$ respectively. The `ONE`
+//! wire is the $c$ wire of gate $0$. Linear combinations created by
+//! [`Driver::add`] are recorded once and referenced by [`Wire::Sum`], so that
+//! repeatedly accumulated wires do not need to be expanded.
+//!
+//! The $j$th of the $q$ linear constraints is weighted by $Y^{q - 1 - j}$.
+//! Constraint $0$ always binds the registry key, and so its coefficient for
+//! the `ONE` wire is offset by $-k$ for a registry key $k$ during evaluation,
+//! rather than being recorded.
+//!
+//! ## Text Format
+//!
+//! A [`Wiring`] over a [`PrimeField`] is [displayed](fmt::Display) in a line
+//! oriented text format, so that the constraints of a circuit can be compared
+//! between releases or processed by external tools:
+//!
+//! ```text
+//! ragu wiring v1
+//! field <modulus>
+//! gates <number of multiplication gates>
+//! sums <number of linear combinations>
+//! constraints <number of linear constraints>
+//! s<i> = <terms>
+//! 0 = <terms>
+//! ```
+//!
+//! Each linear combination referenced by [`Wire::Sum`] is written as an
+//! `s<i> = <terms>` line in order, followed by each linear constraint as a
+//! `0 = <terms>` line in the order it was enforced. The terms are separated by
+//! ` + ` and written as `<coefficient>*<wire>`, where the wire is one of
+//! `a<i>`, `b<i>`, `c<i>` or `s<i>` for [`Wire::A`], [`Wire::B`], [`Wire::C`]
+//! and [`Wire::Sum`]; a combination without terms is written as `0`.
+//! Coefficients $v$ are written in decimal if $v < 2^{64}$, as `-` followed by
+//! $-v$ in decimal if $-v < 2^{64}$, and otherwise in big-endian hexadecimal
+//! with a `0x` prefix, which is also how the modulus is written.
+//!
+//! [`CircuitExt::into_object`]: crate::CircuitExt::into_object
+//! [`Driver::add`]: ragu_core::drivers::Driver::add
+
+use arithmetic::Coeff;
+use ff::{Field, PrimeField};
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverTypes, LinearExpression, emulator::Emulator},
+    gadgets::GadgetKind,
+    maybe::Empty,
+    routines::{Prediction, Routine},
+};
+use ragu_primitives::GadgetExt;
+
+use alloc::{vec, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use crate::{
+    Circuit, CircuitObject,
+    polynomials::{Rank, structured, unstructured},
+};
+
+/// A wire referenced by the linear constraints of a [`Wiring`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wire {
+    /// The $a$ wire of the $i$th multiplication gate.
+    A(usize),
+    /// The $b$ wire of the $i$th multiplication gate.
+    B(usize),
+    /// The $c$ wire of the $i$th multiplication gate.
+    C(usize),
+    /// The $i$th linear combination recorded in [`Wiring::sums`].
+    Sum(usize),
+}
+
+impl Wire {
+    /// The `ONE` wire.
+    pub const ONE: Self = Wire::C(0);
+}
+
+/// A linear combination of [`Wire`]s.
+pub type Terms<F> = Vec<(Wire, F)>;
+
+/// The recorded linear constraints of a circuit, which determine its wiring
+/// polynomial $s(X, Y)$.
+#[derive(Clone, Debug)]
+pub struct Wiring<F: Field, R: Rank> {
+    num_multiplications: usize,
+    sums: Vec<Terms<F>>,
+    constraints: Vec<Terms<F>>,
+    _marker: PhantomData<R>,
+}
+
+impl<F: Field, R: Rank> Wiring<F, R> {
+    /// Creates a wiring polynomial from `num_multiplications` multiplication
+    /// gates, the linear combinations referenced by [`Wire::Sum`] and the
+    /// linear constraints themselves.
+    ///
+    /// Every wire must refer to an existing multiplication gate, and every
+    /// [`Wire::Sum`] within `sums` must refer to an earlier linear
+    /// combination.
+    pub fn new(
+        num_multiplications: usize,
+        sums: Vec<Terms<F>>,
+        constraints: Vec<Terms<F>>,
+    ) -> Result<Self> {
+        if num_multiplications == 0 || num_multiplications > R::n() {
+            return Err(Error::MultiplicationBoundExceeded(R::n()));
+        }
+        if constraints.is_empty() || constraints.len() > R::num_coeffs() {
+            return Err(Error::LinearBoundExceeded(R::num_coeffs()));
+        }
+
+        let valid = |terms: &[(Wire, F)], num_sums: usize| {
+            terms.iter().all(|(wire, _)| match *wire {
+                Wire::A(i) | Wire::B(i) | Wire::C(i) => i < num_multiplications,
+                Wire::Sum(i) => i < num_sums,
+            })
+        };
+        if !sums.iter().enumerate().all(|(i, terms)| valid(terms, i))
+            || !constraints.iter().all(|terms| valid(terms, sums.len()))
+        {
+            return Err(Error::Initialization(
+                "wiring references an undefined wire".into(),
+            ));
+        }
+
+        Ok(Wiring {
+            num_multiplications,
+            sums,
+            constraints,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Records the wiring polynomial of a circuit by synthesizing it.
+    pub fn record<C: Circuit<F>>(circuit: &C) -> Result<Self> {
+        let mut recorder = Recorder::<F, R> {
+            num_multiplications: 0,
+            sums: vec![],
+            constraints: vec![],
+            available_b: None,
+            _marker: PhantomData,
+        };
+
+        let (key_wire, _, one) = recorder.mul(|| unreachable!())?;
+
+        // The registry key is applied during evaluation.
+        recorder.enforce_zero(|lc| lc.add(&key_wire))?;
+
+        let mut outputs = vec![];
+        let (io, _) = circuit.witness(&mut recorder, Empty)?;
+        io.write(&mut recorder, &mut outputs)?;
+        for output in outputs {
+            recorder.enforce_zero(|lc| lc.add(output.wire()))?;
+        }
+        recorder.enforce_zero(|lc| lc.add(&one))?;
+
+        Self::new(
+            recorder.num_multiplications,
+            recorder.sums,
+            recorder.constraints,
+        )
+    }
+
+    /// Returns the number of multiplication gates.
+    pub fn num_multiplications(&self) -> usize {
+        self.num_multiplications
+    }
+
+    /// Returns the linear combinations referenced by [`Wire::Sum`].
+    pub fn sums(&self) -> &[Terms<F>] {
+        &self.sums
+    }
+
+    /// Returns the linear constraints, in the order they were enforced.
+    pub fn constraints(&self) -> &[Terms<F>] {
+        &self.constraints
+    }
+
+    /// Evaluates every wire and linear combination at $X = x$, returning
+    /// the per-gate monomials for the $a$, $b$ and $c$ wires and the values
+    /// of each linear combination.
+    fn wire_evals(&self, x: F) -> ([Vec<F>; 3], Vec<F>) {
+        let n = R::n() as u64;
+        let x_inv = x.invert().unwrap_or(F::ZERO);
+        let xn2 = x.pow_vartime([2 * n]);
+
+        let mut current = [xn2 * x_inv, xn2, xn2.square() * x_inv];
+        let mut monomials: [Vec<F>; 3] = Default::default();
+        for _ in 0..self.num_multiplications {
+            for (monomials, current) in monomials.iter_mut().zip(current.iter()) {
+                monomials.push(*current);
+            }
+            current[0] *= x_inv;
+            current[1] *= x;
+            current[2] *= x_inv;
+        }
+
+        let mut sums = Vec::with_capacity(self.sums.len());
+        for terms in &self.sums {
+            let value = eval_terms(terms, &monomials, &sums);
+            sums.push(value);
+        }
+
+        (monomials, sums)
+    }
+
+    /// Returns the evaluations at $X = x$ of each linear constraint, including
+    /// the registry key.
+    fn constraint_evals(&self, x: F, key: F) -> impl DoubleEndedIterator<Item = F> {
+        let (monomials, sums) = self.wire_evals(x);
+        let key_term = key * monomials[2][0];
+
+        self.constraints
+            .iter()
+            .enumerate()
+            .map(move |(j, terms)| {
+                let eval = eval_terms(terms, &monomials, &sums);
+                if j == 0 { eval - key_term } else { eval }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+fn eval_terms<F: Field>(terms: &[(Wire, F)], monomials: &[Vec<F>; 3], sums: &[F]) -> F {
+    terms
+        .iter()
+        .map(|(wire, coeff)| {
+            *coeff
+                * match *wire {
+                    Wire::A(i) => monomials[0][i],
+                    Wire::B(i) => monomials[1][i],
+                    Wire::C(i) => monomials[2][i],
+                    Wire::Sum(i) => sums[i],
+                }
+        })
+        .sum()
+}
+
+impl<F: Field, R: Rank> CircuitObject<F, R> for Wiring<F, R> {
+    fn sxy(&self, x: F, y: F, key: F) -> F {
+        if x == F::ZERO {
+            return F::ZERO;
+        }
+
+        self.constraint_evals(x, key)
+            .fold(F::ZERO, |acc, eval| acc * y + eval)
+    }
+
+    fn sx(&self, x: F, key: F) -> unstructured::Polynomial<F, R> {
+        if x == F::ZERO {
+            return unstructured::Polynomial::new();
+        }
+
+        unstructured::Polynomial::from_coeffs(self.constraint_evals(x, key).rev().collect())
+    }
+
+    fn sy(&self, y: F, key: F) -> structured::Polynomial<F, R> {
+        let mut weights: [Vec<F>; 3] = Default::default();
+        for weights in weights.iter_mut() {
+            weights.resize(self.num_multiplications, F::ZERO);
+        }
+        let mut sum_weights = vec![F::ZERO; self.sums.len()];
+
+        let mut distribute = |terms: &[(Wire, F)], weight: F, sum_weights: &mut [F]| {
+            for (wire, coeff) in terms {
+                let weight = weight * coeff;
+                match *wire {
+                    Wire::A(i) => weights[0][i] += weight,
+                    Wire::B(i) => weights[1][i] += weight,
+                    Wire::C(i) => weights[2][i] += weight,
+                    Wire::Sum(i) => sum_weights[i] += weight,
+                }
+            }
+        };
+
+        // Weight the jth constraint by y^(q - 1 - j).
+        let mut yq = F::ONE;
+        for (j, terms) in self.constraints.iter().enumerate().rev() {
+            distribute(terms, yq, &mut sum_weights);
+            if j == 0 {
+                distribute(&[(Wire::ONE, -key)], yq, &mut sum_weights);
+            }
+            yq *= y;
+        }
+
+        // Linear combinations only reference earlier linear combinations, so
+        // their weights are final once all later ones have been distributed.
+        for i in (0..self.sums.len()).rev() {
+            let weight = sum_weights[i];
+            if weight != F::ZERO {
+                distribute(&self.sums[i], weight, &mut sum_weights);
+            }
+        }
+
+        let [a, b, c] = weights;
+        let mut poly = structured::Polynomial::new();
+        {
+            let view = poly.backward();
+            *view.a = a;
+            *view.b = b;
+            *view.c = c;
+        }
+
+        poly
+    }
+
+    fn constraint_counts(&self) -> (usize, usize) {
+        (self.num_multiplications, self.constraints.len())
+    }
+
+    fn wiring(&self) -> Result<Wiring<F, R>> {
+        Ok(self.clone())
+    }
+}
+
+impl<F: PrimeField, R: Rank> fmt::Display for Wiring<F, R> {
+    /// Writes this wiring in the text format described in the
+    /// [module documentation](self).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ragu wiring v1")?;
+        writeln!(f, "field {}", F::MODULUS)?;
+        writeln!(f, "gates {}", self.num_multiplications)?;
+        writeln!(f, "sums {}", self.sums.len())?;
+        writeln!(f, "constraints {}", self.constraints.len())?;
+        for (i, terms) in self.sums.iter().enumerate() {
+            writeln!(f, "s{i} = {}", DisplayTerms(terms))?;
+        }
+        for terms in &self.constraints {
+            writeln!(f, "0 = {}", DisplayTerms(terms))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Wire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Wire::A(i) => write!(f, "a{i}"),
+            Wire::B(i) => write!(f, "b{i}"),
+            Wire::C(i) => write!(f, "c{i}"),
+            Wire::Sum(i) => write!(f, "s{i}"),
+        }
+    }
+}
+
+/// Displays a linear combination in the text format of a [`Wiring`].
+struct DisplayTerms<'a, F>(&'a [(Wire, F)]);
+
+impl<F: PrimeField> fmt::Display for DisplayTerms<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, (wire, coeff)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            write_coeff(f, *coeff)?;
+            write!(f, "*{wire}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a coefficient in decimal if it or its negation is less than $2^{64}$,
+/// and in big-endian hexadecimal otherwise.
+fn write_coeff<F: PrimeField>(f: &mut fmt::Formatter<'_>, coeff: F) -> fmt::Result {
+    // The byte order of `PrimeField::Repr` is not fixed, so detect it from the
+    // encoding of one.
+    let big_endian = |value: F| {
+        let mut bytes = value.to_repr().as_ref().to_vec();
+        if F::ONE.to_repr().as_ref()[0] == 1 {
+            bytes.reverse();
+        }
+        bytes
+    };
+    let small = |bytes: &[u8]| {
+        let (high, low) = bytes.split_at(bytes.len().saturating_sub(8));
+        high.iter().all(|&byte| byte == 0).then(|| {
+            low.iter()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte))
+        })
+    };
+
+    let bytes = big_endian(coeff);
+    if let Some(value) = small(&bytes) {
+        write!(f, "{value}")
+    } else if let Some(value) = small(&big_endian(-coeff)) {
+        write!(f, "-{value}")
+    } else {
+        write!(f, "0x")?;
+        bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// A [`Driver`] that records the linear constraints of a circuit.
+struct Recorder<F, R> {
+    num_multiplications: usize,
+    sums: Vec<Terms<F>>,
+    constraints: Vec<Terms<F>>,
+    /// Stashed $b$ wire from paired allocation (see [`Driver::alloc`]).
+    available_b: Option<Wire>,
+    _marker: PhantomData<R>,
+}
+
+/// Accumulates the terms of a linear combination for the [`Recorder`].
+struct TermsBuilder<F: Field> {
+    terms: Terms<F>,
+    gain: Coeff<F>,
+}
+
+impl<F: Field> TermsBuilder<F> {
+    fn new() -> Self {
+        TermsBuilder {
+            terms: vec![],
+            gain: Coeff::One,
+        }
+    }
+}
+
+impl<F: Field> LinearExpression<Wire, F> for TermsBuilder<F> {
+    fn add_term(mut self, wire: &Wire, coeff: Coeff<F>) -> Self {
+        let coeff = (coeff * self.gain).value();
+        if coeff != F::ZERO {
+            self.terms.push((*wire, coeff));
+        }
+        self
+    }
+
+    fn gain(mut self, coeff: Coeff<F>) -> Self {
+        self.gain = self.gain * coeff;
+        self
+    }
+}
+
+impl<F: Field, R: Rank> DriverTypes for Recorder<F, R> {
+    type MaybeKind = Empty;
+    type LCadd = TermsBuilder<F>;
+    type LCenforce = TermsBuilder<F>;
+    type ImplField = F;
+    type ImplWire = Wire;
+}
+
+impl<'dr, F: Field, R: Rank> Driver<'dr> for Recorder<F, R> {
+    type F = F;
+    type Wire = Wire;
+
+    const ONE: Self::Wire = Wire::ONE;
+
+    fn alloc(&mut self, _: impl Fn() -> Result<Coeff<Self::F>>) -> Result<Self::Wire> {
+        if let Some(wire) = self.available_b.take() {
+            Ok(wire)
+        } else {
+            let (a, b, _) = self.mul(|| unreachable!())?;
+            self.available_b = Some(b);
+
+            Ok(a)
+        }
+    }
+
+    fn mul(
+        &mut self,
+        _: impl Fn() -> Result<(Coeff<F>, Coeff<F>, Coeff<F>)>,
+    ) -> Result<(Self::Wire, Self::Wire, Self::Wire)> {
+        let index = self.num_multiplications;
+        if index == R::n() {
+            return Err(Error::MultiplicationBoundExceeded(R::n()));
+        }
+        self.num_multiplications += 1;
+
+        Ok((Wire::A(index), Wire::B(index), Wire::C(index)))
+    }
+
+    fn add(&mut self, lc: impl Fn(Self::LCadd) -> Self::LCadd) -> Self::Wire {
+        let terms = lc(TermsBuilder::new()).terms;
+        match terms.as_slice() {
+            [(wire, coeff)] if *coeff == F::ONE => *wire,
+            _ => {
+                self.sums.push(terms);
+                Wire::Sum(self.sums.len() - 1)
+            }
+        }
+    }
+
+    fn enforce_zero(&mut self, lc: impl Fn(Self::LCenforce) -> Self::LCenforce) -> Result<()> {
+        if self.constraints.len() == R::num_coeffs() {
+            return Err(Error::LinearBoundExceeded(R::num_coeffs()));
+        }
+        self.constraints.push(lc(TermsBuilder::new()).terms);
+
+        Ok(())
+    }
+
+    fn routine<Ro: Routine<Self::F> + 'dr>(
+        &mut self,
+        routine: Ro,
+        input: <Ro::Input as GadgetKind<Self::F>>::Rebind<'dr, Self>,
+    ) -> Result<<Ro::Output as GadgetKind<Self::F>>::Rebind<'dr, Self>> {
+        let tmp = self.available_b.take();
+        let mut dummy = Emulator::wireless();
+        let dummy_input = Ro::Input::map_gadget(&input, &mut dummy)?;
+        let result = match routine.predict(&mut dummy, &dummy_input)? {
+            Prediction::Known(_, aux) | Prediction::Unknown(aux) => {
+                routine.execute(self, input, aux)?
+            }
+        };
+        self.available_b = tmp;
+        Ok(result)
+    }
+}
@@ -43,6 +43,14 @@
 //! optimizations. In order to achieve this, drivers implement the
 //! [`FromDriver`] trait to specify how wires can be translated from one driver
 //! to another.
+//!
+//! ### Namespaces
+//!
+//! Circuit code can name the sections of synthesis it performs with
+//! [`Driver::namespace`], so that drivers which check or measure constraints
+//! can describe where a constraint was created with a hierarchical path such
+//! as `sponge/permutation/round 3`. Namespaces do not affect the constraints
+//! themselves, and most drivers ignore them.
 
 pub mod emulator;
 mod linexp;
@@ -58,6 +66,8 @@ use crate::{
     routines::{Prediction, Routine},
 };
 
+use alloc::string::String;
+
 pub use linexp::{DirectSum, LinearExpression};
 
 /// Alias for the concrete [`Maybe<T>`] type for a driver `D`, used to represent input data
@@ -181,6 +191,34 @@ pub trait Driver<'dr>: DriverTypes<ImplWire = Self::Wire, ImplField = Self::F> +
         self.enforce_zero(|lc| lc.add(a).sub(b))
     }
 
+    /// Enters a namespace, which is named by `name` within the current
+    /// namespace until the matching [`pop_namespace`](Driver::pop_namespace).
+    ///
+    /// Drivers only call `name` if they record namespaces, and by default they
+    /// do not. Prefer [`namespace`](Driver::namespace), which cannot leave a
+    /// namespace unbalanced.
+    fn push_namespace<N: Into<String>>(&mut self, name: impl FnOnce() -> N) {
+        let _ = name;
+    }
+
+    /// Leaves the namespace most recently entered with
+    /// [`push_namespace`](Driver::push_namespace).
+    fn pop_namespace(&mut self) {}
+
+    /// Runs `f` within a namespace named by `name`. See the
+    /// [module documentation](self#namespaces).
+    fn namespace<N: Into<String>, T>(
+        &mut self,
+        name: impl FnOnce() -> N,
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.push_namespace(name);
+        let result = f(self);
+        self.pop_namespace();
+
+        result
+    }
+
     /// Proxy for the `Input::just` method for this driver.
     fn just<R: Send>(f: impl FnOnce() -> R) -> DriverValue<Self, R> {
         <DriverValue<Self, R> as Maybe<R>>::just(f)
@@ -55,6 +55,16 @@ pub enum Error {
     /// Failure in the process of performing setup or other initialization steps.
     #[error("initialization failed: {0}")]
     Initialization(Box<dyn error::Error + Send + Sync + 'static>),
+
+    /// Proofs can only be verified or extended by the application that
+    /// produced them.
+    #[error("proof was produced by a different application")]
+    ApplicationMismatch,
+
+    /// Optional functionality may not be provided by every implementation of
+    /// a trait.
+    #[error("unsupported operation: {0}")]
+    Unsupported(Box<dyn error::Error + Send + Sync + 'static>),
 }
 
 #[test]
@@ -102,4 +112,12 @@ fn test_error_display() {
         ),
         "initialization failed: registry registration failed"
     );
+    assert_eq!(
+        format!("{}", Error::ApplicationMismatch),
+        "proof was produced by a different application"
+    );
+    assert_eq!(
+        format!("{}", Error::Unsupported("header decoding".into())),
+        "unsupported operation: header decoding"
+    );
 }
@@ -0,0 +1,363 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, GenericParam, Ident, LitInt, Result, Type,
+    parse_quote, spanned::Spanned,
+};
+
+use crate::path_resolution::{RaguCorePath, RaguPcdPath, RaguPrimitivesPath};
+
+/// The gadget that a field of a header's data is allocated as.
+enum FieldType {
+    Element,
+    Boolean,
+}
+
+impl FieldType {
+    fn of(ty: &Type) -> Self {
+        match ty {
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool") => {
+                FieldType::Boolean
+            }
+            _ => FieldType::Element,
+        }
+    }
+}
+
+/// The `#[ragu(suffix = ..., field = ...)]` attribute of a header.
+struct HeaderAttributes {
+    suffix: Option<LitInt>,
+    field: Option<Type>,
+}
+
+impl HeaderAttributes {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut res = HeaderAttributes {
+            suffix: None,
+            field: None,
+        };
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("ragu")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("suffix") {
+                    res.suffix = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("field") {
+                    res.field = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `suffix` or `field`"))
+                }
+            })?;
+        }
+
+        Ok(res)
+    }
+}
+
+pub fn derive(
+    input: DeriveInput,
+    ragu_core_path: RaguCorePath,
+    ragu_primitives_path: RaguPrimitivesPath,
+    ragu_pcd_path: RaguPcdPath,
+) -> Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        vis,
+        ident: struct_ident,
+        generics,
+        data,
+    } = &input;
+
+    let HeaderAttributes { suffix, field } = HeaderAttributes::parse(attrs)?;
+    let suffix = suffix.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "Header derive requires a suffix, such as #[ragu(suffix = 0)]",
+        )
+    })?;
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if let Some(wc) = where_clause {
+        return Err(Error::new(
+            wc.span(),
+            "Header derive does not yet support where clauses",
+        ));
+    }
+
+    // The field is either given explicitly, or is the only generic parameter
+    // of the header.
+    let field: Type = match (field, generics.params.len()) {
+        (Some(field), 0) => field,
+        (None, 1) => match &generics.params[0] {
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                parse_quote!( #ident )
+            }
+            gp => {
+                return Err(Error::new(
+                    gp.span(),
+                    "Header derive expects the only generic parameter to be the field",
+                ));
+            }
+        },
+        (None, 0) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Header derive requires the field of a non-generic header, such as #[ragu(field = Fp)]",
+            ));
+        }
+        _ => {
+            return Err(Error::new(
+                generics.span(),
+                "Header derive expects either a single generic field parameter or #[ragu(field = ...)]",
+            ));
+        }
+    };
+
+    let fields: Vec<(&Ident, FieldType, Vec<&Attribute>, &syn::Visibility)> = match data {
+        Data::Struct(s) => {
+            let fields = match &s.fields {
+                Fields::Named(named) if !named.named.is_empty() => &named.named,
+                _ => {
+                    return Err(Error::new(
+                        s.struct_token.span(),
+                        "Header derive only works on structs with named fields",
+                    ));
+                }
+            };
+
+            fields
+                .iter()
+                .map(|f| {
+                    let fid = f.ident.as_ref().expect("fields contains only named fields");
+                    let docs = f
+                        .attrs
+                        .iter()
+                        .filter(|a| a.path().is_ident("doc"))
+                        .collect();
+                    (fid, FieldType::of(&f.ty), docs, &f.vis)
+                })
+                .collect()
+        }
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Header derive only works on structs",
+            ));
+        }
+    };
+
+    let gadget_ident = format_ident!("{}Gadget", struct_ident);
+    let gadget_doc = format!("The gadget that encodes a [`{struct_ident}`] header.");
+
+    let gadget_fields = fields.iter().map(|(id, ty, docs, vis)| {
+        let ty = match ty {
+            FieldType::Element => quote! { #ragu_primitives_path::Element<'dr, D> },
+            FieldType::Boolean => quote! { #ragu_primitives_path::Boolean<'dr, D> },
+        };
+        quote! {
+            #( #docs )*
+            #[ragu(gadget)]
+            #vis #id: #ty
+        }
+    });
+
+    let gadget_struct = quote! {
+        #[doc = #gadget_doc]
+        #[derive(#ragu_core_path::gadgets::Gadget, #ragu_primitives_path::io::Write)]
+        #vis struct #gadget_ident<'dr, D: #ragu_core_path::drivers::Driver<'dr>> {
+            #( #gadget_fields, )*
+        }
+    };
+
+    let encode_inits = fields.iter().map(|(id, ty, _, _)| {
+        let alloc = match ty {
+            FieldType::Element => quote! { #ragu_primitives_path::Element::alloc },
+            FieldType::Boolean => quote! { #ragu_primitives_path::Boolean::alloc },
+        };
+        quote! {
+            #id: #alloc(
+                dr,
+                #ragu_core_path::maybe::Maybe::map(
+                    #ragu_core_path::maybe::Maybe::view(&witness),
+                    |data: &Self| ::core::clone::Clone::clone(&data.#id),
+                ),
+            )?
+        }
+    });
+
+    let field_idents = fields.iter().map(|(id, _, _, _)| id);
+    let decode_inits = fields.iter().map(|(id, ty, _, _)| {
+        let init = match ty {
+            FieldType::Element => quote! { *#id },
+            FieldType::Boolean => quote! { #ragu_pcd_path::header::decode_bool(*#id)? },
+        };
+        quote! { #id: #init }
+    });
+
+    let header_impl = quote! {
+        #[automatically_derived]
+        impl #impl_generics #ragu_pcd_path::header::Header<#field> for #struct_ident #ty_generics {
+            const SUFFIX: #ragu_pcd_path::header::Suffix = #ragu_pcd_path::header::Suffix::new(#suffix);
+            type Data<'source> = Self;
+            type Output = #gadget_ident<'static, ::core::marker::PhantomData<#field>>;
+
+            fn encode<'dr, 'source: 'dr, D: #ragu_core_path::drivers::Driver<'dr, F = #field>>(
+                dr: &mut D,
+                witness: #ragu_core_path::drivers::DriverValue<D, Self::Data<'source>>,
+            ) -> #ragu_core_path::Result<<Self::Output as #ragu_core_path::gadgets::GadgetKind<#field>>::Rebind<'dr, D>> {
+                Ok(#gadget_ident {
+                    #( #encode_inits, )*
+                })
+            }
+
+            fn decode<'source>(elements: &[#field]) -> #ragu_core_path::Result<Self::Data<'source>> {
+                let [#( #field_idents, )* ..] = elements else {
+                    return Err(#ragu_core_path::Error::MalformedEncoding(
+                        "header encoding is too short".into(),
+                    ));
+                };
+
+                Ok(#struct_ident {
+                    #( #decode_inits, )*
+                })
+            }
+        }
+    };
+
+    Ok(quote! {
+        #gadget_struct
+
+        #header_impl
+    })
+}
+
+#[cfg(test)]
+fn derive_default(input: DeriveInput) -> Result<TokenStream> {
+    derive(
+        input,
+        RaguCorePath::default(),
+        RaguPrimitivesPath::default(),
+        RaguPcdPath::default(),
+    )
+}
+
+#[test]
+fn test_fail_missing_suffix() {
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        struct Counter<F: Field> {
+            count: F,
+        }
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for missing suffix"
+    );
+}
+
+#[test]
+fn test_fail_missing_field() {
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 0)]
+        struct Counter {
+            count: Fp,
+        }
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for non-generic header without a field"
+    );
+
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 0)]
+        struct Counter<F: Field, G: Field> {
+            count: F,
+        }
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for ambiguous field"
+    );
+}
+
+#[test]
+fn test_fail_unnamed_struct() {
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 0)]
+        struct Counter<F: Field>(F);
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for unnamed struct fields"
+    );
+
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 0)]
+        struct Counter<F: Field> {}
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for struct without fields"
+    );
+}
+
+#[test]
+fn test_fail_unknown_attribute() {
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 0, driver)]
+        struct Counter<F: Field> {
+            count: F,
+        }
+    };
+
+    assert!(
+        derive_default(input).is_err(),
+        "Expected error for unknown attribute"
+    );
+}
+
+#[test]
+fn test_header_derive() {
+    let input: DeriveInput = parse_quote! {
+        #[derive(Header)]
+        #[ragu(suffix = 3)]
+        pub struct Counter<F: Field> {
+            pub count: F,
+            stopped: bool,
+        }
+    };
+
+    let result = derive_default(input).unwrap().to_string();
+
+    // The output gadget allocates each field in order.
+    assert!(result.contains(
+        "pub struct CounterGadget < 'dr , D : :: ragu_core :: drivers :: Driver < 'dr >>"
+    ));
+    assert!(result.contains("pub count : :: ragu_primitives :: Element < 'dr , D >"));
+    assert!(result.contains("stopped : :: ragu_primitives :: Boolean < 'dr , D >"));
+    assert!(result.contains("count : :: ragu_primitives :: Element :: alloc"));
+    assert!(result.contains("stopped : :: ragu_primitives :: Boolean :: alloc"));
+
+    // The header is implemented for the generic field.
+    assert!(
+        result
+            .contains("impl < F : Field > :: ragu_pcd :: header :: Header < F > for Counter < F >")
+    );
+    assert!(result.contains("Suffix :: new (3)"));
+    assert!(result.contains(
+        "type Output = CounterGadget < 'static , :: core :: marker :: PhantomData < F >>"
+    ));
+    assert!(result.contains("let [count , stopped , ..] = elements"));
+}
@@ -1,5 +1,6 @@
 pub mod gadget;
 pub mod gadgetwrite;
+pub mod header;
 
 use proc_macro2::Span;
 use syn::{AngleBracketedGenericArguments, GenericArgument, Lifetime, Type, parse_quote};
@@ -79,6 +79,23 @@ pub fn derive_write(input: TokenStream) -> TokenStream {
     })
 }
 
+#[cfg(test)]
+#[allow(unused_imports)]
+use ragu_pcd::header::Header as _;
+
+// Documentation for the `Header` derive macro is in `derive@ragu_pcd::header::Header`.
+#[allow(missing_docs)]
+#[proc_macro_derive(Header, attributes(ragu))]
+pub fn derive_header(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    macro_body(|| {
+        let ragu_core_path = path_resolution::RaguCorePath::resolve()?;
+        let ragu_primitives_path = path_resolution::RaguPrimitivesPath::resolve()?;
+        let ragu_pcd_path = path_resolution::RaguPcdPath::resolve()?;
+        derive::header::derive(input, ragu_core_path, ragu_primitives_path, ragu_pcd_path)
+    })
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 use ragu_core::maybe::MaybeCast as _;
@@ -25,15 +25,17 @@ pub struct PastaParams {
 pub struct PallasGenerators {
     pub(crate) g: Vec<EpAffine>,
     pub(crate) h: EpAffine,
+    pub(crate) q: EpAffine,
 }
 
 /// Fixed generators for the Vesta curve.
 pub struct VestaGenerators {
     pub(crate) g: Vec<EqAffine>,
     pub(crate) h: EqAffine,
+    pub(crate) q: EqAffine,
 }
 
-fn params_for_curve<C: CurveExt>(n: usize) -> (Vec<C::AffineExt>, C::AffineExt) {
+fn params_for_curve<C: CurveExt>(n: usize) -> (Vec<C::AffineExt>, C::AffineExt, C::AffineExt) {
     let g_projective = {
         let hasher = C::hash_to_curve(DOMAIN_PREFIX);
         let mut g = Vec::with_capacity(n);
@@ -48,24 +50,27 @@ fn params_for_curve<C: CurveExt>(n: usize) -> (Vec<C::AffineExt>, C::AffineExt)
     Curve::batch_normalize(&g_projective[..], &mut g);
 
     let h: C::AffineExt = C::hash_to_curve(DOMAIN_PREFIX)(&[1]).into();
+    let q: C::AffineExt = C::hash_to_curve(DOMAIN_PREFIX)(&[2]).into();
 
-    (g, h)
+    (g, h, q)
 }
 
 impl PastaParams {
     /// Generate Pasta parameters at runtime via hash-to-curve.
     pub(crate) fn generate() -> Self {
-        let (ep_g, ep_h) = params_for_curve::<Ep>(1usize << DEFAULT_EP_K);
-        let (eq_g, eq_h) = params_for_curve::<Eq>(1usize << DEFAULT_EQ_K);
+        let (ep_g, ep_h, ep_q) = params_for_curve::<Ep>(1usize << DEFAULT_EP_K);
+        let (eq_g, eq_h, eq_q) = params_for_curve::<Eq>(1usize << DEFAULT_EQ_K);
 
         PastaParams {
             pallas: PallasGenerators {
                 g: ep_g,
                 h: ep_h,
+                q: ep_q,
             },
             vesta: VestaGenerators {
                 g: eq_g,
                 h: eq_h,
+                q: eq_q,
             }
         }
     }
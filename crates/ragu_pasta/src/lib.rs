@@ -36,7 +36,7 @@ mod common {
 mod poseidon_fp;
 mod poseidon_fq;
 
-use arithmetic::{Cycle, FixedGenerators};
+use arithmetic::{Cycle, FixedGenerators, OpeningGenerators};
 
 pub use common::{PallasGenerators, PastaParams, VestaGenerators};
 pub use pasta_curves::{Ep, EpAffine, Eq, EqAffine, Fp, Fq};
@@ -96,6 +96,12 @@ impl FixedGenerators<pasta_curves::EpAffine> for PallasGenerators {
     }
 }
 
+impl OpeningGenerators<pasta_curves::EpAffine> for PallasGenerators {
+    fn q(&self) -> &pasta_curves::EpAffine {
+        &self.q
+    }
+}
+
 impl FixedGenerators<pasta_curves::EqAffine> for VestaGenerators {
     fn g(&self) -> &[pasta_curves::EqAffine] {
         &self.g
@@ -106,6 +112,12 @@ impl FixedGenerators<pasta_curves::EqAffine> for VestaGenerators {
     }
 }
 
+impl OpeningGenerators<pasta_curves::EqAffine> for VestaGenerators {
+    fn q(&self) -> &pasta_curves::EqAffine {
+        &self.q
+    }
+}
+
 #[cfg(feature = "baked")]
 mod baked {
     use alloc::vec::Vec;
@@ -130,28 +142,39 @@ mod baked {
         C::from_xy(x, y).unwrap()
     }
 
-    fn get_points_for_curve<C: CurveAffine>(source: &mut &[u8], n: usize) -> (Vec<C>, C) {
+    fn get_points_for_curve<C: CurveAffine>(source: &mut &[u8], n: usize) -> (Vec<C>, C, C) {
         let mut g = Vec::with_capacity(n);
         for _ in 0..n {
             g.push(get_point(source));
         }
         let h = get_point(source);
+        let q = get_point(source);
 
-        (g, h)
+        (g, h, q)
     }
 
     lazy_static! {
         static ref PASTA_PARAMETERS: PastaParams = {
             let mut params = RAW_PARAMETERS;
 
-            let (ep_g, ep_h) = get_points_for_curve(&mut params, 1 << crate::common::DEFAULT_EP_K);
-            let (eq_g, eq_h) = get_points_for_curve(&mut params, 1 << crate::common::DEFAULT_EQ_K);
+            let (ep_g, ep_h, ep_q) =
+                get_points_for_curve(&mut params, 1 << crate::common::DEFAULT_EP_K);
+            let (eq_g, eq_h, eq_q) =
+                get_points_for_curve(&mut params, 1 << crate::common::DEFAULT_EQ_K);
 
             assert_eq!(params.len(), 0);
 
             PastaParams {
-                pallas: PallasGenerators { g: ep_g, h: ep_h },
-                vesta: VestaGenerators { g: eq_g, h: eq_h },
+                pallas: PallasGenerators {
+                    g: ep_g,
+                    h: ep_h,
+                    q: ep_q,
+                },
+                vesta: VestaGenerators {
+                    g: eq_g,
+                    h: eq_h,
+                    q: eq_q,
+                },
             }
         };
     }
@@ -165,7 +188,7 @@ mod baked {
 
     #[test]
     fn test_baked_params() {
-        use arithmetic::{Cycle, FixedGenerators};
+        use arithmetic::{Cycle, FixedGenerators, OpeningGenerators};
 
         let params = Pasta::baked();
 
@@ -196,5 +219,13 @@ mod baked {
             Pasta::host_generators(params).h(),
             Pasta::host_generators(&regenerated).h()
         );
+        assert_eq!(
+            Pasta::nested_generators(params).q(),
+            Pasta::nested_generators(&regenerated).q()
+        );
+        assert_eq!(
+            Pasta::host_generators(params).q(),
+            Pasta::host_generators(&regenerated).q()
+        );
     }
 }
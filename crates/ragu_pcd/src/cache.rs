@@ -0,0 +1,128 @@
+//! Caching the registries of an [`Application`] across restarts.
+//!
+//! [`ApplicationBuilder::finalize`] synthesizes every circuit of both of the
+//! application's registries to compute its constraint counts, and then
+//! computes the digest of each registry, which requires evaluating every
+//! circuit several times and dominates the start-up time of large
+//! applications. The bytes returned by [`Application::registry_cache`] record
+//! this data as a [`RegistryCache`] for each registry, along with the digest
+//! of the application, and can be passed to
+//! [`ApplicationBuilder::finalize_cached`] so that each circuit is only
+//! evaluated once, to check that it has not changed.
+
+use arithmetic::Cycle;
+use ragu_circuits::{polynomials::Rank, registry::RegistryCache};
+use ragu_core::Result;
+
+use alloc::vec::Vec;
+
+use crate::{
+    Application, ApplicationBuilder,
+    proof::encoding::{Reader, Writer},
+};
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
+    /// Returns the serialized [`RegistryCache`]s of this application's native
+    /// and nested registries, for use with
+    /// [`ApplicationBuilder::finalize_cached`].
+    pub fn registry_cache(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        for cache in [
+            self.native_registry.cache().map(|cache| cache.to_bytes()),
+            self.nested_registry.cache().map(|cache| cache.to_bytes()),
+        ] {
+            writer.bytes(&cache.expect("application registries are finalized"));
+        }
+        writer.field(&self.digest);
+        writer.into_bytes()
+    }
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
+    ApplicationBuilder<'params, C, R, HEADER_SIZE>
+{
+    /// Perform finalization as [`ApplicationBuilder::finalize`] does, reusing
+    /// the registry data recorded in a `cache` produced by
+    /// [`Application::registry_cache`].
+    ///
+    /// Each registry falls back to being fully finalized if any of its
+    /// circuits has changed since the cache was created (see
+    /// [`RegistryBuilder::finalize_cached`]), and both do if the cache is
+    /// malformed. The rest of the cache is trusted, and so it must come from
+    /// a trusted source (see [`RegistryCache`]); the resulting application is
+    /// then the same as the one [`ApplicationBuilder::finalize`] would
+    /// produce.
+    ///
+    /// [`RegistryBuilder::finalize_cached`]: ragu_circuits::registry::RegistryBuilder::finalize_cached
+    pub fn finalize_cached(
+        self,
+        params: &'params C::Params,
+        cache: &[u8],
+    ) -> Result<Application<'params, C, R, HEADER_SIZE>> {
+        let cache = decode::<C>(cache).ok();
+        self.finalize_with(
+            params,
+            cache
+                .as_ref()
+                .map(|(native, nested, digest)| (native, nested, *digest)),
+        )
+    }
+}
+
+/// Decodes the [`RegistryCache`]s and the digest written by
+/// [`Application::registry_cache`].
+fn decode<C: Cycle>(
+    bytes: &[u8],
+) -> Result<(
+    RegistryCache<C::CircuitField>,
+    RegistryCache<C::ScalarField>,
+    C::CircuitField,
+)> {
+    let mut reader = Reader::new(bytes);
+    let native = RegistryCache::from_bytes(reader.bytes()?)?;
+    let nested = RegistryCache::from_bytes(reader.bytes()?)?;
+    let digest = reader.field()?;
+    reader.finish()?;
+
+    Ok((native, nested, digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use ragu_circuits::polynomials::R;
+    use ragu_core::Result;
+    use ragu_pasta::Pasta;
+
+    use crate::ApplicationBuilder;
+
+    #[test]
+    fn test_finalize_cached() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(1)?
+            .finalize(pasta)?;
+        let cache = app.registry_cache();
+
+        let cached = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(1)?
+            .finalize_cached(pasta, &cache)?;
+        assert_eq!(cached.digest(), app.digest());
+        assert_eq!(cached.registry_cache(), cache);
+
+        // Changed circuits and malformed caches fall back to full finalization.
+        let changed = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(2)?
+            .finalize(pasta)?;
+        let fallback = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(2)?
+            .finalize_cached(pasta, &cache)?;
+        assert_eq!(fallback.digest(), changed.digest());
+
+        let malformed = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(1)?
+            .finalize_cached(pasta, &cache[1..])?;
+        assert_eq!(malformed.digest(), app.digest());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+//! Checking that a [`Step`] is satisfied by its witness.
+//!
+//! [`Application::fuse`] does not check the constraints of the [`Step`] it
+//! runs, and so an unsatisfying witness only surfaces as a proof that fails
+//! [`Application::verify`]. [`Application::check_step`] instead runs the step
+//! circuit through [`check_circuit`], which reports the first constraint that
+//! is not satisfied as a [`Violation`].
+//!
+//! [`Violation`]: ragu_circuits::check::Violation
+
+use arithmetic::Cycle;
+use ragu_circuits::{Circuit, check::check_circuit, polynomials::Rank};
+use ragu_core::Result;
+
+use crate::{
+    Application, Pcd,
+    step::{Step, internal::adapter::Adapter},
+};
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
+    /// Check that `witness` satisfies the circuit of a [`Step`] given the
+    /// data of the `left` and `right` [`Pcd`], without creating a proof.
+    ///
+    /// The parameters are the same as those of [`Application::fuse`], except
+    /// that the child proofs are neither consumed nor checked. Returns the
+    /// step's auxiliary data if every constraint is satisfied, and otherwise
+    /// an [`Error::InvalidWitness`] holding the [`Violation`] of the first
+    /// multiplication or linear constraint that is not, numbered as in the
+    /// [wiring](ragu_circuits::check#constraint-indices) of the step's circuit.
+    ///
+    /// [`Error::InvalidWitness`]: ragu_core::Error::InvalidWitness
+    /// [`Violation`]: ragu_circuits::check::Violation
+    pub fn check_step<'source, S: Step<C>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+        left: &Pcd<'source, C, R, S::Left>,
+        right: &Pcd<'source, C, R, S::Right>,
+    ) -> Result<S::Aux<'source>> {
+        step.index().circuit_index(self.num_application_steps)?;
+
+        let (_, aux) = check_step::<C, S, R, HEADER_SIZE>(
+            step,
+            (left.data.clone(), right.data.clone(), witness),
+        )?;
+
+        Ok(aux)
+    }
+}
+
+/// Runs the circuit of a [`Step`] through [`check_circuit`], returning the
+/// encoded left, right and output headers along with the step's auxiliary
+/// data.
+pub(crate) fn check_step<'source, C: Cycle, S: Step<C>, R: Rank, const HEADER_SIZE: usize>(
+    step: S,
+    witness: <Adapter<C, S, R, HEADER_SIZE> as Circuit<C::CircuitField>>::Witness<'source>,
+) -> Result<<Adapter<C, S, R, HEADER_SIZE> as Circuit<C::CircuitField>>::Aux<'source>> {
+    check_circuit(&Adapter::<C, S, R, HEADER_SIZE>::new(step), witness)
+}
+
+#[cfg(test)]
+mod tests {
+    use arithmetic::Coeff;
+    use ragu_circuits::polynomials::R;
+    use ragu_core::{
+        Error, Result,
+        drivers::{Driver, DriverValue},
+        gadgets::{GadgetKind, Kind},
+        maybe::Maybe,
+        routines::{Prediction, Routine},
+    };
+    use ragu_pasta::{Fp, Pasta};
+    use ragu_primitives::Element;
+
+    use crate::{
+        ApplicationBuilder,
+        header::{Header, Suffix},
+        step::{Encoded, Index, Step},
+    };
+
+    /// Enforces that the product of the first two elements is the third.
+    #[derive(Clone)]
+    struct EnforceProduct;
+
+    impl Routine<Fp> for EnforceProduct {
+        type Input = Kind![Fp; ((Element<'_, _>, Element<'_, _>), Element<'_, _>)];
+        type Output = Kind![Fp; ()];
+        type Aux<'dr> = ();
+
+        fn execute<'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            dr: &mut D,
+            ((a, b), c): <Self::Input as GadgetKind<Fp>>::Rebind<'dr, D>,
+            _: DriverValue<D, ()>,
+        ) -> Result<()> {
+            a.mul(dr, &b)?.sub(dr, &c).enforce_zero(dr)
+        }
+
+        fn predict<'dr, D: Driver<'dr, F = Fp>>(
+            &self,
+            _: &mut D,
+            _: &<Self::Input as GadgetKind<Fp>>::Rebind<'dr, D>,
+        ) -> Result<Prediction<(), DriverValue<D, ()>>> {
+            Ok(Prediction::Unknown(D::just(|| ())))
+        }
+    }
+
+    struct Cube;
+
+    impl Header<Fp> for Cube {
+        const SUFFIX: Suffix = Suffix::new(0);
+        type Data<'source> = Fp;
+        type Output = Kind![Fp; Element<'_, _>];
+
+        fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>>(
+            dr: &mut D,
+            witness: DriverValue<D, Self::Data<'source>>,
+        ) -> Result<<Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>> {
+            Element::alloc(dr, witness)
+        }
+    }
+
+    /// Claims that the witness is `(x, x^2, x^3)`, checking the square with a
+    /// multiplication constraint and the cube within a routine.
+    struct Cubing;
+
+    impl Step<Pasta> for Cubing {
+        const INDEX: Index = Index::new(0);
+        type Witness<'source> = (Fp, Fp, Fp);
+        type Aux<'source> = Fp;
+        type Left = ();
+        type Right = ();
+        type Output = Cube;
+
+        fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>, const HEADER_SIZE: usize>(
+            &self,
+            dr: &mut D,
+            witness: DriverValue<D, Self::Witness<'source>>,
+            left: DriverValue<D, ()>,
+            right: DriverValue<D, ()>,
+        ) -> Result<(
+            (
+                Encoded<'dr, D, (), HEADER_SIZE>,
+                Encoded<'dr, D, (), HEADER_SIZE>,
+                Encoded<'dr, D, Cube, HEADER_SIZE>,
+            ),
+            DriverValue<D, Fp>,
+        )> {
+            let left = Encoded::new(dr, left)?;
+            let right = Encoded::new(dr, right)?;
+
+            let (x, square, cube) = witness.cast();
+            let (a, b, c) = dr.mul(|| {
+                let x = Coeff::Arbitrary(*x.snag());
+                Ok((x, x, Coeff::Arbitrary(*square.snag())))
+            })?;
+            dr.enforce_equal(&a, &b)?;
+            let x = Element::promote(a, x);
+            let square = Element::promote(c, square);
+            let cube = Element::alloc(dr, cube)?;
+            dr.namespace(
+                || "cube",
+                |dr| dr.routine(EnforceProduct, ((x, square), cube.clone())),
+            )?;
+
+            let aux = D::just(|| *cube.value().take());
+
+            Ok(((left, right, Encoded::from_gadget(cube)), aux))
+        }
+    }
+
+    #[test]
+    fn test_check_step() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register(Cubing)?
+            .finalize(pasta)?;
+
+        let check = |x: u64, square: u64, cube: u64| {
+            app.check_step(
+                Cubing,
+                (Fp::from(x), Fp::from(square), Fp::from(cube)),
+                &app.trivial_pcd(),
+                &app.trivial_pcd(),
+            )
+        };
+        let unsatisfied = |result: Result<Fp>| match result {
+            Err(Error::InvalidWitness(error)) => error.to_string(),
+            _ => panic!("an unsatisfying witness should be rejected"),
+        };
+
+        assert_eq!(check(3, 9, 27)?, Fp::from(27));
+        assert_eq!(
+            unsatisfied(check(3, 10, 30)),
+            "multiplication constraint 1 is not satisfied"
+        );
+        let error = unsatisfied(check(3, 9, 28));
+        assert!(error.starts_with("linear constraint 4 is not satisfied at cube in routine "));
+        assert!(error.ends_with("EnforceProduct"));
+
+        Ok(())
+    }
+}
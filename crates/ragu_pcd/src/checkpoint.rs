@@ -0,0 +1,371 @@
+//! Suspending and resuming [`Application::fuse`] between its phases.
+//!
+//! Fusing proofs proceeds in a fixed sequence of [`Phase`]s, each of which
+//! commits to some component of the resulting proof before the next
+//! Fiat-Shamir challenge is derived from the transcript. A [`Checkpoint`]
+//! holds the output of every phase completed so far, so that a long-running
+//! fuse operation can be advanced one phase at a time and persisted between
+//! phases:
+//!
+//! * [`Application::begin_fuse`] runs the [`Step`] and returns the first
+//!   checkpoint,
+//! * [`Application::advance_fuse`] completes the next [`Phase`],
+//! * [`Checkpoint::to_bytes`] and [`Checkpoint::from_bytes`] persist a
+//!   checkpoint, which may be resumed in another process by the same
+//!   [`Application`], and
+//! * [`Application::finish_fuse`] completes any remaining phases and returns
+//!   the resulting [`Proof`].
+//!
+//! [`Application`]: crate::Application
+//! [`Application::fuse`]: crate::Application::fuse
+//! [`Application::begin_fuse`]: crate::Application::begin_fuse
+//! [`Application::advance_fuse`]: crate::Application::advance_fuse
+//! [`Application::finish_fuse`]: crate::Application::finish_fuse
+//! [`Step`]: crate::step::Step
+//!
+//! The transcript is not stored in the checkpoint; it is recomputed from the
+//! committed components when a checkpoint is resumed. A checkpoint contains
+//! the child proofs and witness data of the step being proven, and so should
+//! be stored as carefully as the witness itself.
+
+use arithmetic::Cycle;
+use ragu_circuits::polynomials::{Rank, structured};
+use ragu_core::Result;
+use ragu_primitives::vec::FixedVec;
+
+use alloc::vec::Vec;
+
+use crate::{
+    Proof,
+    circuits::native::stages::{error_m, error_n},
+    components::fold_revdot::{self, NativeParameters},
+    proof::{
+        self,
+        encoding::{Encode, Reader, Writer, malformed},
+    },
+};
+
+type NativeN = <NativeParameters as fold_revdot::Parameters>::N;
+
+/// The phases of [`Application::fuse`](crate::Application::fuse) that follow
+/// the evaluation of the [`Step`](crate::step::Step), in the order in which
+/// they are run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Commits to the preamble, which contains the headers and public inputs
+    /// of the child proofs.
+    Preamble,
+    /// Commits to the $m(w, x_i, Y)$ restrictions for the child proofs.
+    SPrime,
+    /// Commits to the error terms of both revdot folding reductions.
+    Errors,
+    /// Commits to the folded revdot claim polynomials $A$ and $B$.
+    AB,
+    /// Commits to the claimed evaluations of the polynomial queries.
+    Query,
+    /// Commits to the quotient polynomial $f(X)$.
+    F,
+    /// Commits to the evaluations of every queried polynomial at $u$.
+    Eval,
+    /// Computes $p(X)$ and the internal circuits, completing the proof. This
+    /// phase is only run by
+    /// [`Application::finish_fuse`](crate::Application::finish_fuse).
+    Finish,
+}
+
+/// A fuse operation that has been suspended between two [`Phase`]s.
+///
+/// See the [module documentation](self) for details.
+pub struct Checkpoint<C: Cycle, R: Rank> {
+    pub(crate) left: Proof<C, R>,
+    pub(crate) right: Proof<C, R>,
+    pub(crate) application: proof::Application<C, R>,
+    pub(crate) preamble: Option<proof::Preamble<C, R>>,
+    pub(crate) s_prime: Option<proof::SPrime<C, R>>,
+    pub(crate) errors: Option<Errors<C, R>>,
+    pub(crate) ab: Option<proof::AB<C, R>>,
+    pub(crate) query: Option<proof::Query<C, R>>,
+    pub(crate) f: Option<proof::F<C, R>>,
+    pub(crate) eval: Option<proof::Eval<C, R>>,
+}
+
+/// The output of [`Phase::Errors`].
+pub(crate) struct Errors<C: Cycle, R: Rank> {
+    pub(crate) error_m: proof::ErrorM<C, R>,
+    pub(crate) error_m_witness: error_m::Witness<C, NativeParameters>,
+    pub(crate) error_n: proof::ErrorN<C, R>,
+    pub(crate) error_n_witness: error_n::Witness<C, NativeParameters>,
+    /// The claim polynomials folded by the first reduction, which are only
+    /// kept until [`Phase::AB`] folds them again.
+    pub(crate) folded: Option<Folded<C, R>>,
+}
+
+pub(crate) type Folded<C, R> = (
+    FixedVec<structured::Polynomial<<C as Cycle>::CircuitField, R>, NativeN>,
+    FixedVec<structured::Polynomial<<C as Cycle>::CircuitField, R>, NativeN>,
+);
+
+impl<C: Cycle, R: Rank> Checkpoint<C, R> {
+    /// Returns the next phase to be run.
+    pub fn phase(&self) -> Phase {
+        if self.preamble.is_none() {
+            Phase::Preamble
+        } else if self.s_prime.is_none() {
+            Phase::SPrime
+        } else if self.errors.is_none() {
+            Phase::Errors
+        } else if self.ab.is_none() {
+            Phase::AB
+        } else if self.query.is_none() {
+            Phase::Query
+        } else if self.f.is_none() {
+            Phase::F
+        } else if self.eval.is_none() {
+            Phase::Eval
+        } else {
+            Phase::Finish
+        }
+    }
+
+    /// Serializes this checkpoint, so that it can be resumed with
+    /// [`Checkpoint::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        self.encode(&mut writer);
+        writer.into_bytes()
+    }
+
+    /// Deserializes a checkpoint from the encoding produced by
+    /// [`Checkpoint::to_bytes`].
+    ///
+    /// Returns [`Error::MalformedEncoding`](ragu_core::Error::MalformedEncoding)
+    /// if the input is not exactly the encoding of some checkpoint for this
+    /// [`Rank`] with headers of `HEADER_SIZE` elements. Whether the checkpoint belongs to an
+    /// [`Application`](crate::Application) is only checked once it is resumed.
+    pub fn from_bytes<const HEADER_SIZE: usize>(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::with_header_size::<HEADER_SIZE>(bytes);
+        let checkpoint = Self::decode(&mut reader)?;
+        reader.finish()?;
+
+        Ok(checkpoint)
+    }
+}
+
+/// Writes an optional component, preceded by a byte indicating whether it is
+/// present.
+fn write_optional<T>(writer: &mut Writer, value: &Option<T>, encode: impl FnOnce(&T, &mut Writer)) {
+    match value {
+        Some(value) => {
+            writer.byte(1);
+            encode(value, writer);
+        }
+        None => writer.byte(0),
+    }
+}
+
+fn read_optional<T>(
+    reader: &mut Reader<'_>,
+    decode: impl FnOnce(&mut Reader<'_>) -> Result<T>,
+) -> Result<Option<T>> {
+    match reader.byte()? {
+        0 => Ok(None),
+        1 => decode(reader).map(Some),
+        _ => Err(malformed("invalid presence flag")),
+    }
+}
+
+impl<C: Cycle, R: Rank> Encode for Checkpoint<C, R> {
+    fn encode(&self, writer: &mut Writer) {
+        self.left.encode(writer);
+        self.right.encode(writer);
+        self.application.encode(writer);
+        write_optional(writer, &self.preamble, Encode::encode);
+        write_optional(writer, &self.s_prime, Encode::encode);
+        write_optional(writer, &self.errors, Encode::encode);
+        write_optional(writer, &self.ab, Encode::encode);
+        write_optional(writer, &self.query, Encode::encode);
+        write_optional(writer, &self.f, Encode::encode);
+        write_optional(writer, &self.eval, Encode::encode);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        let checkpoint = Checkpoint {
+            left: Encode::decode(reader)?,
+            right: Encode::decode(reader)?,
+            application: Encode::decode(reader)?,
+            preamble: read_optional(reader, Encode::decode)?,
+            s_prime: read_optional(reader, Encode::decode)?,
+            errors: read_optional(reader, Encode::decode)?,
+            ab: read_optional(reader, Encode::decode)?,
+            query: read_optional(reader, Encode::decode)?,
+            f: read_optional(reader, Encode::decode)?,
+            eval: read_optional(reader, Encode::decode)?,
+        };
+
+        // Phases are completed in order, so the completed components must be
+        // a prefix of the phases, and the folded polynomials are present
+        // exactly until the AB phase is complete.
+        let completed = [
+            checkpoint.preamble.is_some(),
+            checkpoint.s_prime.is_some(),
+            checkpoint.errors.is_some(),
+            checkpoint.ab.is_some(),
+            checkpoint.query.is_some(),
+            checkpoint.f.is_some(),
+            checkpoint.eval.is_some(),
+        ];
+        if completed.windows(2).any(|pair| pair[1] && !pair[0]) {
+            return Err(malformed("checkpoint phases are not completed in order"));
+        }
+        if let Some(errors) = &checkpoint.errors
+            && errors.folded.is_some() == checkpoint.ab.is_some()
+        {
+            return Err(malformed("checkpoint has unexpected folded polynomials"));
+        }
+
+        Ok(checkpoint)
+    }
+}
+
+impl<C: Cycle, R: Rank> Encode for Errors<C, R> {
+    fn encode(&self, writer: &mut Writer) {
+        self.error_m.encode(writer);
+        for terms in self.error_m_witness.error_terms.iter() {
+            terms.iter().for_each(|term| writer.field(term));
+        }
+
+        self.error_n.encode(writer);
+        let witness = &self.error_n_witness;
+        witness
+            .error_terms
+            .iter()
+            .for_each(|term| writer.field(term));
+        witness
+            .collapsed
+            .iter()
+            .for_each(|value| writer.field(value));
+        for ky in [&witness.ky.left, &witness.ky.right] {
+            writer.field(&ky.application);
+            writer.field(&ky.unified);
+            writer.field(&ky.unified_bridge);
+        }
+        witness
+            .sponge_state_elements
+            .iter()
+            .for_each(|value| writer.field(value));
+
+        write_optional(writer, &self.folded, |(a, b), writer| {
+            a.iter()
+                .chain(b.iter())
+                .for_each(|poly| writer.structured(poly));
+        });
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        let error_m = Encode::decode(reader)?;
+        let error_m_witness = error_m::Witness {
+            error_terms: FixedVec::try_from_fn(|_| FixedVec::try_from_fn(|_| reader.field()))?,
+        };
+
+        let error_n = Encode::decode(reader)?;
+        let error_terms = FixedVec::try_from_fn(|_| reader.field())?;
+        let collapsed = FixedVec::try_from_fn(|_| reader.field())?;
+        let mut ky = || -> Result<_> {
+            Ok(error_n::ChildKyValues {
+                application: reader.field()?,
+                unified: reader.field()?,
+                unified_bridge: reader.field()?,
+            })
+        };
+        let ky = error_n::KyValues {
+            left: ky()?,
+            right: ky()?,
+        };
+        let error_n_witness = error_n::Witness {
+            error_terms,
+            collapsed,
+            ky,
+            sponge_state_elements: FixedVec::try_from_fn(|_| reader.field())?,
+        };
+
+        let folded = read_optional(reader, |reader| {
+            Ok((
+                FixedVec::try_from_fn(|_| reader.structured())?,
+                FixedVec::try_from_fn(|_| reader.structured())?,
+            ))
+        })?;
+
+        Ok(Errors {
+            error_m,
+            error_m_witness,
+            error_n,
+            error_n_witness,
+            folded,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ragu_circuits::polynomials::R;
+    use ragu_core::{Error, Result};
+    use ragu_pasta::Pasta;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Checkpoint, Phase};
+    use crate::{ApplicationBuilder, step::internal::trivial::Trivial};
+
+    #[test]
+    fn test_resume_between_phases() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let (mut checkpoint, ()) = app.begin_fuse(
+            &mut rng,
+            Trivial::new(),
+            (),
+            app.trivial_pcd(),
+            app.trivial_pcd(),
+        )?;
+        assert_eq!(checkpoint.phase(), Phase::Preamble);
+
+        // Persist and resume the checkpoint after every phase.
+        let mut phases = vec![];
+        while checkpoint.phase() != Phase::Finish {
+            phases.push(checkpoint.phase());
+            app.advance_fuse(&mut rng, &mut checkpoint)?;
+
+            let bytes = checkpoint.to_bytes();
+            checkpoint = Checkpoint::from_bytes::<4>(&bytes)?;
+            assert_eq!(checkpoint.to_bytes(), bytes);
+        }
+        assert_eq!(
+            phases,
+            [
+                Phase::Preamble,
+                Phase::SPrime,
+                Phase::Errors,
+                Phase::AB,
+                Phase::Query,
+                Phase::F,
+                Phase::Eval,
+            ]
+        );
+
+        // Advancing a completed checkpoint does nothing.
+        let bytes = checkpoint.to_bytes();
+        app.advance_fuse(&mut rng, &mut checkpoint)?;
+        assert_eq!(checkpoint.to_bytes(), bytes);
+
+        assert!(matches!(
+            Checkpoint::<Pasta, R<13>>::from_bytes::<4>(&bytes[..bytes.len() - 1]),
+            Err(Error::MalformedEncoding(_))
+        ));
+
+        let pcd = app.finish_fuse(&mut rng, checkpoint)?.carry::<()>(());
+        assert!(app.verify(&pcd, &mut rng)?);
+
+        Ok(())
+    }
+}
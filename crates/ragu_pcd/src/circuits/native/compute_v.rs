@@ -157,105 +157,110 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> MultiStageCircuit<C::CircuitFi
         let (eval, builder) = builder.add_stage::<native_eval::Stage<C, R, HEADER_SIZE>>()?;
         let dr = builder.finish();
 
-        // Preamble is enforced because it contains child proof data that must
-        // be validated (Points, Booleans, etc.).
-        let preamble = preamble.enforced(dr, witness.view().map(|w| w.preamble_witness))?;
-
-        // TODO: Query and eval stages are unenforced because they currently
-        // contain only Elements, which require no enforcement logic. Re-evaluate
-        // if additional gadget types are added in the future.
-        let query = query.unenforced(dr, witness.view().map(|w| w.query_witness))?;
-        let eval = eval.unenforced(dr, witness.view().map(|w| w.eval_witness))?;
-
-        let unified_instance = &witness.view().map(|w| w.unified_instance);
-        let mut unified_output = OutputBuilder::new();
-
-        // Retrieve Fiat-Shamir challenges from the unified instance.
-        let w = unified_output.w.get(dr, unified_instance)?;
-        let y = unified_output.y.get(dr, unified_instance)?;
-        let z = unified_output.z.get(dr, unified_instance)?;
-        let x = unified_output.x.get(dr, unified_instance)?;
-
-        // Compute t(xz), the vanishing polynomial evaluated at xz.
-        let txz = dr.routine(Evaluate::<R>::new(), (x.clone(), z.clone()))?;
-
-        // Verify v: compute the expected value and constrain it to match the
-        // unified instance. This binds the prover's polynomial commitments to
-        // the claimed evaluation.
-        {
-            // Step 1: Compute a(x) and b(x) via two-layer revdot folding.
-            // These aggregate all evaluation claims into a single pair.
-            let (computed_ax, computed_bx) = {
-                let mu = unified_output.mu.get(dr, unified_instance)?;
-                let nu = unified_output.nu.get(dr, unified_instance)?;
-                let mu_prime = unified_output.mu_prime.get(dr, unified_instance)?;
-                let nu_prime = unified_output.nu_prime.get(dr, unified_instance)?;
-                let mu_inv = mu.invert(dr)?;
-                let mu_prime_inv = mu_prime.invert(dr)?;
-                let munu = mu.mul(dr, &nu)?;
-                let mu_prime_nu_prime = mu_prime.mul(dr, &nu_prime)?;
-
-                compute_axbx::<_, NativeParameters>(
-                    dr,
-                    &query,
-                    &z,
-                    &txz,
-                    &mu_inv,
-                    &mu_prime_inv,
-                    &munu,
-                    &mu_prime_nu_prime,
-                )?
-            };
-
-            // Step 2: Compute f(u) by accumulating quotient terms.
-            // f(u) = sum_i alpha^{n-1-i} * (p_i(u) - v_i) / (u - x_i)
-            // (Horner accumulation: first query receives highest alpha power)
-            let fu = {
-                let alpha = unified_output.alpha.get(dr, unified_instance)?;
-                let u = unified_output.u.get(dr, unified_instance)?;
-                let denominators = Denominators::new(
-                    dr,
-                    &u,
-                    &w,
-                    &x,
-                    &y,
-                    &z,
-                    &preamble,
-                    self.num_application_steps,
-                )?;
-                let mut horner = Horner::new(&alpha);
-                for (pu, v, denominator) in poly_queries(
-                    &eval,
-                    &query,
-                    &preamble,
-                    &denominators,
-                    &computed_ax,
-                    &computed_bx,
-                ) {
-                    pu.sub(dr, v).mul(dr, denominator)?.write(dr, &mut horner)?;
+        dr.namespace(
+            || "compute_v",
+            |dr| {
+                // Preamble is enforced because it contains child proof data that must
+                // be validated (Points, Booleans, etc.).
+                let preamble = preamble.enforced(dr, witness.view().map(|w| w.preamble_witness))?;
+
+                // TODO: Query and eval stages are unenforced because they currently
+                // contain only Elements, which require no enforcement logic. Re-evaluate
+                // if additional gadget types are added in the future.
+                let query = query.unenforced(dr, witness.view().map(|w| w.query_witness))?;
+                let eval = eval.unenforced(dr, witness.view().map(|w| w.eval_witness))?;
+
+                let unified_instance = &witness.view().map(|w| w.unified_instance);
+                let mut unified_output = OutputBuilder::new();
+
+                // Retrieve Fiat-Shamir challenges from the unified instance.
+                let w = unified_output.w.get(dr, unified_instance)?;
+                let y = unified_output.y.get(dr, unified_instance)?;
+                let z = unified_output.z.get(dr, unified_instance)?;
+                let x = unified_output.x.get(dr, unified_instance)?;
+
+                // Compute t(xz), the vanishing polynomial evaluated at xz.
+                let txz = dr.routine(Evaluate::<R>::new(), (x.clone(), z.clone()))?;
+
+                // Verify v: compute the expected value and constrain it to match the
+                // unified instance. This binds the prover's polynomial commitments to
+                // the claimed evaluation.
+                {
+                    // Step 1: Compute a(x) and b(x) via two-layer revdot folding.
+                    // These aggregate all evaluation claims into a single pair.
+                    let (computed_ax, computed_bx) = {
+                        let mu = unified_output.mu.get(dr, unified_instance)?;
+                        let nu = unified_output.nu.get(dr, unified_instance)?;
+                        let mu_prime = unified_output.mu_prime.get(dr, unified_instance)?;
+                        let nu_prime = unified_output.nu_prime.get(dr, unified_instance)?;
+                        let mu_inv = mu.invert(dr)?;
+                        let mu_prime_inv = mu_prime.invert(dr)?;
+                        let munu = mu.mul(dr, &nu)?;
+                        let mu_prime_nu_prime = mu_prime.mul(dr, &nu_prime)?;
+
+                        compute_axbx::<_, NativeParameters>(
+                            dr,
+                            &query,
+                            &z,
+                            &txz,
+                            &mu_inv,
+                            &mu_prime_inv,
+                            &munu,
+                            &mu_prime_nu_prime,
+                        )?
+                    };
+
+                    // Step 2: Compute f(u) by accumulating quotient terms.
+                    // f(u) = sum_i alpha^{n-1-i} * (p_i(u) - v_i) / (u - x_i)
+                    // (Horner accumulation: first query receives highest alpha power)
+                    let fu = {
+                        let alpha = unified_output.alpha.get(dr, unified_instance)?;
+                        let u = unified_output.u.get(dr, unified_instance)?;
+                        let denominators = Denominators::new(
+                            dr,
+                            &u,
+                            &w,
+                            &x,
+                            &y,
+                            &z,
+                            &preamble,
+                            self.num_application_steps,
+                        )?;
+                        let mut horner = Horner::new(&alpha);
+                        for (pu, v, denominator) in poly_queries(
+                            &eval,
+                            &query,
+                            &preamble,
+                            &denominators,
+                            &computed_ax,
+                            &computed_bx,
+                        ) {
+                            pu.sub(dr, v).mul(dr, denominator)?.write(dr, &mut horner)?;
+                        }
+                        horner.finish(dr)
+                    };
+
+                    // Step 3: Compute v = f(u) + beta * eval via Horner accumulation.
+                    // This combines f(u) with the evaluation component polynomials.
+                    // First extract endoscalar from pre_beta and compute effective beta.
+                    let computed_v = {
+                        let pre_beta = unified_output.pre_beta.get(dr, unified_instance)?;
+                        let beta_endo = Endoscalar::extract(dr, pre_beta)?;
+                        let effective_beta = beta_endo.field_scale(dr)?;
+                        let mut horner = Horner::new(&effective_beta);
+                        fu.write(dr, &mut horner)?;
+                        eval.write(dr, &mut horner)?;
+                        horner.finish(dr)
+                    };
+
+                    // Constrain v: the computed value must equal the claimed v in the
+                    // unified instance. This is enforced when finish() serializes the output.
+                    unified_output.v.set(computed_v);
                 }
-                horner.finish(dr)
-            };
-
-            // Step 3: Compute v = f(u) + beta * eval via Horner accumulation.
-            // This combines f(u) with the evaluation component polynomials.
-            // First extract endoscalar from pre_beta and compute effective beta.
-            let computed_v = {
-                let pre_beta = unified_output.pre_beta.get(dr, unified_instance)?;
-                let beta_endo = Endoscalar::extract(dr, pre_beta)?;
-                let effective_beta = beta_endo.field_scale(dr)?;
-                let mut horner = Horner::new(&effective_beta);
-                fu.write(dr, &mut horner)?;
-                eval.write(dr, &mut horner)?;
-                horner.finish(dr)
-            };
-
-            // Constrain v: the computed value must equal the claimed v in the
-            // unified instance. This is enforced when finish() serializes the output.
-            unified_output.v.set(computed_v);
-        }
 
-        Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+                Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+            },
+        )
     }
 }
 
@@ -148,40 +148,46 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize, FP: fold_revdot::Parameters>
         let (error_n, builder) = builder.add_stage::<error_n::Stage<C, R, HEADER_SIZE, FP>>()?;
         let dr = builder.finish();
 
-        let preamble = preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
-        let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
-
-        let unified_instance = &witness.view().map(|w| w.unified_instance);
-        let mut unified_output = OutputBuilder::new();
-
-        // Get layer 2 folding challenges. These are distinct from the layer 1
-        // challenges (mu, nu) used in partial_collapse.
-        let mu_prime = unified_output.mu_prime.get(dr, unified_instance)?;
-        let nu_prime = unified_output.nu_prime.get(dr, unified_instance)?;
-
-        // Compute the final folded revdot claim c via layer 2 reduction.
-        // The collapsed values from layer 1 (verified by partial_collapse) serve
-        // as the k(y) inputs for this final fold.
-        {
-            let fold_products = fold_revdot::FoldProducts::new(dr, &mu_prime, &nu_prime)?;
-            let computed_c = fold_products.fold_products_n::<FP>(
-                dr,
-                &error_n.error_terms,
-                &error_n.collapsed,
-            )?;
-
-            // Retrieve the witnessed c from the unified instance.
-            let witnessed_c = unified_output.c.get(dr, unified_instance)?;
-
-            // Enforce witnessed_c == computed_c, but only when NOT in base case.
-            // In base case (both children are trivial proofs), the prover may
-            // witness any c value to seed the recursion.
-            preamble
-                .is_base_case(dr)?
-                .not(dr)
-                .conditional_enforce_equal(dr, &witnessed_c, &computed_c)?;
-        }
-
-        Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+        dr.namespace(
+            || "full_collapse",
+            |dr| {
+                let preamble =
+                    preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
+                let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
+
+                let unified_instance = &witness.view().map(|w| w.unified_instance);
+                let mut unified_output = OutputBuilder::new();
+
+                // Get layer 2 folding challenges. These are distinct from the layer 1
+                // challenges (mu, nu) used in partial_collapse.
+                let mu_prime = unified_output.mu_prime.get(dr, unified_instance)?;
+                let nu_prime = unified_output.nu_prime.get(dr, unified_instance)?;
+
+                // Compute the final folded revdot claim c via layer 2 reduction.
+                // The collapsed values from layer 1 (verified by partial_collapse) serve
+                // as the k(y) inputs for this final fold.
+                {
+                    let fold_products = fold_revdot::FoldProducts::new(dr, &mu_prime, &nu_prime)?;
+                    let computed_c = fold_products.fold_products_n::<FP>(
+                        dr,
+                        &error_n.error_terms,
+                        &error_n.collapsed,
+                    )?;
+
+                    // Retrieve the witnessed c from the unified instance.
+                    let witnessed_c = unified_output.c.get(dr, unified_instance)?;
+
+                    // Enforce witnessed_c == computed_c, but only when NOT in base case.
+                    // In base case (both children are trivial proofs), the prover may
+                    // witness any c value to seed the recursion.
+                    preamble
+                        .is_base_case(dr)?
+                        .not(dr)
+                        .conditional_enforce_equal(dr, &witnessed_c, &computed_c)?;
+                }
+
+                Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+            },
+        )
     }
 }
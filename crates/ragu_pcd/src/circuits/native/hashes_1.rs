@@ -216,87 +216,93 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize, FP: fold_revdot::Parameters>
             builder.add_stage::<native_error_n::Stage<C, R, HEADER_SIZE, FP>>()?;
         let dr = builder.finish();
 
-        let preamble = preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
-        let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
+        dr.namespace(
+            || "hashes_1",
+            |dr| {
+                let preamble =
+                    preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
+                let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
 
-        // Verify circuit IDs are valid roots of unity in the registry domain.
-        root_of_unity::enforce(dr, preamble.left.circuit_id.clone(), self.log2_circuits)?;
-        root_of_unity::enforce(dr, preamble.right.circuit_id.clone(), self.log2_circuits)?;
+                // Verify circuit IDs are valid roots of unity in the registry domain.
+                root_of_unity::enforce(dr, preamble.left.circuit_id.clone(), self.log2_circuits)?;
+                root_of_unity::enforce(dr, preamble.right.circuit_id.clone(), self.log2_circuits)?;
 
-        let unified_instance = &witness.view().map(|w| w.unified_instance);
-        let mut unified_output = OutputBuilder::new();
+                let unified_instance = &witness.view().map(|w| w.unified_instance);
+                let mut unified_output = OutputBuilder::new();
 
-        // Create a single long-lived sponge for all challenge derivations
-        let mut sponge = Sponge::new(dr, C::circuit_poseidon(self.params));
+                // Create a single long-lived sponge for all challenge derivations
+                let mut sponge = Sponge::new(dr, C::circuit_poseidon(self.params));
 
-        // Derive w by absorbing nested_preamble_commitment and squeezing
-        let w = {
-            let nested_preamble_commitment = unified_output
-                .nested_preamble_commitment
-                .get(dr, unified_instance)?;
-            nested_preamble_commitment.write(dr, &mut sponge)?;
-            sponge.squeeze(dr)?
-        };
-        unified_output.w.set(w.clone());
+                // Derive w by absorbing nested_preamble_commitment and squeezing
+                let w = {
+                    let nested_preamble_commitment = unified_output
+                        .nested_preamble_commitment
+                        .get(dr, unified_instance)?;
+                    nested_preamble_commitment.write(dr, &mut sponge)?;
+                    sponge.squeeze(dr)?
+                };
+                unified_output.w.set(w.clone());
 
-        // Derive (y, z) by absorbing nested_s_prime_commitment and squeezing twice
-        let (y, z) = {
-            let nested_s_prime_commitment = unified_output
-                .nested_s_prime_commitment
-                .get(dr, unified_instance)?;
-            nested_s_prime_commitment.write(dr, &mut sponge)?;
-            let y = sponge.squeeze(dr)?;
-            let z = sponge.squeeze(dr)?;
-            (y, z)
-        };
-        unified_output.y.set(y.clone());
-        unified_output.z.set(z);
+                // Derive (y, z) by absorbing nested_s_prime_commitment and squeezing twice
+                let (y, z) = {
+                    let nested_s_prime_commitment = unified_output
+                        .nested_s_prime_commitment
+                        .get(dr, unified_instance)?;
+                    nested_s_prime_commitment.write(dr, &mut sponge)?;
+                    let y = sponge.squeeze(dr)?;
+                    let z = sponge.squeeze(dr)?;
+                    (y, z)
+                };
+                unified_output.y.set(y.clone());
+                unified_output.z.set(z);
 
-        // Compute k(y) values from preamble and enforce equality with staged
-        // values.
-        {
-            let left_application_ky = preamble.left.application_ky(dr, &y)?;
-            let right_application_ky = preamble.right.application_ky(dr, &y)?;
+                // Compute k(y) values from preamble and enforce equality with staged
+                // values.
+                {
+                    let left_application_ky = preamble.left.application_ky(dr, &y)?;
+                    let right_application_ky = preamble.right.application_ky(dr, &y)?;
 
-            left_application_ky.enforce_equal(dr, &error_n.left.application)?;
-            right_application_ky.enforce_equal(dr, &error_n.right.application)?;
+                    left_application_ky.enforce_equal(dr, &error_n.left.application)?;
+                    right_application_ky.enforce_equal(dr, &error_n.right.application)?;
 
-            let (left_unified_ky, left_unified_bridge_ky) =
-                preamble.left.unified_ky_values(dr, &y)?;
-            let (right_unified_ky, right_unified_bridge_ky) =
-                preamble.right.unified_ky_values(dr, &y)?;
+                    let (left_unified_ky, left_unified_bridge_ky) =
+                        preamble.left.unified_ky_values(dr, &y)?;
+                    let (right_unified_ky, right_unified_bridge_ky) =
+                        preamble.right.unified_ky_values(dr, &y)?;
 
-            left_unified_ky.enforce_equal(dr, &error_n.left.unified)?;
-            right_unified_ky.enforce_equal(dr, &error_n.right.unified)?;
-            left_unified_bridge_ky.enforce_equal(dr, &error_n.left.unified_bridge)?;
-            right_unified_bridge_ky.enforce_equal(dr, &error_n.right.unified_bridge)?;
-        }
+                    left_unified_ky.enforce_equal(dr, &error_n.left.unified)?;
+                    right_unified_ky.enforce_equal(dr, &error_n.right.unified)?;
+                    left_unified_bridge_ky.enforce_equal(dr, &error_n.left.unified_bridge)?;
+                    right_unified_bridge_ky.enforce_equal(dr, &error_n.right.unified_bridge)?;
+                }
 
-        // Absorb nested_error_m_commitment and verify saved sponge state
-        {
-            let nested_error_m_commitment = unified_output
-                .nested_error_m_commitment
-                .get(dr, unified_instance)?;
-            nested_error_m_commitment.write(dr, &mut sponge)?;
+                // Absorb nested_error_m_commitment and verify saved sponge state
+                {
+                    let nested_error_m_commitment = unified_output
+                        .nested_error_m_commitment
+                        .get(dr, unified_instance)?;
+                    nested_error_m_commitment.write(dr, &mut sponge)?;
 
-            // save_state() applies a permutation (since there's pending absorbed data)
-            // and returns the raw state, ready for squeeze-mode resumption in hashes_2.
-            sponge
-                .save_state(dr)
-                .expect("save_state should succeed after absorbing")
-                .enforce_equal(dr, &error_n.sponge_state)?;
-        }
+                    // save_state() applies a permutation (since there's pending absorbed data)
+                    // and returns the raw state, ready for squeeze-mode resumption in hashes_2.
+                    sponge
+                        .save_state(dr)
+                        .expect("save_state should succeed after absorbing")
+                        .enforce_equal(dr, &error_n.sponge_state)?;
+                }
 
-        // Output headers from preamble + unified instance. Verification with
-        // `unified_bridge_ky` ensures preamble headers match ApplicationProof
-        // headers.
-        let output = Output {
-            left_header: preamble.left.output_header,
-            right_header: preamble.right.output_header,
-            unified: unified_output.finish_no_suffix(dr, unified_instance)?,
-        };
+                // Output headers from preamble + unified instance. Verification with
+                // `unified_bridge_ky` ensures preamble headers match ApplicationProof
+                // headers.
+                let output = Output {
+                    left_header: preamble.left.output_header,
+                    right_header: preamble.right.output_header,
+                    unified: unified_output.finish_no_suffix(dr, unified_instance)?,
+                };
 
-        let zero = Element::zero(dr);
-        Ok((WithSuffix::new(output, zero), D::just(|| ())))
+                let zero = Element::zero(dr);
+                Ok((WithSuffix::new(output, zero), D::just(|| ())))
+            },
+        )
     }
 }
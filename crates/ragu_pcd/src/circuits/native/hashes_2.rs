@@ -161,74 +161,82 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize, FP: fold_revdot::Parameters>
             builder.add_stage::<native_error_n::Stage<C, R, HEADER_SIZE, FP>>()?;
         let dr = builder.finish();
 
-        let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
-
-        let unified_instance = &witness.view().map(|w| w.unified_instance);
-        let mut unified_output = OutputBuilder::new();
-
-        // Resume sponge from saved state (error_m already absorbed in hashes_1)
-        // and squeeze mu (first challenge from error_m absorption)
-        let (mu, mut sponge) =
-            Sponge::resume_and_squeeze(dr, error_n.sponge_state, C::circuit_poseidon(self.params))?;
-        unified_output.mu.set(mu);
-
-        // Squeeze nu (second challenge from error_m absorption)
-        let nu = sponge.squeeze(dr)?;
-        unified_output.nu.set(nu);
-
-        // Derive (mu_prime, nu_prime) by absorbing nested_error_n_commitment
-        let (mu_prime, nu_prime) = {
-            let nested_error_n_commitment = unified_output
-                .nested_error_n_commitment
-                .get(dr, unified_instance)?;
-            nested_error_n_commitment.write(dr, &mut sponge)?;
-            let mu_prime = sponge.squeeze(dr)?;
-            let nu_prime = sponge.squeeze(dr)?;
-            (mu_prime, nu_prime)
-        };
-        unified_output.mu_prime.set(mu_prime);
-        unified_output.nu_prime.set(nu_prime);
-
-        // Derive x by absorbing nested_ab_commitment and squeezing
-        let x = {
-            let nested_ab_commitment = unified_output
-                .nested_ab_commitment
-                .get(dr, unified_instance)?;
-            nested_ab_commitment.write(dr, &mut sponge)?;
-            sponge.squeeze(dr)?
-        };
-        unified_output.x.set(x);
-
-        // Derive alpha by absorbing nested_query_commitment and squeezing
-        let alpha = {
-            let nested_query_commitment = unified_output
-                .nested_query_commitment
-                .get(dr, unified_instance)?;
-            nested_query_commitment.write(dr, &mut sponge)?;
-            sponge.squeeze(dr)?
-        };
-        unified_output.alpha.set(alpha.clone());
-
-        // Derive u by absorbing nested_f_commitment and squeezing
-        let u = {
-            let nested_f_commitment = unified_output
-                .nested_f_commitment
-                .get(dr, unified_instance)?;
-            nested_f_commitment.write(dr, &mut sponge)?;
-            sponge.squeeze(dr)?
-        };
-        unified_output.u.set(u);
-
-        // Derive pre_beta by absorbing nested_eval_commitment and squeezing
-        let pre_beta = {
-            let nested_eval_commitment = unified_output
-                .nested_eval_commitment
-                .get(dr, unified_instance)?;
-            nested_eval_commitment.write(dr, &mut sponge)?;
-            sponge.squeeze(dr)?
-        };
-        unified_output.pre_beta.set(pre_beta);
-
-        Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+        dr.namespace(
+            || "hashes_2",
+            |dr| {
+                let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
+
+                let unified_instance = &witness.view().map(|w| w.unified_instance);
+                let mut unified_output = OutputBuilder::new();
+
+                // Resume sponge from saved state (error_m already absorbed in hashes_1)
+                // and squeeze mu (first challenge from error_m absorption)
+                let (mu, mut sponge) = Sponge::resume_and_squeeze(
+                    dr,
+                    error_n.sponge_state,
+                    C::circuit_poseidon(self.params),
+                )?;
+                unified_output.mu.set(mu);
+
+                // Squeeze nu (second challenge from error_m absorption)
+                let nu = sponge.squeeze(dr)?;
+                unified_output.nu.set(nu);
+
+                // Derive (mu_prime, nu_prime) by absorbing nested_error_n_commitment
+                let (mu_prime, nu_prime) = {
+                    let nested_error_n_commitment = unified_output
+                        .nested_error_n_commitment
+                        .get(dr, unified_instance)?;
+                    nested_error_n_commitment.write(dr, &mut sponge)?;
+                    let mu_prime = sponge.squeeze(dr)?;
+                    let nu_prime = sponge.squeeze(dr)?;
+                    (mu_prime, nu_prime)
+                };
+                unified_output.mu_prime.set(mu_prime);
+                unified_output.nu_prime.set(nu_prime);
+
+                // Derive x by absorbing nested_ab_commitment and squeezing
+                let x = {
+                    let nested_ab_commitment = unified_output
+                        .nested_ab_commitment
+                        .get(dr, unified_instance)?;
+                    nested_ab_commitment.write(dr, &mut sponge)?;
+                    sponge.squeeze(dr)?
+                };
+                unified_output.x.set(x);
+
+                // Derive alpha by absorbing nested_query_commitment and squeezing
+                let alpha = {
+                    let nested_query_commitment = unified_output
+                        .nested_query_commitment
+                        .get(dr, unified_instance)?;
+                    nested_query_commitment.write(dr, &mut sponge)?;
+                    sponge.squeeze(dr)?
+                };
+                unified_output.alpha.set(alpha.clone());
+
+                // Derive u by absorbing nested_f_commitment and squeezing
+                let u = {
+                    let nested_f_commitment = unified_output
+                        .nested_f_commitment
+                        .get(dr, unified_instance)?;
+                    nested_f_commitment.write(dr, &mut sponge)?;
+                    sponge.squeeze(dr)?
+                };
+                unified_output.u.set(u);
+
+                // Derive pre_beta by absorbing nested_eval_commitment and squeezing
+                let pre_beta = {
+                    let nested_eval_commitment = unified_output
+                        .nested_eval_commitment
+                        .get(dr, unified_instance)?;
+                    nested_eval_commitment.write(dr, &mut sponge)?;
+                    sponge.squeeze(dr)?
+                };
+                unified_output.pre_beta.set(pre_beta);
+
+                Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+            },
+        )
     }
 }
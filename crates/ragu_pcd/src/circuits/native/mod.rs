@@ -52,9 +52,50 @@ pub(crate) fn total_circuit_counts(num_application_steps: usize) -> (usize, u32)
 }
 
 impl InternalCircuitIndex {
+    /// Every internal circuit, in the order in which they are registered.
+    pub(crate) const ALL: [Self; NUM_INTERNAL_CIRCUITS] = [
+        Self::PreambleStage,
+        Self::ErrorMStage,
+        Self::ErrorNStage,
+        Self::QueryStage,
+        Self::EvalStage,
+        Self::ErrorMFinalStaged,
+        Self::ErrorNFinalStaged,
+        Self::EvalFinalStaged,
+        Self::Hashes1Circuit,
+        Self::Hashes2Circuit,
+        Self::PartialCollapseCircuit,
+        Self::FullCollapseCircuit,
+        Self::ComputeVCircuit,
+    ];
+
     pub(crate) fn circuit_index(self, num_application_steps: usize) -> CircuitIndex {
         CircuitIndex::new(num_application_steps + step::NUM_INTERNAL_STEPS + self as usize)
     }
+
+    /// Returns whether this is a stage mask rather than a circuit.
+    pub(crate) fn is_stage(self) -> bool {
+        (self as usize) < (Self::Hashes1Circuit as usize)
+    }
+
+    /// Returns a human-readable name for this circuit.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::PreambleStage => "preamble_stage",
+            Self::ErrorMStage => "error_m_stage",
+            Self::ErrorNStage => "error_n_stage",
+            Self::QueryStage => "query_stage",
+            Self::EvalStage => "eval_stage",
+            Self::ErrorMFinalStaged => "error_m_final_staged",
+            Self::ErrorNFinalStaged => "error_n_final_staged",
+            Self::EvalFinalStaged => "eval_final_staged",
+            Self::Hashes1Circuit => "hashes_1",
+            Self::Hashes2Circuit => "hashes_2",
+            Self::PartialCollapseCircuit => "partial_collapse",
+            Self::FullCollapseCircuit => "full_collapse",
+            Self::ComputeVCircuit => "compute_v",
+        }
+    }
 }
 
 /// Register internal native circuits into the provided registry.
@@ -28,6 +28,25 @@
 //! - Application and unified $k(y)$ evaluations from [`error_n`] (computed and
 //!   verified in [`hashes_1`]).
 //!
+//! All of these are replaced with zero for a child whose output header is
+//! `()`, so that the empty trivial proof can stand in for it.
+//!
+//! ### Children with `()` headers
+//!
+//! Zeroing the $k(y)$ values of a child makes its claims vacuous. This is
+//! sound for the same reason as the [base case] exception in
+//! [`full_collapse`], applied to each child on its own:
+//! - A `()` header carries no data, and anyone can produce a valid proof for
+//!   it, so the claims about such a child establish nothing that a step could
+//!   rely on.
+//! - Whether a child is trivial is read from the suffix of its output header
+//!   in the [`preamble`]. That header is also the corresponding input header
+//!   of the step's application circuit, which ends it with the constant
+//!   [`Header::SUFFIX`] of the step's input type. Only `()` uses the suffix
+//!   `1`: application suffixes start at `2`, and `0` is reserved for
+//!   internal circuits. So a child whose header carries data always keeps
+//!   all of its claims.
+//!
 //! ## Staging
 //!
 //! This circuit uses [`error_m`] as its final stage, which inherits in the
@@ -43,6 +62,8 @@
 //!
 //! [`Parameters`]: fold_revdot::Parameters
 //! [`full_collapse`]: super::full_collapse
+//! [base case]: super::full_collapse#base-case-handling
+//! [`Header::SUFFIX`]: crate::header::Header::SUFFIX
 //! [$c$]: unified::Output::c
 //! [$\mu$]: unified::Output::mu
 //! [$\nu$]: unified::Output::nu
@@ -64,7 +85,7 @@ use ragu_core::{
     gadgets::{Gadget, GadgetKind},
     maybe::Maybe,
 };
-use ragu_primitives::{Element, vec::FixedVec};
+use ragu_primitives::vec::FixedVec;
 
 use core::marker::PhantomData;
 
@@ -151,51 +172,67 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize, FP: fold_revdot::Parameters>
         let (error_m, builder) =
             builder.add_stage::<native_error_m::Stage<C, R, HEADER_SIZE, FP>>()?;
         let dr = builder.finish();
-        let preamble = preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
-
-        // TODO: these are unenforced for now, because error_n/error_m stages
-        // aren't supposed to contain anything (yet) besides Elements, which
-        // require no enforcement logic. Re-evaluate this in the future.
-        let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
-        let error_m = error_m.unenforced(dr, witness.view().map(|w| w.error_m_witness))?;
-
-        let unified_instance = &witness.view().map(|w| w.unified_instance);
-        let mut unified_output = OutputBuilder::new();
-
-        // Get layer 1 folding challenges from the unified instance.
-        let mu = unified_output.mu.get(dr, unified_instance)?;
-        let nu = unified_output.nu.get(dr, unified_instance)?;
-        let fold_products = fold_revdot::FoldProducts::new(dr, &mu, &nu)?;
-
-        // Assemble k(y) values from multiple sources. The ordering must match
-        // claims's iteration order for correct folding correspondence.
-        // Sources include:
-        // - Child c values from preamble (the children's final revdot claims)
-        // - Application and unified k(y) evaluations from error_n
-        let ky = TwoProofKySource {
-            left_raw_c: preamble.left.unified.c.clone(),
-            right_raw_c: preamble.right.unified.c.clone(),
-            left_app: error_n.left.application.clone(),
-            right_app: error_n.right.application.clone(),
-            left_bridge: error_n.left.unified_bridge.clone(),
-            right_bridge: error_n.right.unified_bridge.clone(),
-            left_unified: error_n.left.unified.clone(),
-            right_unified: error_n.right.unified.clone(),
-            zero: Element::zero(dr),
-        };
-        let mut ky = ky_values(&ky);
-
-        // Verify each group's layer 1 reduction. For each group, fold the
-        // error_m terms with the corresponding k(y) values and enforce the
-        // result matches the collapsed value witnessed in error_n.
-        for (i, error_terms) in error_m.error_terms.iter().enumerate() {
-            let ky = FixedVec::from_fn(|_| ky.next().unwrap());
-
-            fold_products
-                .fold_products_m::<FP>(dr, error_terms, &ky)?
-                .enforce_equal(dr, &error_n.collapsed[i])?;
-        }
-
-        Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+
+        dr.namespace(
+            || "partial_collapse",
+            |dr| {
+                let preamble =
+                    preamble.unenforced(dr, witness.view().map(|w| w.preamble_witness))?;
+
+                // TODO: these are unenforced for now, because error_n/error_m stages
+                // aren't supposed to contain anything (yet) besides Elements, which
+                // require no enforcement logic. Re-evaluate this in the future.
+                let error_n = error_n.unenforced(dr, witness.view().map(|w| w.error_n_witness))?;
+                let error_m = error_m.unenforced(dr, witness.view().map(|w| w.error_m_witness))?;
+
+                let unified_instance = &witness.view().map(|w| w.unified_instance);
+                let mut unified_output = OutputBuilder::new();
+
+                // Get layer 1 folding challenges from the unified instance.
+                let mu = unified_output.mu.get(dr, unified_instance)?;
+                let nu = unified_output.nu.get(dr, unified_instance)?;
+                let fold_products = fold_revdot::FoldProducts::new(dr, &mu, &nu)?;
+
+                // Assemble k(y) values from multiple sources. The ordering must match
+                // claims's iteration order for correct folding correspondence.
+                // Sources include:
+                // - Child c values from preamble (the children's final revdot claims)
+                // - Application and unified k(y) evaluations from error_n
+                // - Zero in place of all of these for children with `()` headers
+                let left_is_trivial = preamble.left.is_trivial(dr)?;
+                let right_is_trivial = preamble.right.is_trivial(dr)?;
+                let ky = TwoProofKySource::new(
+                    dr,
+                    &left_is_trivial,
+                    [
+                        &preamble.left.unified.c,
+                        &error_n.left.application,
+                        &error_n.left.unified_bridge,
+                        &error_n.left.unified,
+                    ],
+                    &right_is_trivial,
+                    [
+                        &preamble.right.unified.c,
+                        &error_n.right.application,
+                        &error_n.right.unified_bridge,
+                        &error_n.right.unified,
+                    ],
+                )?;
+                let mut ky = ky_values(&ky);
+
+                // Verify each group's layer 1 reduction. For each group, fold the
+                // error_m terms with the corresponding k(y) values and enforce the
+                // result matches the collapsed value witnessed in error_n.
+                for (i, error_terms) in error_m.error_terms.iter().enumerate() {
+                    let ky = FixedVec::from_fn(|_| ky.next().unwrap());
+
+                    fold_products
+                        .fold_products_m::<FP>(dr, error_terms, &ky)?
+                        .enforce_equal(dr, &error_n.collapsed[i])?;
+                }
+
+                Ok((unified_output.finish(dr, unified_instance)?, D::just(|| ())))
+            },
+        )
     }
 }
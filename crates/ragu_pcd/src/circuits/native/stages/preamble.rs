@@ -3,7 +3,11 @@
 //! Verifies child proof headers and computes the Ky term.
 
 use arithmetic::Cycle;
-use ragu_circuits::{polynomials::Rank, staging};
+use ragu_circuits::{
+    polynomials::{Rank, structured},
+    registry::CircuitIndex,
+    staging::{self, StageExt},
+};
 use ragu_core::{
     Error, Result,
     drivers::{Driver, DriverValue},
@@ -15,12 +19,9 @@ use ragu_primitives::{
     vec::{CollectFixed, ConstLen, FixedVec},
 };
 
-use alloc::vec::Vec;
 use core::marker::PhantomData;
 
-use crate::{
-    Proof, circuits::native::unified, components::ky::Ky, header::Header, step::internal::padded,
-};
+use crate::{Proof, circuits::native::unified, components::ky::Ky};
 
 pub(crate) use crate::circuits::native::InternalCircuitIndex::PreambleStage as STAGING_ID;
 
@@ -188,33 +189,33 @@ impl<'dr, D: Driver<'dr, F = C::CircuitField>, C: Cycle, const HEADER_SIZE: usiz
         })
     }
 
-    /// Allocate ProofInputs from a proof reference and some unprocessed header
-    /// data.
-    pub fn alloc_for_verify<'source, R: Rank, H: Header<C::CircuitField>>(
+    /// Allocate ProofInputs from the headers, circuit ID and unified instance
+    /// of a proof, as recorded in a [`CompressedProof`](crate::CompressedProof).
+    pub fn alloc_from_instance(
         dr: &mut D,
-        proof: DriverValue<D, &Proof<C, R>>,
-        header_data: DriverValue<D, H::Data<'source>>,
-    ) -> Result<Self>
-    where
-        'source: 'dr,
-    {
-        let header_data = D::with(|| {
-            use ragu_core::drivers::emulator::{Emulator, Wireless};
-            let emulator = &mut Emulator::<Wireless<D::MaybeKind, D::F>>::wireless();
-
-            let output = H::encode(emulator, header_data)?;
-            let output = padded::for_header::<H, HEADER_SIZE, _>(emulator, output)?;
-
-            let mut header_data = Vec::with_capacity(HEADER_SIZE);
-            output.write(emulator, &mut header_data)?;
-
-            header_data
-                .into_iter()
-                .map(|e| *e.value().take())
-                .collect_fixed()
-        })?;
+        children: [&[D::F]; 2],
+        output_header: &[D::F],
+        circuit_id: CircuitIndex,
+        instance: &unified::Instance<C>,
+    ) -> Result<Self> {
+        // Fails if a header is not `HEADER_SIZE` elements long.
+        let mut alloc_header = |header: &[D::F]| {
+            header
+                .iter()
+                .map(|value| Element::alloc(dr, D::just(|| *value)))
+                .try_collect_fixed()
+        };
+        let [left, right] = children;
 
-        Self::alloc(dr, proof, header_data.view())
+        Ok(ProofInputs {
+            children: ChildHeaders {
+                left: alloc_header(left)?,
+                right: alloc_header(right)?,
+            },
+            output_header: alloc_header(output_header)?,
+            circuit_id: Element::alloc(dr, D::just(|| circuit_id.omega_j()))?,
+            unified: unified::OutputBuilder::new().finish_no_suffix(dr, &D::just(|| instance))?,
+        })
     }
 }
 
@@ -236,6 +237,31 @@ impl<'dr, D: Driver<'dr>, C: Cycle, const HEADER_SIZE: usize> Output<'dr, D, C,
     }
 }
 
+/// Returns the $x$ challenges of the left and right child proofs, as
+/// witnessed in the preamble stage's witness polynomial `rx`.
+pub fn child_x<C: Cycle, R: Rank, const HEADER_SIZE: usize>(
+    rx: &structured::Polynomial<C::CircuitField, R>,
+) -> [C::CircuitField; 2] {
+    child_x_indices::<HEADER_SIZE>().map(|index| Stage::<C, R, HEADER_SIZE>::value(rx, index))
+}
+
+/// Returns the degrees of the coefficients of the preamble stage's witness
+/// polynomial `rx` that hold the values returned by [`child_x`].
+pub fn child_x_degrees<C: Cycle, R: Rank, const HEADER_SIZE: usize>() -> [usize; 2] {
+    child_x_indices::<HEADER_SIZE>().map(Stage::<C, R, HEADER_SIZE>::value_degree)
+}
+
+/// Returns the indices of the values of the preamble stage that hold the $x$
+/// challenges of the left and right child proofs.
+fn child_x_indices<const HEADER_SIZE: usize>() -> [usize; 2] {
+    // The values of each child's `ProofInputs` are the three headers, the
+    // circuit ID and then its unified instance.
+    let child_values = 3 * HEADER_SIZE + 1 + unified::NUM_WIRES;
+    let x = 3 * HEADER_SIZE + 1 + unified::X_WIRE;
+
+    [x, child_values + x]
+}
+
 #[derive(Default)]
 pub struct Stage<C: Cycle, R, const HEADER_SIZE: usize> {
     _marker: PhantomData<(C, R)>,
@@ -281,10 +307,22 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> staging::Stage<C::CircuitField
 mod tests {
     use super::*;
     use crate::circuits::native::stages::tests::{HEADER_SIZE, R, assert_stage_values};
-    use ragu_pasta::Pasta;
+    use ragu_pasta::{Fp, Pasta};
 
     #[test]
     fn stage_values_matches_wire_count() {
         assert_stage_values(&Stage::<Pasta, R, { HEADER_SIZE }>::default());
     }
+
+    #[test]
+    fn child_x_degrees_match_child_x() {
+        let rx = structured::Polynomial::<Fp, R>::random(&mut rand::thread_rng());
+        let coeffs = rx.unstructured();
+        let degrees = child_x_degrees::<Pasta, R, { HEADER_SIZE }>();
+
+        assert_eq!(
+            degrees.map(|degree| coeffs[degree]),
+            child_x::<Pasta, R, { HEADER_SIZE }>(&rx)
+        );
+    }
 }
@@ -42,6 +42,13 @@ pub type InternalOutputKind<C: Cycle> = Kind![C::CircuitField; WithSuffix<'_, _,
 /// Used for allocation sizing and verified by tests.
 pub const NUM_WIRES: usize = 29;
 
+/// The index of the [`Output::x`] wire among the wires of an [`Output`]
+/// gadget.
+///
+/// Used to read the child proofs' $x$ challenges from the preamble stage and
+/// verified by tests.
+pub const X_WIRE: usize = 18;
+
 /// Shared public inputs for internal verification circuits.
 ///
 /// This gadget contains the commitments, Fiat-Shamir challenges, and final
@@ -157,6 +164,7 @@ pub struct Output<'dr, D: Driver<'dr>, C: Cycle> {
 /// and passed to circuits as witness data for gadget allocation.
 ///
 /// See [`Output`] for field descriptions.
+#[derive(Clone)]
 pub struct Instance<C: Cycle> {
     pub nested_preamble_commitment: C::NestedCurve,
     pub w: C::CircuitField,
@@ -448,8 +456,11 @@ impl<'a, 'dr, D: Driver<'dr, F = C::CircuitField>, C: Cycle> OutputBuilder<'a, '
 mod tests {
     use super::*;
     use ragu_circuits::polynomials::R;
-    use ragu_core::{drivers::emulator::Emulator, maybe::Empty};
-    use ragu_pasta::Pasta;
+    use ragu_core::{
+        drivers::emulator::Emulator,
+        maybe::{Always, Empty, MaybeKind},
+    };
+    use ragu_pasta::{Fp, Pasta};
 
     #[test]
     fn num_wires_constant_is_correct() {
@@ -464,4 +475,23 @@ mod tests {
             "NUM_WIRES constant does not match actual wire count"
         );
     }
+
+    #[test]
+    fn x_wire_constant_is_correct() {
+        let mut proof = crate::ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .finalize(Pasta::baked())
+            .expect("finalization should succeed")
+            .trivial_proof();
+        proof.challenges.x = Fp::from(0x1234_5678);
+
+        let mut emulator = Emulator::extractor();
+        let output = Output::<'_, _, Pasta>::alloc_from_proof::<R<13>>(
+            &mut emulator,
+            Always::maybe_just(|| &proof),
+        )
+        .expect("allocation should succeed");
+        let wires = emulator.wires(&output).expect("wires should be extracted");
+
+        assert_eq!(wires[X_WIRE], proof.challenges.x);
+    }
 }
@@ -5,7 +5,6 @@
 
 use arithmetic::Cycle;
 use ragu_circuits::{
-    CircuitExt,
     polynomials::Rank,
     registry::{CircuitIndex, RegistryBuilder},
     staging::{MultiStage, StageExt},
@@ -75,7 +74,7 @@ pub(crate) fn register_all<'params, C: Cycle, R: Rank>(
     for step in 0..num_steps {
         let step_circuit = EndoscalingStep::<C::HostCurve, R, NUM_ENDOSCALING_POINTS>::new(step);
         let staged = MultiStage::new(step_circuit);
-        registry = registry.register_circuit_object(staged.into_object()?)?;
+        registry = registry.register_circuit(staged)?;
     }
     Ok(registry)
 }
@@ -65,7 +65,7 @@ fn test_internal_circuit_constraint_counts() {
 
     check_constraints!(Hashes1Circuit,         mul = 2045, lin = 3423);
     check_constraints!(Hashes2Circuit,         mul = 1879, lin = 2952);
-    check_constraints!(PartialCollapseCircuit, mul = 1756, lin = 1919);
+    check_constraints!(PartialCollapseCircuit, mul = 1768, lin = 1943);
     check_constraints!(FullCollapseCircuit,    mul = 811 , lin = 809);
     check_constraints!(ComputeVCircuit,        mul = 1404, lin = 2280);
 }
@@ -169,7 +169,7 @@ fn test_native_registry_digest() {
         .finalize(pasta)
         .unwrap();
 
-    let expected = fp!(0x1277dee2ad8fa4dddc022539e29ed544f6cd96261ee1baaa22819611e9e3e593);
+    let expected = fp!(0x21b17de395d3e2fc9c2ceba7f73fdb6b03e3bfb30d73978c4d5ac3ee36d4261e);
 
     assert_eq!(
         app.native_registry.get_key(),
@@ -67,6 +67,9 @@ pub struct Builder<'m, 'rx, F: PrimeField, R: Rank> {
     pub(crate) y: F,
     pub(crate) z: F,
     pub(crate) tz: structured::Polynomial<F, R>,
+    /// The $s(X, y)$ restrictions of the circuits seen so far, which repeat
+    /// once per child proof.
+    sy: Vec<(CircuitIndex, structured::Polynomial<F, R>)>,
     /// The accumulated `a` polynomials for revdot claims.
     pub a: Vec<Cow<'rx, structured::Polynomial<F, R>>>,
     /// The accumulated `b` polynomials for revdot claims.
@@ -82,20 +85,34 @@ impl<'m, 'rx, F: PrimeField, R: Rank> Builder<'m, 'rx, F, R> {
             y,
             z,
             tz: R::tz(z),
+            sy: Vec::new(),
             a: Vec::new(),
             b: Vec::new(),
         }
     }
 
+    /// Returns $s(X, y)$ for the given circuit, evaluating it only the first
+    /// time the circuit is seen.
+    fn circuit_y(&mut self, circuit_id: CircuitIndex) -> &structured::Polynomial<F, R> {
+        let index = match self.sy.iter().position(|(id, _)| *id == circuit_id) {
+            Some(index) => index,
+            None => {
+                let sy = self.registry.circuit_y(circuit_id, self.y);
+                self.sy.push((circuit_id, sy));
+                self.sy.len() - 1
+            }
+        };
+        &self.sy[index].1
+    }
+
     fn circuit_impl(
         &mut self,
         circuit_id: CircuitIndex,
         rx: Cow<'rx, structured::Polynomial<F, R>>,
     ) {
-        let sy = self.registry.circuit_y(circuit_id, self.y);
         let mut b = rx.as_ref().clone();
         b.dilate(self.z);
-        b.add_assign(&sy);
+        b.add_assign(self.circuit_y(circuit_id));
         b.add_assign(&self.tz);
 
         self.a.push(rx);
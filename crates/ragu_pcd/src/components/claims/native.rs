@@ -20,7 +20,7 @@ use ragu_circuits::{
 };
 use ragu_core::Result;
 use ragu_core::drivers::Driver;
-use ragu_primitives::Element;
+use ragu_primitives::{Boolean, Element};
 
 use super::{Builder, Source};
 use crate::circuits::{self, native::InternalCircuitIndex};
@@ -64,6 +64,29 @@ pub enum RxComponent {
     Eval,
 }
 
+impl RxComponent {
+    /// Every component, in declaration order, so that `component as usize`
+    /// indexes into this array.
+    pub const ALL: [RxComponent; 13] = {
+        use RxComponent::*;
+        [
+            AbA,
+            AbB,
+            Application,
+            Hashes1,
+            Hashes2,
+            PartialCollapse,
+            FullCollapse,
+            ComputeV,
+            Preamble,
+            ErrorM,
+            ErrorN,
+            Query,
+            Eval,
+        ]
+    };
+}
+
 /// Trait for processing claim values into accumulated outputs.
 ///
 /// This trait defines how to process rx values from a [`Source`].
@@ -282,6 +305,7 @@ pub fn ky_values<S: KySource>(source: &S) -> impl Iterator<Item = S::Ky> {
         .chain(core::iter::repeat(source.zero()))
 }
 
+/// k(y) values of the two child proofs folded by a step.
 pub struct TwoProofKySource<'dr, D: Driver<'dr>> {
     pub left_raw_c: Element<'dr, D>,
     pub right_raw_c: Element<'dr, D>,
@@ -294,6 +318,43 @@ pub struct TwoProofKySource<'dr, D: Driver<'dr>> {
     pub zero: Element<'dr, D>,
 }
 
+impl<'dr, D: Driver<'dr>> TwoProofKySource<'dr, D> {
+    /// Creates a source from the `[c, application, unified_bridge, unified]`
+    /// values of each child proof.
+    ///
+    /// The values of a child whose output header is `()` are replaced with
+    /// zero, so that its claims are satisfied by the empty trivial proof. See
+    /// the [`partial_collapse`] circuit for why this is sound.
+    ///
+    /// [`partial_collapse`]: crate::circuits::native::partial_collapse
+    pub fn new(
+        dr: &mut D,
+        left_is_trivial: &Boolean<'dr, D>,
+        left: [&Element<'dr, D>; 4],
+        right_is_trivial: &Boolean<'dr, D>,
+        right: [&Element<'dr, D>; 4],
+    ) -> Result<Self> {
+        let zero = Element::zero(dr);
+        let mut gate = |is_trivial: &Boolean<'dr, D>, ky: &Element<'dr, D>| {
+            is_trivial.conditional_select(dr, ky, &zero)
+        };
+        let [left_raw_c, left_app, left_bridge, left_unified] = left;
+        let [right_raw_c, right_app, right_bridge, right_unified] = right;
+
+        Ok(TwoProofKySource {
+            left_raw_c: gate(left_is_trivial, left_raw_c)?,
+            right_raw_c: gate(right_is_trivial, right_raw_c)?,
+            left_app: gate(left_is_trivial, left_app)?,
+            right_app: gate(right_is_trivial, right_app)?,
+            left_bridge: gate(left_is_trivial, left_bridge)?,
+            right_bridge: gate(right_is_trivial, right_bridge)?,
+            left_unified: gate(left_is_trivial, left_unified)?,
+            right_unified: gate(right_is_trivial, right_unified)?,
+            zero: zero.clone(),
+        })
+    }
+}
+
 impl<'dr, D: Driver<'dr>> KySource for TwoProofKySource<'dr, D> {
     type Ky = Element<'dr, D>;
 
@@ -317,3 +378,15 @@ impl<'dr, D: Driver<'dr>> KySource for TwoProofKySource<'dr, D> {
         self.zero.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rx_components_are_indexed_by_discriminant() {
+        for (index, component) in RxComponent::ALL.into_iter().enumerate() {
+            assert_eq!(component as usize, index);
+        }
+    }
+}
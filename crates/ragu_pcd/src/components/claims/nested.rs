@@ -25,6 +25,29 @@ pub enum RxComponent {
     EndoscalingStep(usize),
 }
 
+/// Returns the number of nested field rx polynomials of a proof, which are
+/// indexed by [`RxComponent::index`].
+pub fn num_rx_components() -> usize {
+    use crate::circuits::nested::NUM_ENDOSCALING_POINTS;
+    use crate::components::endoscalar::NumStepsLen;
+    use ragu_primitives::vec::Len;
+
+    2 + NumStepsLen::<NUM_ENDOSCALING_POINTS>::len()
+}
+
+impl RxComponent {
+    /// Returns the position of this component when the rx polynomials of a
+    /// proof are listed as the endoscalar stage, the points stage and then
+    /// every endoscaling step in order.
+    pub fn index(self) -> usize {
+        match self {
+            RxComponent::EndoscalarStage => 0,
+            RxComponent::PointsStage => 1,
+            RxComponent::EndoscalingStep(step) => 2 + step,
+        }
+    }
+}
+
 /// Trait for processing nested claim values into accumulated outputs.
 ///
 /// This trait defines how to process rx values from a [`Source`].
@@ -0,0 +1,428 @@
+//! Bulletproofs inner product argument for opening commitments.
+//!
+//! Given a commitment $P = \langle \mathbf{p}, \mathbf{G} \rangle + r H$ to a
+//! vector $\mathbf{p}$ of length $n = 2^k$ (such as the coefficients of a
+//! polynomial $p(X)$) and a public vector $\mathbf{b}$, an [`Opening`]
+//! convinces the verifier that $\langle \mathbf{p}, \mathbf{b} \rangle = v$
+//! using $O(k)$ group elements and $O(n)$ verifier work in a single
+//! multiscalar multiplication. Choosing $\mathbf{b} = (1, u, u^2, \ldots,
+//! u^{n-1})$ (see [`powers`]) opens $p(X)$ at $u$.
+//!
+//! The argument follows the hiding variant used in Halo 2:
+//!
+//! 1. The prover commits to a random vector $\mathbf{s}$ with $\langle
+//!    \mathbf{s}, \mathbf{b} \rangle = 0$ as $S$. Given a challenge $\xi$ the
+//!    claim becomes an opening of $P + \xi S$ to $\mathbf{a} = \mathbf{p} + \xi
+//!    \mathbf{s}$, which reveals nothing about $\mathbf{p}$ when $\mathbf{a}$ is
+//!    eventually sent.
+//! 2. Given a challenge $z$, the value generator $Q' = z Q$ binds the inner
+//!    product $\langle \mathbf{a}, \mathbf{b} \rangle = v$.
+//! 3. Each of the $k$ rounds sends blinded cross terms $L_j, R_j$ and halves
+//!    $\mathbf{a}$, $\mathbf{b}$ and $\mathbf{G}$ with a challenge $x_j$, so
+//!    that the final claim concerns a single scalar $a$ and blinding factor.
+//!
+//! Challenges are derived from a Poseidon transcript over the base field of
+//! the curve, to which a caller-provided context, the commitments and the
+//! claimed value are absorbed, and are mapped into the scalar field as
+//! endoscalars. The vector $\mathbf{b}$ is not absorbed, and so must be
+//! determined by the context.
+//!
+//! See the book's Bulletproofs preliminaries for background on the
+//! underlying argument.
+
+use arithmetic::{CurveAffine, OpeningGenerators, PoseidonPermutation};
+use ff::{Field, PrimeField, WithSmallOrderMulGroup};
+use pasta_curves::group::{Curve, Group};
+use ragu_core::{
+    Result,
+    drivers::{Driver, emulator::Emulator},
+    maybe::{Always, Maybe},
+};
+use ragu_primitives::{
+    Element, GadgetExt, Point, compute_endoscalar, extract_endoscalar, poseidon::Sponge,
+};
+use rand::Rng;
+
+use alloc::{vec, vec::Vec};
+
+use crate::limbs;
+
+/// Succinct opening of a commitment against a public vector.
+#[derive(Clone)]
+pub(crate) struct Opening<G: CurveAffine> {
+    /// Commitment to the masking vector $\mathbf{s}$.
+    pub(crate) s_commitment: G,
+    /// Cross terms $L_j$ for each round.
+    pub(crate) l: Vec<G>,
+    /// Cross terms $R_j$ for each round.
+    pub(crate) r: Vec<G>,
+    /// The fully folded coefficient $a$.
+    pub(crate) a: G::ScalarExt,
+    /// The fully folded blinding factor.
+    pub(crate) blind: G::ScalarExt,
+}
+
+/// Returns the first `n` powers $(1, u, u^2, \ldots)$ of `u`, against which an
+/// [`Opening`] of the coefficients of a polynomial attests to its evaluation
+/// at `u`.
+pub(crate) fn powers<F: Field>(u: F, n: usize) -> Vec<F> {
+    core::iter::successors(Some(F::ONE), |b| Some(*b * u))
+        .take(n)
+        .collect()
+}
+
+/// Fiat-Shamir transcript for the opening argument.
+struct Transcript<'dr, D: Driver<'dr>, P: PoseidonPermutation<D::F>> {
+    sponge: Sponge<'dr, D, P>,
+}
+
+impl<'dr, D, P> Transcript<'dr, D, P>
+where
+    D: Driver<'dr, MaybeKind = Always<()>>,
+    D::F: PrimeField,
+    P: PoseidonPermutation<D::F>,
+{
+    fn new(dr: &mut D, params: &'dr P) -> Self {
+        Transcript {
+            sponge: Sponge::new(dr, params),
+        }
+    }
+
+    /// Absorbs a point, failing if it is the identity.
+    fn point<G: CurveAffine<Base = D::F>>(&mut self, dr: &mut D, p: G) -> Result<()> {
+        Point::constant(dr, p)?.write(dr, &mut self.sponge)
+    }
+
+    /// Absorbs bytes as their length followed by their [`limbs`].
+    fn bytes(&mut self, dr: &mut D, bytes: &[u8]) -> Result<()> {
+        let len = Element::constant(dr, D::F::from(bytes.len() as u64));
+        self.sponge.absorb(dr, &len)?;
+        for limb in limbs(bytes) {
+            let limb = Element::constant(dr, limb);
+            self.sponge.absorb(dr, &limb)?;
+        }
+
+        Ok(())
+    }
+
+    /// Absorbs a scalar field element as the [`limbs`] of its representation,
+    /// which always fit in the base field.
+    fn scalar<F: PrimeField>(&mut self, dr: &mut D, value: F) -> Result<()> {
+        for limb in limbs(value.to_repr().as_ref()) {
+            let limb = Element::constant(dr, limb);
+            self.sponge.absorb(dr, &limb)?;
+        }
+
+        Ok(())
+    }
+
+    fn challenge<F: WithSmallOrderMulGroup<3>>(&mut self, dr: &mut D) -> Result<F> {
+        let value = self.sponge.squeeze(dr)?;
+        Ok(compute_endoscalar(extract_endoscalar(
+            *value.value().take(),
+        )))
+    }
+}
+
+/// The public statement of an [`Opening`]: that the vector committed to by
+/// `commitment` has inner product `v` with `b`.
+pub(crate) struct Statement<'a, G: CurveAffine> {
+    /// Bytes that the challenges are bound to, which must determine `b`.
+    pub(crate) context: &'a [u8],
+    pub(crate) commitment: G,
+    pub(crate) b: &'a [G::ScalarExt],
+    pub(crate) v: G::ScalarExt,
+}
+
+impl<G: CurveAffine> Statement<'_, G> {
+    fn absorb<'dr, D, P>(&self, dr: &mut D, transcript: &mut Transcript<'dr, D, P>) -> Result<()>
+    where
+        D: Driver<'dr, F = G::Base, MaybeKind = Always<()>>,
+        P: PoseidonPermutation<G::Base>,
+    {
+        transcript.bytes(dr, self.context)?;
+        transcript.point(dr, self.commitment)?;
+        transcript.scalar(dr, self.v)
+    }
+}
+
+/// Creates an [`Opening`] of the commitment in `statement` to `coeffs` with
+/// blinding factor `blind`, whose length must be a power of two equal to that
+/// of the statement's public vector.
+pub(crate) fn open<G: CurveAffine, RNG: Rng>(
+    generators: &impl OpeningGenerators<G>,
+    poseidon: &impl PoseidonPermutation<G::Base>,
+    statement: &Statement<'_, G>,
+    coeffs: &[G::ScalarExt],
+    blind: G::ScalarExt,
+    rng: &mut RNG,
+) -> Result<Opening<G>> {
+    let n = coeffs.len();
+    assert!(n.is_power_of_two() && statement.b.len() == n);
+
+    let mut dr = Emulator::execute();
+    let mut transcript = Transcript::new(&mut dr, poseidon);
+
+    // Mask with a random s orthogonal to b, by adjusting the coefficient of
+    // the first nonzero entry of b.
+    let mut s: Vec<_> = (0..n).map(|_| G::ScalarExt::random(&mut *rng)).collect();
+    if let Some(k) = statement.b.iter().position(|b| !bool::from(b.is_zero())) {
+        let s_b = arithmetic::dot(&s, statement.b);
+        s[k] -= s_b * statement.b[k].invert().expect("b_k is nonzero");
+    }
+    let s_blind = G::ScalarExt::random(&mut *rng);
+    let s_commitment: G = arithmetic::mul(
+        s.iter().chain([&s_blind]),
+        generators.g()[..n].iter().chain([generators.h()]),
+    )
+    .into();
+
+    statement.absorb(&mut dr, &mut transcript)?;
+    transcript.point(&mut dr, s_commitment)?;
+    let xi: G::ScalarExt = transcript.challenge(&mut dr)?;
+    let z: G::ScalarExt = transcript.challenge(&mut dr)?;
+
+    let q = (*generators.q() * z).to_affine();
+    let h = *generators.h();
+
+    let mut a: Vec<_> = coeffs.iter().zip(&s).map(|(p, s)| *p + xi * s).collect();
+    let mut b = statement.b.to_vec();
+    let mut g = generators.g()[..n].to_vec();
+    let mut blind = blind + xi * s_blind;
+
+    let rounds = n.trailing_zeros() as usize;
+    let mut l = Vec::with_capacity(rounds);
+    let mut r = Vec::with_capacity(rounds);
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(half);
+        let (b_lo, b_hi) = b.split_at(half);
+        let (g_lo, g_hi) = g.split_at(half);
+
+        let l_blind = G::ScalarExt::random(&mut *rng);
+        let r_blind = G::ScalarExt::random(&mut *rng);
+        let l_value = arithmetic::dot(a_lo, b_hi);
+        let r_value = arithmetic::dot(a_hi, b_lo);
+        let l_j: G = arithmetic::mul(
+            a_lo.iter().chain([&l_value, &l_blind]),
+            g_hi.iter().chain([&q, &h]),
+        )
+        .into();
+        let r_j: G = arithmetic::mul(
+            a_hi.iter().chain([&r_value, &r_blind]),
+            g_lo.iter().chain([&q, &h]),
+        )
+        .into();
+
+        transcript.point(&mut dr, l_j)?;
+        transcript.point(&mut dr, r_j)?;
+        let x: G::ScalarExt = transcript.challenge(&mut dr)?;
+        let x_inv = x.invert().expect("endoscalar challenges are nonzero");
+
+        a = a_lo
+            .iter()
+            .zip(a_hi)
+            .map(|(lo, hi)| *lo * x + *hi * x_inv)
+            .collect();
+        b = b_lo
+            .iter()
+            .zip(b_hi)
+            .map(|(lo, hi)| *lo * x_inv + *hi * x)
+            .collect();
+        let folded: Vec<_> = g_lo
+            .iter()
+            .zip(g_hi)
+            .map(|(lo, hi)| *lo * x_inv + *hi * x)
+            .collect();
+        g = vec![G::identity(); half];
+        G::Curve::batch_normalize(&folded, &mut g);
+        blind += x.square() * l_blind + x_inv.square() * r_blind;
+
+        l.push(l_j);
+        r.push(r_j);
+    }
+
+    Ok(Opening {
+        s_commitment,
+        l,
+        r,
+        a: a[0],
+        blind,
+    })
+}
+
+/// Checks that `opening` shows the claim made by `statement`, whose public
+/// vector must have a length that is a power of two.
+pub(crate) fn verify<G: CurveAffine>(
+    generators: &impl OpeningGenerators<G>,
+    poseidon: &impl PoseidonPermutation<G::Base>,
+    statement: &Statement<'_, G>,
+    opening: &Opening<G>,
+) -> Result<bool> {
+    let n = statement.b.len();
+    assert!(n.is_power_of_two());
+
+    let rounds = n.trailing_zeros() as usize;
+    if opening.l.len() != rounds || opening.r.len() != rounds {
+        return Ok(false);
+    }
+    if core::iter::once(&statement.commitment)
+        .chain(Some(&opening.s_commitment))
+        .chain(&opening.l)
+        .chain(&opening.r)
+        .any(|p| bool::from(p.is_identity()))
+    {
+        return Ok(false);
+    }
+
+    let mut dr = Emulator::execute();
+    let mut transcript = Transcript::new(&mut dr, poseidon);
+
+    statement.absorb(&mut dr, &mut transcript)?;
+    transcript.point(&mut dr, opening.s_commitment)?;
+    let xi: G::ScalarExt = transcript.challenge(&mut dr)?;
+    let z: G::ScalarExt = transcript.challenge(&mut dr)?;
+
+    // The folded generator is G_0 = <s, G> and the folded b_0 = <s, b>, where
+    // s_i is the product over rounds j of x_j (if the bit of i halved in
+    // round j is set) or x_j^{-1}.
+    let mut s = vec![G::ScalarExt::ONE];
+    let mut round_scalars = Vec::with_capacity(rounds * 2);
+    for (l_j, r_j) in opening.l.iter().zip(&opening.r) {
+        transcript.point(&mut dr, *l_j)?;
+        transcript.point(&mut dr, *r_j)?;
+        let x: G::ScalarExt = transcript.challenge(&mut dr)?;
+        let x_inv = x.invert().expect("endoscalar challenges are nonzero");
+
+        s = s.iter().flat_map(|s| [*s * x_inv, *s * x]).collect();
+        round_scalars.push(x.square());
+        round_scalars.push(x_inv.square());
+    }
+    let b = arithmetic::dot(&s, statement.b);
+
+    // Check P + xi S + v Q' + sum_j (x_j^2 L_j + x_j^{-2} R_j)
+    //        = a G_0 + a b_0 Q' + blind H.
+    let q = (*generators.q() * z).to_affine();
+    let scalars: Vec<_> = [G::ScalarExt::ONE, xi, statement.v - opening.a * b]
+        .into_iter()
+        .chain(round_scalars)
+        .chain(s.iter().map(|s| -(opening.a * s)))
+        .chain(Some(-opening.blind))
+        .collect();
+    let bases: Vec<_> = [statement.commitment, opening.s_commitment, q]
+        .into_iter()
+        .chain(opening.l.iter().zip(&opening.r).flat_map(|(l, r)| [*l, *r]))
+        .chain(generators.g()[..n].iter().copied())
+        .chain(Some(*generators.h()))
+        .collect();
+
+    Ok(bool::from(arithmetic::mul(&scalars, &bases).is_identity()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arithmetic::Cycle;
+    use ragu_circuits::polynomials::{R, Rank, unstructured};
+    use ragu_pasta::{Fp, Pasta};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    type TestRank = R<8>;
+
+    #[test]
+    fn test_opening() -> Result<()> {
+        let params = Pasta::baked();
+        let generators = Pasta::host_generators(params);
+        let poseidon = Pasta::scalar_poseidon(params);
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let poly = unstructured::Polynomial::<Fp, TestRank>::random(&mut rng);
+        let blind = Fp::random(&mut rng);
+        let commitment = poly.commit(generators, blind);
+        let u = Fp::random(&mut rng);
+        let b = powers(u, TestRank::num_coeffs());
+        let statement = Statement {
+            context: b"test",
+            commitment,
+            b: &b,
+            v: poly.eval(u),
+        };
+
+        let opening = open(generators, poseidon, &statement, &poly, blind, &mut rng)?;
+        assert!(verify(generators, poseidon, &statement, &opening)?);
+
+        // Wrong evaluation, point, commitment or context.
+        let wrong_b = powers(u + Fp::ONE, TestRank::num_coeffs());
+        for wrong in [
+            Statement {
+                v: statement.v + Fp::ONE,
+                ..statement
+            },
+            Statement {
+                b: &wrong_b,
+                ..statement
+            },
+            Statement {
+                commitment: (commitment + opening.s_commitment).into(),
+                ..statement
+            },
+            Statement {
+                context: b"other",
+                ..statement
+            },
+        ] {
+            assert!(!verify(generators, poseidon, &wrong, &opening)?);
+        }
+
+        // Tampered final scalar or wrong number of rounds.
+        let mut tampered = opening.clone();
+        tampered.a += Fp::ONE;
+        assert!(!verify(generators, poseidon, &statement, &tampered)?);
+        let mut tampered = opening.clone();
+        tampered.l.pop();
+        assert!(!verify(generators, poseidon, &statement, &tampered)?);
+
+        // A dishonest prover claiming the wrong value is rejected.
+        let wrong = Statement {
+            v: statement.v + Fp::ONE,
+            ..statement
+        };
+        let opening = open(generators, poseidon, &wrong, &poly, blind, &mut rng)?;
+        assert!(!verify(generators, poseidon, &wrong, &opening)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opening_coefficients() -> Result<()> {
+        let params = Pasta::baked();
+        let generators = Pasta::host_generators(params);
+        let poseidon = Pasta::scalar_poseidon(params);
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        // Opens a linear combination of two coefficients.
+        let poly = unstructured::Polynomial::<Fp, TestRank>::random(&mut rng);
+        let blind = Fp::random(&mut rng);
+        let rho = Fp::random(&mut rng);
+        let mut b = vec![Fp::ZERO; TestRank::num_coeffs()];
+        b[3] = Fp::ONE;
+        b[100] = rho;
+        let statement = Statement {
+            context: &[],
+            commitment: poly.commit(generators, blind),
+            b: &b,
+            v: poly[3] + rho * poly[100],
+        };
+
+        let opening = open(generators, poseidon, &statement, &poly, blind, &mut rng)?;
+        assert!(verify(generators, poseidon, &statement, &opening)?);
+
+        let wrong = Statement {
+            v: poly[3] + rho * poly[101],
+            ..statement
+        };
+        assert!(!verify(generators, poseidon, &wrong, &opening)?);
+
+        Ok(())
+    }
+}
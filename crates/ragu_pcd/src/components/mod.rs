@@ -4,6 +4,7 @@ pub(crate) mod claims;
 pub(crate) mod endoscalar;
 pub(crate) mod fold_revdot;
 pub(crate) mod horner;
+pub(crate) mod ipa;
 pub(crate) mod ky;
 pub(crate) mod root_of_unity;
 pub(crate) mod suffix;
@@ -0,0 +1,930 @@
+//! This module provides the [`Application::compress`] and
+//! [`Application::verify_compressed`] method implementations.
+//!
+//! A [`CompressedProof`] replaces every polynomial of a [`Proof`] with its
+//! commitment. The verifier of an uncompressed proof checks three kinds of
+//! claims about these polynomials, which are instead checked as follows:
+//!
+//! * **Revdot claims.** The revdot claims $\langle \mathbf{a}_i, \mathbf{b}_i
+//!   \rangle = k_i$ over each field are folded by a challenge $c$ into the
+//!   claim that the coefficient of $X^{N - 1}$ in $T(X) = \sum_i c^{m - 1 - i}
+//!   a_i(X) b_i(X)$ is $K = \sum_i c^{m - 1 - i} k_i$, where $N$ is the number
+//!   of coefficients of each polynomial. The prover commits to $L(X)$ and
+//!   $H(X)$ such that $X \cdot T(X) = L(X) + K X^N + X^{N + 1} H(X)$, which
+//!   the verifier checks at a challenge $r$ using the evaluations of every rx
+//!   polynomial at $r$ and $zr$, from which it computes $T(r)$.
+//! * **Registry claims.** The evaluations of the registry polynomials of the
+//!   proof are checked at the same point $r$ rather than at separate points.
+//! * **The final evaluation** $p(u) = v$ of the accumulated polynomial.
+//!
+//! The evaluations are then shown to be consistent with the commitments by
+//! [inner product arguments](crate::components::ipa), in which the claims at
+//! each point are batched together with powers of a challenge $\rho$. The
+//! $x$ challenges of the child proofs, which the registry claims depend on,
+//! are opened as coefficients of the preamble's rx polynomial.
+//!
+//! The nested rx polynomials of $p(X)$ have no commitments in the
+//! uncompressed proof, and so are committed to during compression.
+//!
+//! Challenges are derived by hashing the [encoding](CompressedProof::to_bytes)
+//! of the compressed proof up to the point at which they are needed, along
+//! with the output header of the proof.
+
+use arithmetic::{CurveAffine, Cycle, Domain, OpeningGenerators};
+use ff::{Field, PrimeField};
+use ragu_circuits::{
+    polynomials::{Rank, structured},
+    registry::{CircuitIndex, Registry},
+};
+use ragu_core::{Error, Result};
+use rand::Rng;
+
+use alloc::{borrow::Cow, vec, vec::Vec};
+use core::{iter::once, marker::PhantomData};
+
+use crate::{
+    Application, CompressedPcd, CompressedProof, Pcd,
+    circuits::{native, native::stages::preamble, nested},
+    components::{
+        claims::{self, Source},
+        ipa,
+    },
+    hash,
+    header::Header,
+    limbs,
+    proof::{
+        Evaluations, Openings, Products, Statement,
+        encoding::{self, Encode},
+    },
+    step::internal::padded,
+    verify,
+};
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
+    /// Compresses some [`Pcd`] for publication.
+    ///
+    /// The resulting [`CompressedProof`] has a size that is logarithmic in the
+    /// [`Rank`], and can be verified in linear time with a few multiscalar
+    /// multiplications. The inner product arguments are blinded, but the
+    /// evaluations of the polynomials of the proof at the challenge points
+    /// are revealed.
+    ///
+    /// This method does not check that the proof is valid.
+    pub fn compress<'source, RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        pcd: Pcd<'source, C, R, H>,
+        rng: &mut RNG,
+    ) -> Result<CompressedPcd<'source, C, R, H>>
+    where
+        C::HostGenerators: OpeningGenerators<C::HostCurve>,
+        C::NestedGenerators: OpeningGenerators<C::NestedCurve>,
+    {
+        let proof = &pcd.proof;
+        let output_header = padded::encode::<H, HEADER_SIZE, _>(pcd.data.clone())?;
+        let host_generators = C::host_generators(self.params);
+        let nested_generators = C::nested_generators(self.params);
+
+        let native_rxs: Vec<_> = claims::native::RxComponent::ALL
+            .into_iter()
+            .map(|component| {
+                let (blind, commitment) = proof.native_rx_commitment(component);
+                Committed::structured(proof.native_rx(component), blind, commitment)
+            })
+            .collect();
+        let nested_rxs: Vec<_> = proof
+            .nested_rxs()
+            .into_iter()
+            .map(|rx| {
+                let blind = C::ScalarField::random(&mut *rng);
+                Committed::structured(rx, blind, rx.commit(nested_generators, blind))
+            })
+            .collect();
+        let registry = [
+            Committed::new(
+                proof.query.registry_xy_poly.to_vec(),
+                proof.query.registry_xy_blind,
+                proof.query.registry_xy_commitment,
+            ),
+            Committed::new(
+                proof.s_prime.registry_wx0_poly.to_vec(),
+                proof.s_prime.registry_wx0_blind,
+                proof.s_prime.registry_wx0_commitment,
+            ),
+            Committed::new(
+                proof.s_prime.registry_wx1_poly.to_vec(),
+                proof.s_prime.registry_wx1_blind,
+                proof.s_prime.registry_wx1_commitment,
+            ),
+            Committed::structured(
+                &proof.error_m.registry_wy_poly,
+                proof.error_m.registry_wy_blind,
+                proof.error_m.registry_wy_commitment,
+            ),
+        ];
+
+        let statement = Statement {
+            digest: proof.digest,
+            circuit_id: proof.application.circuit_id,
+            left_header: proof.application.left_header.clone(),
+            right_header: proof.application.right_header.clone(),
+            instance: proof.unified_instance(),
+            native_rx_commitments: native_rxs.iter().map(|rx| rx.commitment).collect(),
+            registry_xy_commitment: registry[0].commitment,
+            registry_wx0_commitment: registry[1].commitment,
+            registry_wx1_commitment: registry[2].commitment,
+            registry_wy_commitment: registry[3].commitment,
+            p_commitment: proof.p.commitment,
+            nested_rx_commitments: nested_rxs.iter().map(|rx| rx.commitment).collect(),
+        };
+        let mut transcript = Transcript::<C>::new::<R>(self.params, &statement, &output_header);
+        let (native_challenges, nested_challenges) = transcript.claim_challenges();
+
+        // Split the products of the folded revdot claims.
+        let [native_low, native_high] = {
+            let mut builder = claims::Builder::new(
+                &self.native_registry,
+                self.num_application_steps,
+                native_challenges.y,
+                native_challenges.z,
+            );
+            claims::native::build(&verify::native::SingleProofSource { proof }, &mut builder)?;
+
+            split_product(&builder.a, &builder.b, native_challenges.fold)
+                .map(|coeffs| Committed::commit(coeffs, host_generators, &mut *rng))
+        };
+        let [nested_low, nested_high] = {
+            let mut builder = claims::Builder::new(
+                &self.nested_registry,
+                0,
+                nested_challenges.y,
+                nested_challenges.z,
+            );
+            claims::nested::build(&verify::nested::SingleProofSource { proof }, &mut builder)?;
+
+            split_product(&builder.a, &builder.b, nested_challenges.fold)
+                .map(|coeffs| Committed::commit(coeffs, nested_generators, &mut *rng))
+        };
+
+        let products = Products {
+            native_low_commitment: native_low.commitment,
+            native_high_commitment: native_high.commitment,
+            nested_low_commitment: nested_low.commitment,
+            nested_high_commitment: nested_high.commitment,
+        };
+        transcript.absorb(&products);
+        let (r, r_nested) = transcript.point_challenges();
+        let zr = native_challenges.z * r;
+        let zr_nested = nested_challenges.z * r_nested;
+
+        let evaluations = Evaluations {
+            native_rx_at_r: native_rxs.iter().map(|rx| rx.eval(r)).collect(),
+            native_rx_at_zr: native_rxs.iter().map(|rx| rx.eval(zr)).collect(),
+            native_low_at_r: native_low.eval(r),
+            native_high_at_r: native_high.eval(r),
+            registry_xy_at_r: registry[0].eval(r),
+            registry_wx0_at_r: registry[1].eval(r),
+            registry_wx1_at_r: registry[2].eval(r),
+            registry_wy_at_r: registry[3].eval(r),
+            child_x: preamble::child_x::<C, R, HEADER_SIZE>(&proof.preamble.native_rx),
+            nested_rx_at_r: nested_rxs.iter().map(|rx| rx.eval(r_nested)).collect(),
+            nested_rx_at_zr: nested_rxs.iter().map(|rx| rx.eval(zr_nested)).collect(),
+            nested_low_at_r: nested_low.eval(r_nested),
+            nested_high_at_r: nested_high.eval(r_nested),
+        };
+        transcript.absorb(&evaluations);
+        let batch = transcript.batch_challenges();
+
+        let host = Opener {
+            generators: host_generators,
+            poseidon: C::scalar_poseidon(self.params),
+            context: &batch.context,
+            rho: batch.rho,
+        };
+        let nested = Opener {
+            generators: nested_generators,
+            poseidon: C::circuit_poseidon(self.params),
+            context: &batch.context,
+            rho: batch.rho_nested,
+        };
+        let p = Committed::new(proof.p.poly.to_vec(), proof.p.blind, proof.p.commitment);
+
+        let openings = Openings {
+            native_at_r: host.open(
+                native_rxs
+                    .iter()
+                    .chain([&native_low, &native_high])
+                    .chain(&registry),
+                &ipa::powers(r, R::num_coeffs()),
+                rng,
+            )?,
+            native_at_zr: host.open(&native_rxs, &ipa::powers(zr, R::num_coeffs()), rng)?,
+            child_x: host.open(
+                [&native_rxs[claims::native::RxComponent::Preamble as usize]],
+                &child_x_vector::<C, R, HEADER_SIZE>(batch.rho),
+                rng,
+            )?,
+            p: host.open([&p], &ipa::powers(proof.challenges.u, R::num_coeffs()), rng)?,
+            nested_at_r: nested.open(
+                nested_rxs.iter().chain([&nested_low, &nested_high]),
+                &ipa::powers(r_nested, R::num_coeffs()),
+                rng,
+            )?,
+            nested_at_zr: nested.open(
+                &nested_rxs,
+                &ipa::powers(zr_nested, R::num_coeffs()),
+                rng,
+            )?,
+        };
+
+        Ok(CompressedPcd {
+            proof: CompressedProof {
+                statement,
+                products,
+                evaluations,
+                openings,
+                _marker: PhantomData,
+            },
+            data: pcd.data,
+        })
+    }
+
+    /// Verifies some [`CompressedPcd`] for the provided [`Header`].
+    ///
+    /// This checks the same claims as [`Application::verify`], through the
+    /// evaluations and inner product arguments of the compressed proof. The
+    /// verifier's challenges are derived from the compressed proof, so that
+    /// the result is a deterministic function of its inputs.
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if the proof was created by a
+    /// different [`Application`].
+    pub fn verify_compressed<H: Header<C::CircuitField>>(
+        &self,
+        pcd: &CompressedPcd<'_, C, R, H>,
+    ) -> Result<bool>
+    where
+        C::HostGenerators: OpeningGenerators<C::HostCurve>,
+        C::NestedGenerators: OpeningGenerators<C::NestedCurve>,
+    {
+        let CompressedProof {
+            statement,
+            products,
+            evaluations,
+            openings,
+            ..
+        } = &pcd.proof;
+        if statement.digest != self.digest {
+            return Err(Error::ApplicationMismatch);
+        }
+        let output_header = padded::encode::<H, HEADER_SIZE, _>(pcd.data.clone())?;
+
+        // See `Verifier::fold_claims` for the corresponding checks of an
+        // uncompressed proof.
+        if !self.native_registry.circuit_in_domain(statement.circuit_id)
+            || statement.left_header.len() != HEADER_SIZE
+            || statement.right_header.len() != HEADER_SIZE
+        {
+            return Ok(false);
+        }
+
+        let mut transcript = Transcript::<C>::new::<R>(self.params, statement, &output_header);
+        let (native_challenges, nested_challenges) = transcript.claim_challenges();
+        transcript.absorb(products);
+        let (r, r_nested) = transcript.point_challenges();
+        transcript.absorb(evaluations);
+        let batch = transcript.batch_challenges();
+
+        // Check the folded revdot claims.
+        let native_holds = {
+            let mut evaluator = Evaluator::new(
+                &self.native_registry,
+                self.num_application_steps,
+                &native_challenges,
+                r,
+            );
+            claims::native::build(
+                &EvaluationSource::<_, claims::native::RxComponent>::new(
+                    &evaluations.native_rx_at_r,
+                    &evaluations.native_rx_at_zr,
+                    statement.circuit_id,
+                ),
+                &mut evaluator,
+            )?;
+
+            let ky = verify::native::SingleProofKySource::new::<C, HEADER_SIZE>(
+                [&statement.left_header, &statement.right_header],
+                &output_header,
+                statement.circuit_id,
+                &statement.instance,
+                native_challenges.y,
+            )?;
+            evaluator.holds(
+                verify::native::ky_values(&ky),
+                evaluations.native_low_at_r,
+                evaluations.native_high_at_r,
+            )
+        };
+        let nested_holds = {
+            let mut evaluator =
+                Evaluator::new(&self.nested_registry, 0, &nested_challenges, r_nested);
+            claims::nested::build(
+                &EvaluationSource::<_, claims::nested::RxComponent>::new(
+                    &evaluations.nested_rx_at_r,
+                    &evaluations.nested_rx_at_zr,
+                    statement.circuit_id,
+                ),
+                &mut evaluator,
+            )?;
+
+            let ky = verify::nested::SingleProofKySource::<C::ScalarField>::new();
+            evaluator.holds(
+                verify::nested::ky_values(&ky),
+                evaluations.nested_low_at_r,
+                evaluations.nested_high_at_r,
+            )
+        };
+
+        // Check the registry polynomials at r.
+        let registry_holds = {
+            let instance = &statement.instance;
+            let [x0, x1] = evaluations.child_x;
+            let registry = &self.native_registry;
+
+            evaluations.registry_xy_at_r == registry.wxy(r, instance.x, instance.y)
+                && evaluations.registry_wx0_at_r == registry.wxy(instance.w, x0, r)
+                && evaluations.registry_wx1_at_r == registry.wxy(instance.w, x1, r)
+                && evaluations.registry_wy_at_r == registry.wxy(instance.w, r, instance.y)
+        };
+
+        if !(native_holds && nested_holds && registry_holds) {
+            return Ok(false);
+        }
+
+        // Check the evaluations against the commitments.
+        let host = Opener {
+            generators: C::host_generators(self.params),
+            poseidon: C::scalar_poseidon(self.params),
+            context: &batch.context,
+            rho: batch.rho,
+        };
+        let nested = Opener {
+            generators: C::nested_generators(self.params),
+            poseidon: C::circuit_poseidon(self.params),
+            context: &batch.context,
+            rho: batch.rho_nested,
+        };
+        let zr = native_challenges.z * r;
+        let zr_nested = nested_challenges.z * r_nested;
+        let preamble = claims::native::RxComponent::Preamble as usize;
+
+        Ok(host.verify(
+            statement
+                .native_rx_commitments
+                .iter()
+                .copied()
+                .zip(evaluations.native_rx_at_r.iter().copied())
+                .chain([
+                    (products.native_low_commitment, evaluations.native_low_at_r),
+                    (
+                        products.native_high_commitment,
+                        evaluations.native_high_at_r,
+                    ),
+                    (
+                        statement.registry_xy_commitment,
+                        evaluations.registry_xy_at_r,
+                    ),
+                    (
+                        statement.registry_wx0_commitment,
+                        evaluations.registry_wx0_at_r,
+                    ),
+                    (
+                        statement.registry_wx1_commitment,
+                        evaluations.registry_wx1_at_r,
+                    ),
+                    (
+                        statement.registry_wy_commitment,
+                        evaluations.registry_wy_at_r,
+                    ),
+                ]),
+            &ipa::powers(r, R::num_coeffs()),
+            &openings.native_at_r,
+        )? && host.verify(
+            statement
+                .native_rx_commitments
+                .iter()
+                .copied()
+                .zip(evaluations.native_rx_at_zr.iter().copied()),
+            &ipa::powers(zr, R::num_coeffs()),
+            &openings.native_at_zr,
+        )? && host.verify(
+            [(
+                statement.native_rx_commitments[preamble],
+                evaluations.child_x[0] + batch.rho * evaluations.child_x[1],
+            )],
+            &child_x_vector::<C, R, HEADER_SIZE>(batch.rho),
+            &openings.child_x,
+        )? && host.verify(
+            [(statement.p_commitment, statement.instance.v)],
+            &ipa::powers(statement.instance.u, R::num_coeffs()),
+            &openings.p,
+        )? && nested.verify(
+            statement
+                .nested_rx_commitments
+                .iter()
+                .copied()
+                .zip(evaluations.nested_rx_at_r.iter().copied())
+                .chain([
+                    (products.nested_low_commitment, evaluations.nested_low_at_r),
+                    (
+                        products.nested_high_commitment,
+                        evaluations.nested_high_at_r,
+                    ),
+                ]),
+            &ipa::powers(r_nested, R::num_coeffs()),
+            &openings.nested_at_r,
+        )? && nested.verify(
+            statement
+                .nested_rx_commitments
+                .iter()
+                .copied()
+                .zip(evaluations.nested_rx_at_zr.iter().copied()),
+            &ipa::powers(zr_nested, R::num_coeffs()),
+            &openings.nested_at_zr,
+        )?)
+    }
+}
+
+/// Challenges with which the revdot claims over one field are evaluated and
+/// folded.
+struct ClaimChallenges<F> {
+    y: F,
+    z: F,
+    fold: F,
+}
+
+/// Challenges with which the evaluation claims at each point are batched.
+struct BatchChallenges<C: Cycle> {
+    rho: C::CircuitField,
+    rho_nested: C::ScalarField,
+    /// Binds the inner product arguments to the rest of the transcript.
+    context: Vec<u8>,
+}
+
+/// Fiat-Shamir transcript of a [`CompressedProof`], which is its encoding up
+/// to the current point with the output header inserted after the statement.
+///
+/// Challenges in the scalar field are derived from a final challenge in the
+/// circuit field.
+struct Transcript<'a, C: Cycle> {
+    params: &'a C::Params,
+    writer: encoding::Writer,
+}
+
+impl<'a, C: Cycle> Transcript<'a, C> {
+    fn new<R: Rank>(
+        params: &'a C::Params,
+        statement: &Statement<C>,
+        output_header: &[C::CircuitField],
+    ) -> Self {
+        let mut writer = encoding::Writer::new();
+        encoding::write_version::<R>(&mut writer);
+        statement.encode(&mut writer);
+        writer.fields_exact(output_header);
+
+        Transcript { params, writer }
+    }
+
+    fn absorb(&mut self, component: &impl Encode) {
+        component.encode(&mut self.writer);
+    }
+
+    fn squeeze<const N: usize>(&self) -> [C::CircuitField; N] {
+        let bytes = self.writer.as_bytes();
+        hash(
+            C::circuit_poseidon(self.params),
+            once(C::CircuitField::from(bytes.len() as u64)).chain(limbs(bytes)),
+        )
+    }
+
+    fn squeeze_nested<const N: usize>(&self, seed: C::CircuitField) -> [C::ScalarField; N] {
+        hash(
+            C::scalar_poseidon(self.params),
+            limbs(seed.to_repr().as_ref()),
+        )
+    }
+
+    fn claim_challenges(
+        &self,
+    ) -> (
+        ClaimChallenges<C::CircuitField>,
+        ClaimChallenges<C::ScalarField>,
+    ) {
+        let [y, z, fold, seed] = self.squeeze();
+        let [y_nested, z_nested, fold_nested] = self.squeeze_nested(seed);
+
+        (
+            ClaimChallenges { y, z, fold },
+            ClaimChallenges {
+                y: y_nested,
+                z: z_nested,
+                fold: fold_nested,
+            },
+        )
+    }
+
+    fn point_challenges(&self) -> (C::CircuitField, C::ScalarField) {
+        let [r, seed] = self.squeeze();
+        let [r_nested] = self.squeeze_nested(seed);
+
+        (r, r_nested)
+    }
+
+    fn batch_challenges(&self) -> BatchChallenges<C> {
+        let [rho, seed] = self.squeeze();
+        let [rho_nested] = self.squeeze_nested(seed);
+
+        BatchChallenges {
+            rho,
+            rho_nested,
+            context: seed.to_repr().as_ref().to_vec(),
+        }
+    }
+}
+
+/// A polynomial in coefficient form, with the blinding factor of its
+/// commitment.
+struct Committed<G: CurveAffine> {
+    coeffs: Vec<G::ScalarExt>,
+    blind: G::ScalarExt,
+    commitment: G,
+}
+
+impl<G: CurveAffine> Committed<G> {
+    fn new(coeffs: Vec<G::ScalarExt>, blind: G::ScalarExt, commitment: G) -> Self {
+        Committed {
+            coeffs,
+            blind,
+            commitment,
+        }
+    }
+
+    fn structured<R: Rank>(
+        poly: &structured::Polynomial<G::ScalarExt, R>,
+        blind: G::ScalarExt,
+        commitment: G,
+    ) -> Self {
+        Self::new(poly.iter_coeffs().collect(), blind, commitment)
+    }
+
+    fn commit<RNG: Rng>(
+        coeffs: Vec<G::ScalarExt>,
+        generators: &impl arithmetic::FixedGenerators<G>,
+        rng: &mut RNG,
+    ) -> Self {
+        let blind = G::ScalarExt::random(rng);
+        let commitment = arithmetic::mul(
+            coeffs.iter().chain([&blind]),
+            generators.g()[..coeffs.len()]
+                .iter()
+                .chain([generators.h()]),
+        )
+        .into();
+
+        Self::new(coeffs, blind, commitment)
+    }
+
+    fn eval(&self, x: G::ScalarExt) -> G::ScalarExt {
+        arithmetic::eval(&self.coeffs, x)
+    }
+}
+
+/// Returns the coefficients of $L(X)$ and $H(X)$ for the revdot claims
+/// $\langle \mathbf{a}_i, \mathbf{b}_i \rangle$ folded by `fold`, as
+/// described in the module documentation.
+fn split_product<F: PrimeField, R: Rank>(
+    a: &[Cow<'_, structured::Polynomial<F, R>>],
+    b: &[Cow<'_, structured::Polynomial<F, R>>],
+    fold: F,
+) -> [Vec<F>; 2] {
+    let n = R::num_coeffs();
+    let domain = Domain::new(R::RANK + 1);
+    let evals = |poly: &structured::Polynomial<F, R>| {
+        let mut evals: Vec<_> = poly
+            .iter_coeffs()
+            .chain(core::iter::repeat_n(F::ZERO, n))
+            .collect();
+        domain.fft(&mut evals);
+        evals
+    };
+
+    let mut product = vec![F::ZERO; 2 * n];
+    for (a, b) in a.iter().zip(b) {
+        for ((product, a), b) in product.iter_mut().zip(evals(a)).zip(evals(b)) {
+            *product = *product * fold + a * b;
+        }
+    }
+    domain.ifft(&mut product);
+
+    // The coefficient of X^{N - 1} is the claimed value, and is omitted.
+    let high = product.split_off(n);
+    product.pop();
+    product.insert(0, F::ZERO);
+
+    [product, high]
+}
+
+/// Processor that evaluates the revdot claims at a point $r$ from the
+/// evaluations of the rx polynomials at $r$ and $zr$.
+struct Evaluator<'m, F: PrimeField, R: Rank> {
+    registry: &'m Registry<'m, F, R>,
+    num_application_steps: usize,
+    challenges: &'m ClaimChallenges<F>,
+    r: F,
+    txz: F,
+    a: Vec<F>,
+    b: Vec<F>,
+}
+
+impl<'m, F: PrimeField, R: Rank> Evaluator<'m, F, R> {
+    fn new(
+        registry: &'m Registry<'m, F, R>,
+        num_application_steps: usize,
+        challenges: &'m ClaimChallenges<F>,
+        r: F,
+    ) -> Self {
+        Evaluator {
+            registry,
+            num_application_steps,
+            challenges,
+            r,
+            txz: R::txz(r, challenges.z),
+            a: Vec::new(),
+            b: Vec::new(),
+        }
+    }
+
+    fn sy(&self, circuit_id: CircuitIndex) -> F {
+        self.registry
+            .wxy(circuit_id.omega_j(), self.r, self.challenges.y)
+    }
+
+    fn circuit_impl(&mut self, circuit_id: CircuitIndex, (at_r, at_zr): (F, F)) {
+        self.a.push(at_r);
+        self.b.push(at_zr + self.sy(circuit_id) + self.txz);
+    }
+
+    fn stage_impl(&mut self, circuit_id: CircuitIndex, rxs: impl Iterator<Item = (F, F)>) {
+        let z = self.challenges.z;
+        self.a
+            .push(rxs.fold(F::ZERO, |acc, (at_r, _)| acc * z + at_r));
+        self.b.push(self.sy(circuit_id));
+    }
+
+    /// Checks $r \cdot T(r) = L(r) + K r^N + r^{N + 1} H(r)$ for the claims
+    /// evaluated so far, given their values `ky`.
+    fn holds(&self, ky: impl Iterator<Item = F>, low: F, high: F) -> bool {
+        let fold = self.challenges.fold;
+        let (t, k) = self
+            .a
+            .iter()
+            .zip(&self.b)
+            .zip(ky)
+            .fold((F::ZERO, F::ZERO), |(t, k), ((a, b), ky)| {
+                (t * fold + *a * b, k * fold + ky)
+            });
+        let r_n = self.r.pow_vartime([R::num_coeffs() as u64]);
+
+        self.r * t == low + k * r_n + r_n * self.r * high
+    }
+}
+
+fn sum_rxs<F: Field>(rxs: impl Iterator<Item = (F, F)>) -> (F, F) {
+    rxs.fold((F::ZERO, F::ZERO), |(r, zr), (at_r, at_zr)| {
+        (r + at_r, zr + at_zr)
+    })
+}
+
+impl<F: PrimeField, R: Rank> claims::native::Processor<(F, F), CircuitIndex>
+    for Evaluator<'_, F, R>
+{
+    fn raw_claim(&mut self, a: (F, F), b: (F, F)) {
+        self.a.push(a.0);
+        self.b.push(b.0);
+    }
+
+    fn circuit(&mut self, circuit_id: CircuitIndex, rx: (F, F)) {
+        self.circuit_impl(circuit_id, rx);
+    }
+
+    fn internal_circuit(
+        &mut self,
+        id: native::InternalCircuitIndex,
+        rxs: impl Iterator<Item = (F, F)>,
+    ) {
+        let circuit_id = id.circuit_index(self.num_application_steps);
+        self.circuit_impl(circuit_id, sum_rxs(rxs));
+    }
+
+    fn stage(
+        &mut self,
+        id: native::InternalCircuitIndex,
+        rxs: impl Iterator<Item = (F, F)>,
+    ) -> Result<()> {
+        let circuit_id = id.circuit_index(self.num_application_steps);
+        self.stage_impl(circuit_id, rxs);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField, R: Rank> claims::nested::Processor<(F, F)> for Evaluator<'_, F, R> {
+    fn internal_circuit(
+        &mut self,
+        id: nested::InternalCircuitIndex,
+        rxs: impl Iterator<Item = (F, F)>,
+    ) {
+        self.circuit_impl(id.circuit_index(), sum_rxs(rxs));
+    }
+
+    fn stage(
+        &mut self,
+        id: nested::InternalCircuitIndex,
+        rxs: impl Iterator<Item = (F, F)>,
+    ) -> Result<()> {
+        self.stage_impl(id.circuit_index(), rxs);
+        Ok(())
+    }
+}
+
+/// Source of the evaluations of the rx polynomials of a compressed proof at
+/// $r$ and $zr$, indexed by the rx components `K`.
+struct EvaluationSource<'a, F, K> {
+    at_r: &'a [F],
+    at_zr: &'a [F],
+    circuit_id: CircuitIndex,
+    _marker: PhantomData<K>,
+}
+
+impl<'a, F, K> EvaluationSource<'a, F, K> {
+    fn new(at_r: &'a [F], at_zr: &'a [F], circuit_id: CircuitIndex) -> Self {
+        EvaluationSource {
+            at_r,
+            at_zr,
+            circuit_id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Source for EvaluationSource<'_, F, claims::native::RxComponent> {
+    type RxComponent = claims::native::RxComponent;
+    type Rx = (F, F);
+    type AppCircuitId = CircuitIndex;
+
+    fn rx(&self, component: Self::RxComponent) -> impl Iterator<Item = Self::Rx> {
+        let index = component as usize;
+        once((self.at_r[index], self.at_zr[index]))
+    }
+
+    fn app_circuits(&self) -> impl Iterator<Item = Self::AppCircuitId> {
+        once(self.circuit_id)
+    }
+}
+
+impl<F: Field> Source for EvaluationSource<'_, F, claims::nested::RxComponent> {
+    type RxComponent = claims::nested::RxComponent;
+    type Rx = (F, F);
+    type AppCircuitId = ();
+
+    fn rx(&self, component: Self::RxComponent) -> impl Iterator<Item = Self::Rx> {
+        let index = component.index();
+        once((self.at_r[index], self.at_zr[index]))
+    }
+
+    fn app_circuits(&self) -> impl Iterator<Item = Self::AppCircuitId> {
+        core::iter::empty()
+    }
+}
+
+/// Batches the evaluation claims at a point with powers of `rho`, and opens
+/// or verifies them with an [inner product argument](ipa).
+struct Opener<'a, G: CurveAffine, Gens, P> {
+    generators: &'a Gens,
+    poseidon: &'a P,
+    context: &'a [u8],
+    rho: G::ScalarExt,
+}
+
+impl<G: CurveAffine, Gens: OpeningGenerators<G>, P: arithmetic::PoseidonPermutation<G::Base>>
+    Opener<'_, G, Gens, P>
+{
+    fn open<'p, RNG: Rng>(
+        &self,
+        polys: impl IntoIterator<Item = &'p Committed<G>>,
+        b: &[G::ScalarExt],
+        rng: &mut RNG,
+    ) -> Result<ipa::Opening<G>>
+    where
+        G: 'p,
+    {
+        let mut coeffs = vec![G::ScalarExt::ZERO; b.len()];
+        let mut blind = G::ScalarExt::ZERO;
+        let mut commitments = Vec::new();
+        let mut power = G::ScalarExt::ONE;
+        for poly in polys {
+            for (acc, coeff) in coeffs.iter_mut().zip(&poly.coeffs) {
+                *acc += power * coeff;
+            }
+            blind += power * poly.blind;
+            commitments.push(poly.commitment);
+            power *= self.rho;
+        }
+
+        let statement = ipa::Statement {
+            context: self.context,
+            commitment: self.batch(&commitments),
+            b,
+            v: arithmetic::dot(&coeffs, b),
+        };
+        ipa::open(
+            self.generators,
+            self.poseidon,
+            &statement,
+            &coeffs,
+            blind,
+            rng,
+        )
+    }
+
+    fn verify(
+        &self,
+        claims: impl IntoIterator<Item = (G, G::ScalarExt)>,
+        b: &[G::ScalarExt],
+        opening: &ipa::Opening<G>,
+    ) -> Result<bool> {
+        let (commitments, values): (Vec<_>, Vec<_>) = claims.into_iter().unzip();
+        let statement = ipa::Statement {
+            context: self.context,
+            commitment: self.batch(&commitments),
+            b,
+            v: arithmetic::dot(&ipa::powers(self.rho, values.len()), &values),
+        };
+        ipa::verify(self.generators, self.poseidon, &statement, opening)
+    }
+
+    fn batch(&self, commitments: &[G]) -> G {
+        arithmetic::mul(&ipa::powers(self.rho, commitments.len()), commitments).into()
+    }
+}
+
+/// Returns the public vector with which the coefficients of the preamble's rx
+/// polynomial holding the child $x$ challenges are opened, batched by `rho`.
+fn child_x_vector<C: Cycle, R: Rank, const HEADER_SIZE: usize>(
+    rho: C::CircuitField,
+) -> Vec<C::CircuitField> {
+    let [left, right] = preamble::child_x_degrees::<C, R, HEADER_SIZE>();
+    let mut b = vec![C::CircuitField::ZERO; R::num_coeffs()];
+    b[left] += C::CircuitField::ONE;
+    b[right] += rho;
+    b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ragu_circuits::polynomials::R;
+    use ragu_pasta::Fp;
+
+    type TestRank = R<8>;
+
+    #[test]
+    fn test_split_product() {
+        let mut rng = rand::thread_rng();
+        let polys = |rng: &mut _| -> Vec<_> {
+            (0..3)
+                .map(|_| Cow::Owned(structured::Polynomial::<Fp, TestRank>::random(rng)))
+                .collect()
+        };
+        let (a, b) = (polys(&mut rng), polys(&mut rng));
+        let fold = Fp::random(&mut rng);
+        let r = Fp::random(&mut rng);
+        let [low, high] = split_product(&a, &b, fold);
+
+        let t = a
+            .iter()
+            .zip(&b)
+            .fold(Fp::ZERO, |t, (a, b)| t * fold + a.eval(r) * b.eval(r));
+        let k = a
+            .iter()
+            .zip(&b)
+            .fold(Fp::ZERO, |k, (a, b)| k * fold + a.revdot(b));
+        let r_n = r.pow_vartime([TestRank::num_coeffs() as u64]);
+
+        assert_eq!(
+            r * t,
+            arithmetic::eval(&low, r) + k * r_n + r_n * r * arithmetic::eval(&high, r)
+        );
+        assert_ne!(
+            r * t,
+            arithmetic::eval(&low, r) + (k + Fp::ONE) * r_n + r_n * r * arithmetic::eval(&high, r)
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Deterministic proving, for reproducible proofs and test vectors.
+//!
+//! [`Application::fuse`] and the methods built on it sample every blinding
+//! factor from the provided random number generator, so that proving the same
+//! step twice never produces the same proof. A [`Deterministic`] prover,
+//! obtained from [`Application::deterministic`], instead derives every blinding
+//! factor from a caller-provided seed and the transcript of the proof being
+//! created: the application digest, the child proofs and a commitment to the
+//! step's witness. Proving the same step with the same seed, witness and child
+//! proofs therefore always produces byte-identical proofs.
+//!
+//! The blinding factors are only as unpredictable as the seed. Proofs that
+//! must hide their witness (such as the output of
+//! [`Deterministic::rerandomize`]) should use a secret seed, or be created with
+//! the randomized methods of [`Application`] instead.
+
+use arithmetic::Cycle;
+use ff::{Field, PrimeField};
+use pasta_curves::group::GroupEncoding;
+use ragu_circuits::polynomials::{Rank, structured};
+use ragu_core::Result;
+use rand::{Error, RngCore};
+
+use alloc::vec::Vec;
+use core::iter::once;
+
+use crate::{
+    Application, Pcd, Proof, hash,
+    header::Header,
+    limbs,
+    step::{
+        Step, UnaryStep,
+        internal::{rerandomize::Rerandomize, unary::Unary},
+    },
+};
+
+/// Creates proof-carrying data for an [`Application`] without a random number
+/// generator.
+///
+/// See the [module documentation](self) for details.
+pub struct Deterministic<'app, 'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    app: &'app Application<'params, C, R, HEADER_SIZE>,
+    seed: [u8; 32],
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'params, C, R, HEADER_SIZE> {
+    /// Returns a [`Deterministic`] prover for this application, which derives
+    /// all blinding factors from `seed` and the transcript of each proof.
+    pub fn deterministic(&self, seed: [u8; 32]) -> Deterministic<'_, 'params, C, R, HEADER_SIZE> {
+        Deterministic { app: self, seed }
+    }
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Deterministic<'_, '_, C, R, HEADER_SIZE> {
+    /// Seed a new computation by running a step with trivial inputs, as
+    /// [`Application::seed`] would.
+    pub fn seed<'source, S: Step<C, Left = (), Right = ()>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        self.fuse(
+            step,
+            witness,
+            self.app.trivial_pcd(),
+            self.app.trivial_pcd(),
+        )
+    }
+
+    /// Seed a new incremental computation by running a [`UnaryStep`] on a
+    /// trivial input, as [`Application::seed_unary`] would.
+    pub fn seed_unary<'source, S: UnaryStep<C, Input = ()>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        self.fuse(
+            Unary::new(step),
+            witness,
+            self.app.trivial_pcd(),
+            self.app.trivial_pcd(),
+        )
+    }
+
+    /// Fuse two [`Pcd`] into one using a provided [`Step`], as
+    /// [`Application::fuse`] would.
+    pub fn fuse<'source, S: Step<C>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+        left: Pcd<'source, C, R, S::Left>,
+        right: Pcd<'source, C, R, S::Right>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        let rng = self.rng(&left.proof, &right.proof);
+        self.app.fuse_with(rng, step, witness, left, right)
+    }
+
+    /// Extend some [`Pcd`] with a [`UnaryStep`], as [`Application::extend`]
+    /// would.
+    pub fn extend<'source, S: UnaryStep<C>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+        input: Pcd<'source, C, R, S::Input>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        self.fuse(Unary::new(step), witness, input, self.app.trivial_pcd())
+    }
+
+    /// Rerandomize proof-carrying data, as [`Application::rerandomize`] would.
+    ///
+    /// The result hides the original proof only if the seed is secret.
+    pub fn rerandomize<'source, H: Header<C::CircuitField>>(
+        &self,
+        pcd: Pcd<'source, C, R, H>,
+    ) -> Result<Pcd<'source, C, R, H>> {
+        let data = pcd.data.clone();
+        let (proof, ()) = self.fuse(Rerandomize::new(), (), pcd, self.app.trivial_pcd())?;
+
+        Ok(proof.carry(data))
+    }
+
+    /// Returns a function that creates the random number generator for a proof
+    /// with the provided children from the (unblinded) commitment to its
+    /// application `rx` polynomial.
+    fn rng<'a>(
+        &'a self,
+        left: &Proof<C, R>,
+        right: &Proof<C, R>,
+    ) -> impl FnOnce(&structured::Polynomial<C::CircuitField, R>) -> TranscriptRng<'a, C> + 'a {
+        let children = [left.application.commitment, right.application.commitment];
+
+        move |rx| {
+            let rx_commitment =
+                rx.commit(C::host_generators(self.app.params), C::CircuitField::ZERO);
+            let mut commitments = Vec::new();
+            for commitment in children.iter().chain(once(&rx_commitment)) {
+                commitments.extend_from_slice(commitment.to_bytes().as_ref());
+            }
+
+            let inputs = limbs(&self.seed)
+                .chain(once(self.app.digest))
+                .chain(limbs(&commitments));
+            let [key] = hash(C::circuit_poseidon(self.app.params), inputs);
+
+            TranscriptRng {
+                params: self.app.params,
+                key,
+                counter: 0,
+            }
+        }
+    }
+}
+
+/// A random number generator that hashes a key, derived from the seed and the
+/// transcript, together with a counter using the Poseidon sponge.
+struct TranscriptRng<'params, C: Cycle> {
+    params: &'params C::Params,
+    key: C::CircuitField,
+    counter: u64,
+}
+
+impl<C: Cycle> RngCore for TranscriptRng<'_, C> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // Only the low 128 bits of each output are used, which are uniformly
+        // distributed up to a negligible bias.
+        for chunk in dest.chunks_mut(16) {
+            let [block] = hash(
+                C::circuit_poseidon(self.params),
+                [self.key, C::CircuitField::from(self.counter)],
+            );
+            self.counter += 1;
+            chunk.copy_from_slice(&block.to_repr().as_ref()[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> core::result::Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ragu_circuits::polynomials::R;
+    use ragu_core::Result;
+    use ragu_pasta::Pasta;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{ApplicationBuilder, step::internal::trivial::Trivial};
+
+    #[test]
+    fn test_deterministic_proofs() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let prover = app.deterministic([1; 32]);
+        let (proof, ()) = prover.seed(Trivial::new(), ())?;
+        let pcd = proof.carry::<()>(());
+        assert!(app.verify(&pcd, &mut rng)?);
+
+        // The same seed and inputs reproduce the same proof, and a different
+        // seed does not.
+        assert_eq!(
+            prover.seed(Trivial::new(), ())?.0.to_bytes(),
+            pcd.proof.to_bytes()
+        );
+        assert_ne!(
+            app.deterministic([2; 32])
+                .seed(Trivial::new(), ())?
+                .0
+                .to_bytes(),
+            pcd.proof.to_bytes()
+        );
+
+        // Randomized proving in between does not affect the results.
+        app.rerandomize(pcd.clone(), &mut rng)?;
+        let rerandomized = prover.rerandomize(pcd.clone())?;
+        assert!(app.verify(&rerandomized, &mut rng)?);
+        assert_eq!(
+            prover.rerandomize(pcd)?.proof.to_bytes(),
+            rerandomized.proof.to_bytes()
+        );
+
+        Ok(())
+    }
+}
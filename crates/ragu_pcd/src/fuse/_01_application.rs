@@ -4,11 +4,15 @@
 //! the step witness. This produces the [`proof::Application`] component of the
 //! proof. The inputs are all consumed, and the `left` and `right proofs are
 //! returned to the caller along with the auxiliary data from the application
-//! synthesis.
+//! synthesis, and the random number generator for the rest of the proof, which
+//! is created from the step's `rx` polynomial before its blind is sampled.
 
 use arithmetic::Cycle;
 use ff::Field;
-use ragu_circuits::{CircuitExt, polynomials::Rank};
+use ragu_circuits::{
+    CircuitExt,
+    polynomials::{Rank, structured},
+};
 use ragu_core::Result;
 use rand::Rng;
 
@@ -20,33 +24,38 @@ use crate::{
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
     pub(super) fn compute_application_proof<'source, RNG: Rng, S: Step<C>>(
         &self,
-        rng: &mut RNG,
+        rng: impl FnOnce(&structured::Polynomial<C::CircuitField, R>) -> RNG,
         step: S,
         witness: S::Witness<'source>,
         left: Pcd<'source, C, R, S::Left>,
         right: Pcd<'source, C, R, S::Right>,
     ) -> Result<(
+        RNG,
         Proof<C, R>,
         Proof<C, R>,
         proof::Application<C, R>,
         S::Aux<'source>,
     )> {
+        let circuit_id = step.index().circuit_index(self.num_application_steps)?;
         let (rx, aux) = Adapter::<C, S, R, HEADER_SIZE>::new(step).rx::<R>(
             (left.data, right.data, witness),
             self.native_registry.get_key(),
         )?;
-        let blind = C::CircuitField::random(&mut *rng);
+        let mut rng = rng(&rx);
+        let blind = C::CircuitField::random(&mut rng);
         let commitment = rx.commit(C::host_generators(self.params), blind);
 
-        let ((left_header, right_header), aux) = aux;
+        let ((left_header, right_header, output_header), aux) = aux;
 
         Ok((
+            rng,
             left.proof,
             right.proof,
             proof::Application {
-                circuit_id: S::INDEX.circuit_index(self.num_application_steps)?,
+                circuit_id,
                 left_header: left_header.into_inner(),
                 right_header: right_header.into_inner(),
+                output_header: output_header.into_inner(),
                 rx,
                 blind,
                 commitment,
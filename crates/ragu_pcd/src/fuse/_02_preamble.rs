@@ -16,24 +16,14 @@ use crate::{
 };
 
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
-    pub(super) fn compute_preamble<'a, RNG: Rng>(
+    pub(super) fn compute_preamble<RNG: Rng>(
         &self,
         rng: &mut RNG,
-        left: &'a Proof<C, R>,
-        right: &'a Proof<C, R>,
-        application: &proof::Application<C, R>,
-    ) -> Result<(
-        proof::Preamble<C, R>,
-        native_preamble::Witness<'a, C, R, HEADER_SIZE>,
-    )> {
-        let preamble_witness = native_preamble::Witness::new(
-            left,
-            right,
-            &application.left_header,
-            &application.right_header,
-        )?;
-
-        let native_rx = native_preamble::Stage::<C, R, HEADER_SIZE>::rx(&preamble_witness)?;
+        left: &Proof<C, R>,
+        right: &Proof<C, R>,
+        preamble_witness: &native_preamble::Witness<'_, C, R, HEADER_SIZE>,
+    ) -> Result<proof::Preamble<C, R>> {
+        let native_rx = native_preamble::Stage::<C, R, HEADER_SIZE>::rx(preamble_witness)?;
         let native_blind = C::CircuitField::random(&mut *rng);
         let native_commitment = native_rx.commit(C::host_generators(self.params), native_blind);
 
@@ -48,16 +38,13 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         let nested_blind = C::ScalarField::random(&mut *rng);
         let nested_commitment = nested_rx.commit(C::nested_generators(self.params), nested_blind);
 
-        Ok((
-            proof::Preamble {
-                native_rx,
-                native_blind,
-                native_commitment,
-                nested_rx,
-                nested_blind,
-                nested_commitment,
-            },
-            preamble_witness,
-        ))
+        Ok(proof::Preamble {
+            native_rx,
+            native_blind,
+            native_commitment,
+            nested_rx,
+            nested_blind,
+            nested_commitment,
+        })
     }
 }
@@ -14,7 +14,7 @@ use ragu_core::{
 use ragu_primitives::Element;
 use rand::Rng;
 
-use crate::{Application, Proof, circuits::nested, proof};
+use crate::{Application, Proof, circuits::nested, parallel, proof};
 
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
     pub(super) fn compute_s_prime<'dr, D, RNG: Rng>(
@@ -31,14 +31,17 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         let x0 = left.challenges.x;
         let x1 = right.challenges.x;
 
-        let native_registry_wx0_poly = self.native_registry.wx(w, x0);
+        let registry = &self.native_registry;
+        let generators = C::host_generators(self.params);
+
+        let (native_registry_wx0_poly, native_registry_wx1_poly) =
+            parallel::join(|| registry.wx(w, x0), || registry.wx(w, x1));
         let native_registry_wx0_blind = C::CircuitField::random(&mut *rng);
-        let native_registry_wx0_commitment = native_registry_wx0_poly
-            .commit(C::host_generators(self.params), native_registry_wx0_blind);
-        let native_registry_wx1_poly = self.native_registry.wx(w, x1);
         let native_registry_wx1_blind = C::CircuitField::random(&mut *rng);
-        let native_registry_wx1_commitment = native_registry_wx1_poly
-            .commit(C::host_generators(self.params), native_registry_wx1_blind);
+        let (native_registry_wx0_commitment, native_registry_wx1_commitment) = parallel::join(
+            || native_registry_wx0_poly.commit(generators, native_registry_wx0_blind),
+            || native_registry_wx1_poly.commit(generators, native_registry_wx1_blind),
+        );
 
         let nested_s_prime_witness = nested::stages::s_prime::Witness {
             registry_wx0: native_registry_wx0_commitment,
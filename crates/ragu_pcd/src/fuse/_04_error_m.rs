@@ -25,7 +25,7 @@ use crate::{
         claims,
         fold_revdot::{self, NativeParameters},
     },
-    proof,
+    parallel, proof,
 };
 
 use super::FuseProofSource;
@@ -51,19 +51,28 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         let y = *y.value().take();
         let z = *z.value().take();
 
-        let registry_wy_poly = self.native_registry.wy(w, y);
+        // The m(w, X, y) restriction does not depend on the claims, so the two
+        // are computed concurrently.
+        let registry = &self.native_registry;
+        let num_application_steps = self.num_application_steps;
+        let (registry_wy_poly, claims) = parallel::join(
+            || registry.wy(w, y),
+            || -> Result<_> {
+                let source = FuseProofSource { left, right };
+                let mut builder = claims::Builder::new(registry, num_application_steps, y, z);
+                claims::native::build(&source, &mut builder)?;
+
+                let error_terms =
+                    fold_revdot::compute_errors_m::<_, R, NativeParameters>(&builder.a, &builder.b);
+                Ok((builder, error_terms))
+            },
+        );
+        let (builder, error_terms) = claims?;
+
         let registry_wy_blind = C::CircuitField::random(&mut *rng);
         let registry_wy_commitment =
             registry_wy_poly.commit(C::host_generators(self.params), registry_wy_blind);
 
-        let source = FuseProofSource { left, right };
-        let mut builder =
-            claims::Builder::new(&self.native_registry, self.num_application_steps, y, z);
-        claims::native::build(&source, &mut builder)?;
-
-        let error_terms =
-            fold_revdot::compute_errors_m::<_, R, NativeParameters>(&builder.a, &builder.b);
-
         let error_m_witness =
             native::stages::error_m::Witness::<C, NativeParameters> { error_terms };
         let native_rx = native::stages::error_m::Stage::<C, R, HEADER_SIZE, NativeParameters>::rx(
@@ -88,17 +88,25 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
                 let nu = Element::alloc(dr, nu)?;
 
                 // Build k(y) values in claim order.
-                let ky = claims::native::TwoProofKySource {
-                    left_raw_c: preamble.left.unified.c.clone(),
-                    right_raw_c: preamble.right.unified.c.clone(),
-                    left_app: left_application_ky.clone(),
-                    right_app: right_application_ky.clone(),
-                    left_bridge: left_unified_bridge_ky.clone(),
-                    right_bridge: right_unified_bridge_ky.clone(),
-                    left_unified: left_unified_ky.clone(),
-                    right_unified: right_unified_ky.clone(),
-                    zero: Element::zero(dr),
-                };
+                let left_is_trivial = preamble.left.is_trivial(dr)?;
+                let right_is_trivial = preamble.right.is_trivial(dr)?;
+                let ky = claims::native::TwoProofKySource::new(
+                    dr,
+                    &left_is_trivial,
+                    [
+                        &preamble.left.unified.c,
+                        &left_application_ky,
+                        &left_unified_bridge_ky,
+                        &left_unified_ky,
+                    ],
+                    &right_is_trivial,
+                    [
+                        &preamble.right.unified.c,
+                        &right_application_ky,
+                        &right_unified_bridge_ky,
+                        &right_unified_ky,
+                    ],
+                )?;
                 let mut ky = claims::native::ky_values(&ky);
 
                 let fold_products = fold_revdot::FoldProducts::new(dr, &mu, &nu)?;
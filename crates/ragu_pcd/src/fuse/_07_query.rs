@@ -10,7 +10,10 @@
 
 use arithmetic::Cycle;
 use ff::Field;
-use ragu_circuits::{polynomials::Rank, staging::StageExt};
+use ragu_circuits::{
+    polynomials::{Rank, structured, unstructured},
+    staging::StageExt,
+};
 use ragu_core::{
     Result,
     drivers::Driver,
@@ -21,7 +24,7 @@ use rand::Rng;
 
 use crate::{
     Application, Proof,
-    circuits::{self, native, native::stages::query, nested},
+    circuits::{native, native::stages::query, nested},
     proof,
 };
 use native::InternalCircuitIndex;
@@ -37,15 +40,10 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         error_m: &proof::ErrorM<C, R>,
         left: &Proof<C, R>,
         right: &Proof<C, R>,
-    ) -> Result<(
-        proof::Query<C, R>,
-        circuits::native::stages::query::Witness<C>,
-    )>
+    ) -> Result<proof::Query<C, R>>
     where
         D: Driver<'dr, F = C::CircuitField, MaybeKind = Always<()>>,
     {
-        use InternalCircuitIndex::*;
-
         let w = *w.value().take();
         let x = *x.value().take();
         let y = *y.value().take();
@@ -56,47 +54,16 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         let registry_xy_commitment =
             registry_xy_poly.commit(C::host_generators(self.params), registry_xy_blind);
 
-        let registry_at = |idx: InternalCircuitIndex| -> C::CircuitField {
-            let circuit_id = idx.circuit_index(self.num_application_steps);
-            registry_xy_poly.eval(circuit_id.omega_j())
-        };
-
-        let query_witness = query::Witness {
-            fixed_registry: query::FixedRegistryWitness {
-                // TODO: these can all be evaluated at the same time; in fact,
-                // that's what registry.xy is supposed to allow.
-                preamble_stage: registry_at(PreambleStage),
-                error_m_stage: registry_at(ErrorMStage),
-                error_n_stage: registry_at(ErrorNStage),
-                query_stage: registry_at(QueryStage),
-                eval_stage: registry_at(EvalStage),
-                error_m_final_staged: registry_at(ErrorMFinalStaged),
-                error_n_final_staged: registry_at(ErrorNFinalStaged),
-                eval_final_staged: registry_at(EvalFinalStaged),
-                hashes_1_circuit: registry_at(Hashes1Circuit),
-                hashes_2_circuit: registry_at(Hashes2Circuit),
-                partial_collapse_circuit: registry_at(PartialCollapseCircuit),
-                full_collapse_circuit: registry_at(FullCollapseCircuit),
-                compute_v_circuit: registry_at(ComputeVCircuit),
-            },
-            registry_wxy: registry_xy_poly.eval(w),
-            left: query::ChildEvaluationsWitness::from_proof(
-                left,
-                w,
-                x,
-                xz,
-                &registry_xy_poly,
-                &error_m.registry_wy_poly,
-            ),
-            right: query::ChildEvaluationsWitness::from_proof(
-                right,
-                w,
-                x,
-                xz,
-                &registry_xy_poly,
-                &error_m.registry_wy_poly,
-            ),
-        };
+        let query_witness = query_witness(
+            self.num_application_steps,
+            w,
+            x,
+            xz,
+            &registry_xy_poly,
+            &error_m.registry_wy_poly,
+            left,
+            right,
+        );
 
         let native_rx = query::Stage::<C, R, HEADER_SIZE>::rx(&query_witness)?;
         let native_blind = C::CircuitField::random(&mut *rng);
@@ -110,19 +77,73 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         let nested_blind = C::ScalarField::random(&mut *rng);
         let nested_commitment = nested_rx.commit(C::nested_generators(self.params), nested_blind);
 
-        Ok((
-            proof::Query {
-                registry_xy_poly,
-                registry_xy_blind,
-                registry_xy_commitment,
-                native_rx,
-                native_blind,
-                native_commitment,
-                nested_rx,
-                nested_blind,
-                nested_commitment,
-            },
-            query_witness,
-        ))
+        Ok(proof::Query {
+            registry_xy_poly,
+            registry_xy_blind,
+            registry_xy_commitment,
+            native_rx,
+            native_blind,
+            native_commitment,
+            nested_rx,
+            nested_blind,
+            nested_commitment,
+        })
+    }
+}
+
+/// Computes the witness for the query stage from the $m(X, x, y)$ restriction
+/// committed in this phase.
+pub(super) fn query_witness<C: Cycle, R: Rank>(
+    num_application_steps: usize,
+    w: C::CircuitField,
+    x: C::CircuitField,
+    xz: C::CircuitField,
+    registry_xy_poly: &unstructured::Polynomial<C::CircuitField, R>,
+    registry_wy_poly: &structured::Polynomial<C::CircuitField, R>,
+    left: &Proof<C, R>,
+    right: &Proof<C, R>,
+) -> query::Witness<C> {
+    use InternalCircuitIndex::*;
+
+    let registry_at = |idx: InternalCircuitIndex| -> C::CircuitField {
+        let circuit_id = idx.circuit_index(num_application_steps);
+        registry_xy_poly.eval(circuit_id.omega_j())
+    };
+
+    query::Witness {
+        fixed_registry: query::FixedRegistryWitness {
+            // TODO: these can all be evaluated at the same time; in fact,
+            // that's what registry.xy is supposed to allow.
+            preamble_stage: registry_at(PreambleStage),
+            error_m_stage: registry_at(ErrorMStage),
+            error_n_stage: registry_at(ErrorNStage),
+            query_stage: registry_at(QueryStage),
+            eval_stage: registry_at(EvalStage),
+            error_m_final_staged: registry_at(ErrorMFinalStaged),
+            error_n_final_staged: registry_at(ErrorNFinalStaged),
+            eval_final_staged: registry_at(EvalFinalStaged),
+            hashes_1_circuit: registry_at(Hashes1Circuit),
+            hashes_2_circuit: registry_at(Hashes2Circuit),
+            partial_collapse_circuit: registry_at(PartialCollapseCircuit),
+            full_collapse_circuit: registry_at(FullCollapseCircuit),
+            compute_v_circuit: registry_at(ComputeVCircuit),
+        },
+        registry_wxy: registry_xy_poly.eval(w),
+        left: query::ChildEvaluationsWitness::from_proof(
+            left,
+            w,
+            x,
+            xz,
+            registry_xy_poly,
+            registry_wy_poly,
+        ),
+        right: query::ChildEvaluationsWitness::from_proof(
+            right,
+            w,
+            x,
+            xz,
+            registry_xy_poly,
+            registry_wy_poly,
+        ),
     }
 }
@@ -8,7 +8,7 @@ use crate::{
     Application,
     circuits::{self, native, native::total_circuit_counts},
     components::fold_revdot::NativeParameters,
-    proof,
+    parallel, proof,
 };
 
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
@@ -55,10 +55,14 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
             v: p.v,
         };
 
-        let (hashes_1_rx, _) =
+        let params = self.params;
+        let num_application_steps = self.num_application_steps;
+        let registry_key = self.native_registry.get_key();
+
+        let hashes_1 = || {
             native::hashes_1::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new(
-                self.params,
-                total_circuit_counts(self.num_application_steps).1,
+                params,
+                total_circuit_counts(num_application_steps).1,
             )
             .rx::<R>(
                 native::hashes_1::Witness {
@@ -66,64 +70,83 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
                     preamble_witness,
                     error_n_witness,
                 },
-                self.native_registry.get_key(),
-            )?;
+                registry_key,
+            )
+        };
+        let hashes_2 = || {
+            native::hashes_2::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new(params).rx::<R>(
+                native::hashes_2::Witness {
+                    unified_instance,
+                    error_n_witness,
+                },
+                registry_key,
+            )
+        };
+        let partial_collapse = || {
+            native::partial_collapse::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new().rx::<R>(
+                native::partial_collapse::Witness {
+                    preamble_witness,
+                    unified_instance,
+                    error_m_witness,
+                    error_n_witness,
+                },
+                registry_key,
+            )
+        };
+        let full_collapse = || {
+            native::full_collapse::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new().rx::<R>(
+                native::full_collapse::Witness {
+                    unified_instance,
+                    preamble_witness,
+                    error_n_witness,
+                },
+                registry_key,
+            )
+        };
+        let compute_v = || {
+            native::compute_v::Circuit::<C, R, HEADER_SIZE>::new(num_application_steps).rx::<R>(
+                native::compute_v::Witness {
+                    unified_instance,
+                    preamble_witness,
+                    query_witness,
+                    eval_witness,
+                },
+                registry_key,
+            )
+        };
+
+        // The internal circuits are witnessed independently of one another.
+        let ((hashes_1, hashes_2), ((partial_collapse, full_collapse), compute_v)) = parallel::join(
+            || parallel::join(hashes_1, hashes_2),
+            || {
+                parallel::join(
+                    || parallel::join(partial_collapse, full_collapse),
+                    compute_v,
+                )
+            },
+        );
+
+        let (hashes_1_rx, _) = hashes_1?;
         let hashes_1_rx_blind = C::CircuitField::random(&mut *rng);
         let hashes_1_rx_commitment =
             hashes_1_rx.commit(C::host_generators(self.params), hashes_1_rx_blind);
 
-        let (hashes_2_rx, _) =
-            native::hashes_2::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new(self.params)
-                .rx::<R>(
-                    native::hashes_2::Witness {
-                        unified_instance,
-                        error_n_witness,
-                    },
-                    self.native_registry.get_key(),
-                )?;
+        let (hashes_2_rx, _) = hashes_2?;
         let hashes_2_rx_blind = C::CircuitField::random(&mut *rng);
         let hashes_2_rx_commitment =
             hashes_2_rx.commit(C::host_generators(self.params), hashes_2_rx_blind);
 
-        let (partial_collapse_rx, _) =
-            native::partial_collapse::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new()
-                .rx::<R>(
-                    native::partial_collapse::Witness {
-                        preamble_witness,
-                        unified_instance,
-                        error_m_witness,
-                        error_n_witness,
-                    },
-                    self.native_registry.get_key(),
-                )?;
+        let (partial_collapse_rx, _) = partial_collapse?;
         let partial_collapse_rx_blind = C::CircuitField::random(&mut *rng);
         let partial_collapse_rx_commitment =
             partial_collapse_rx.commit(C::host_generators(self.params), partial_collapse_rx_blind);
 
-        let (full_collapse_rx, _) =
-            native::full_collapse::Circuit::<C, R, HEADER_SIZE, NativeParameters>::new().rx::<R>(
-                native::full_collapse::Witness {
-                    unified_instance,
-                    preamble_witness,
-                    error_n_witness,
-                },
-                self.native_registry.get_key(),
-            )?;
+        let (full_collapse_rx, _) = full_collapse?;
         let full_collapse_rx_blind = C::CircuitField::random(&mut *rng);
         let full_collapse_rx_commitment =
             full_collapse_rx.commit(C::host_generators(self.params), full_collapse_rx_blind);
 
-        let (compute_v_rx, _) =
-            native::compute_v::Circuit::<C, R, HEADER_SIZE>::new(self.num_application_steps)
-                .rx::<R>(
-                    native::compute_v::Witness {
-                        unified_instance,
-                        preamble_witness,
-                        query_witness,
-                        eval_witness,
-                    },
-                    self.native_registry.get_key(),
-                )?;
+        let (compute_v_rx, _) = compute_v?;
         let compute_v_rx_blind = C::CircuitField::random(&mut *rng);
         let compute_v_rx_commitment =
             compute_v_rx.commit(C::host_generators(self.params), compute_v_rx_blind);
@@ -15,12 +15,14 @@ use ragu_circuits::{
     polynomials::{Rank, structured},
     registry::CircuitIndex,
 };
-use ragu_core::{Result, drivers::emulator::Emulator, maybe::Maybe};
-use ragu_primitives::{GadgetExt, Point, poseidon::Sponge, vec::CollectFixed};
+use ragu_core::{Error, Result, drivers::emulator::Emulator, maybe::Maybe};
+use ragu_primitives::{Element, GadgetExt, Point, poseidon::Sponge, vec::CollectFixed};
 use rand::Rng;
 
 use crate::{
     Application, Pcd, Proof,
+    checkpoint::{Checkpoint, Errors},
+    circuits::native::stages::preamble as native_preamble,
     components::claims::{Source, native::RxComponent},
     proof,
     step::Step,
@@ -43,6 +45,12 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
     ///   [`Step::Left`] header.
     /// * `right`: the right [`Pcd`] to fuse in this step; must correspond to
     ///   the [`Step::Right`] header.
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if either child proof was
+    /// created by a different [`Application`].
+    ///
+    /// Use [`Application::begin_fuse`] to instead run this operation one
+    /// phase at a time, with [checkpoints](crate::checkpoint) in between.
     pub fn fuse<'source, RNG: Rng, S: Step<C>>(
         &self,
         rng: &mut RNG,
@@ -51,80 +59,164 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         left: Pcd<'source, C, R, S::Left>,
         right: Pcd<'source, C, R, S::Right>,
     ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
-        let (left, right, application, application_aux) =
-            self.compute_application_proof(rng, step, witness, left, right)?;
+        self.fuse_with(|_| rng, step, witness, left, right)
+    }
 
-        let mut dr = Emulator::execute();
-        let mut transcript = Sponge::new(&mut dr, C::circuit_poseidon(self.params));
+    /// Fuse two [`Pcd`] as in [`Application::fuse`], creating the random
+    /// number generator only once the application's `rx` polynomial is known,
+    /// so that it can be derived from it.
+    pub(crate) fn fuse_with<'source, RNG: Rng, S: Step<C>>(
+        &self,
+        rng: impl FnOnce(&structured::Polynomial<C::CircuitField, R>) -> RNG,
+        step: S,
+        witness: S::Witness<'source>,
+        left: Pcd<'source, C, R, S::Left>,
+        right: Pcd<'source, C, R, S::Right>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        let (mut rng, checkpoint, aux) = self.begin_fuse_with(rng, step, witness, left, right)?;
+        let proof = self.finish_fuse(&mut rng, checkpoint)?;
 
-        let (preamble, preamble_witness) =
-            self.compute_preamble(rng, &left, &right, &application)?;
-        Point::constant(&mut dr, preamble.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let w = transcript.squeeze(&mut dr)?;
+        Ok((proof, aux))
+    }
 
-        let s_prime = self.compute_s_prime(rng, &w, &left, &right)?;
-        Point::constant(&mut dr, s_prime.nested_s_prime_commitment)?
-            .write(&mut dr, &mut transcript)?;
-        let y = transcript.squeeze(&mut dr)?;
-        let z = transcript.squeeze(&mut dr)?;
+    /// Begin fusing two [`Pcd`] into one using a provided [`Step`], returning
+    /// a [`Checkpoint`] from which the remaining phases of
+    /// [`Application::fuse`] can be run.
+    ///
+    /// The parameters are the same as those of [`Application::fuse`]; this
+    /// method runs the [`Step`] itself, and so returns its auxiliary data.
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if either child proof was
+    /// created by a different [`Application`].
+    pub fn begin_fuse<'source, RNG: Rng, S: Step<C>>(
+        &self,
+        rng: &mut RNG,
+        step: S,
+        witness: S::Witness<'source>,
+        left: Pcd<'source, C, R, S::Left>,
+        right: Pcd<'source, C, R, S::Right>,
+    ) -> Result<(Checkpoint<C, R>, S::Aux<'source>)> {
+        let (_, checkpoint, aux) = self.begin_fuse_with(|_| rng, step, witness, left, right)?;
 
-        let (error_m, error_m_witness, claims) =
-            self.compute_errors_m(rng, &w, &y, &z, &left, &right)?;
-        Point::constant(&mut dr, error_m.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        Ok((checkpoint, aux))
+    }
 
-        let saved_transcript_state = transcript
-            .clone()
-            .save_state(&mut dr)
-            .expect("save_state should succeed after absorbing")
-            .into_elements()
-            .into_iter()
-            .map(|e| *e.value().take())
-            .collect_fixed()?;
+    fn begin_fuse_with<'source, RNG: Rng, S: Step<C>>(
+        &self,
+        rng: impl FnOnce(&structured::Polynomial<C::CircuitField, R>) -> RNG,
+        step: S,
+        witness: S::Witness<'source>,
+        left: Pcd<'source, C, R, S::Left>,
+        right: Pcd<'source, C, R, S::Right>,
+    ) -> Result<(RNG, Checkpoint<C, R>, S::Aux<'source>)> {
+        if left.proof.digest != self.digest || right.proof.digest != self.digest {
+            return Err(Error::ApplicationMismatch);
+        }
 
-        let mu = transcript.squeeze(&mut dr)?;
-        let nu = transcript.squeeze(&mut dr)?;
+        let (rng, left, right, application, application_aux) =
+            self.compute_application_proof(rng, step, witness, left, right)?;
 
-        let (error_n, error_n_witness, a, b) = self.compute_errors_n(
+        Ok((
             rng,
-            &preamble_witness,
-            &error_m_witness,
-            claims,
-            &y,
-            &mu,
-            &nu,
-            saved_transcript_state,
-        )?;
-        Point::constant(&mut dr, error_n.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let mu_prime = transcript.squeeze(&mut dr)?;
-        let nu_prime = transcript.squeeze(&mut dr)?;
+            Checkpoint {
+                left,
+                right,
+                application,
+                preamble: None,
+                s_prime: None,
+                errors: None,
+                ab: None,
+                query: None,
+                f: None,
+                eval: None,
+            },
+            application_aux,
+        ))
+    }
 
-        let ab = self.compute_ab(rng, a, b, &mu_prime, &nu_prime)?;
-        Point::constant(&mut dr, ab.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let x = transcript.squeeze(&mut dr)?;
+    /// Run the next [phase](crate::checkpoint::Phase) of a fuse operation
+    /// that was begun with [`Application::begin_fuse`], recording its output
+    /// in `checkpoint`.
+    ///
+    /// This does nothing once only
+    /// [`Phase::Finish`](crate::checkpoint::Phase::Finish) remains, which is
+    /// run by [`Application::finish_fuse`].
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if the checkpoint was created by
+    /// a different [`Application`].
+    pub fn advance_fuse<RNG: Rng>(
+        &self,
+        rng: &mut RNG,
+        checkpoint: &mut Checkpoint<C, R>,
+    ) -> Result<()> {
+        self.run_phases(rng, checkpoint, 1)?;
 
-        let (query, query_witness) =
-            self.compute_query(rng, &w, &x, &y, &z, &error_m, &left, &right)?;
-        Point::constant(&mut dr, query.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let alpha = transcript.squeeze(&mut dr)?;
+        Ok(())
+    }
 
-        let f = self.compute_f(
-            rng, &w, &y, &z, &x, &alpha, &s_prime, &error_m, &ab, &query, &left, &right,
-        )?;
-        Point::constant(&mut dr, f.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let u = transcript.squeeze(&mut dr)?;
+    /// Run every remaining [phase](crate::checkpoint::Phase) of a fuse
+    /// operation that was begun with [`Application::begin_fuse`], returning
+    /// the resulting [`Proof`].
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if the checkpoint was created by
+    /// a different [`Application`].
+    pub fn finish_fuse<RNG: Rng>(
+        &self,
+        rng: &mut RNG,
+        mut checkpoint: Checkpoint<C, R>,
+    ) -> Result<Proof<C, R>> {
+        let challenges = self
+            .run_phases(rng, &mut checkpoint, usize::MAX)?
+            .expect("every phase before finishing has been run");
+        let Checkpoint {
+            left,
+            right,
+            application,
+            preamble: Some(preamble),
+            s_prime: Some(s_prime),
+            errors: Some(errors),
+            ab: Some(ab),
+            query: Some(query),
+            f: Some(f),
+            eval: Some(eval),
+        } = checkpoint
+        else {
+            unreachable!("every phase before finishing has been run");
+        };
+        let Errors {
+            error_m,
+            error_m_witness,
+            error_n,
+            error_n_witness,
+            ..
+        } = errors;
 
-        let (eval, eval_witness) =
-            self.compute_eval(rng, &u, &left, &right, &s_prime, &error_m, &ab, &query)?;
-        Point::constant(&mut dr, eval.nested_commitment)?.write(&mut dr, &mut transcript)?;
-        let pre_beta = transcript.squeeze(&mut dr)?;
+        let mut dr = Emulator::execute();
+        let u = Element::constant(&mut dr, challenges.u);
+        let pre_beta = Element::constant(&mut dr, challenges.pre_beta);
 
         let p = self.compute_p(
             &pre_beta, &u, &left, &right, &s_prime, &error_m, &ab, &query, &f,
         )?;
 
-        let challenges = proof::Challenges::new(
-            &w, &y, &z, &mu, &nu, &mu_prime, &nu_prime, &x, &alpha, &u, &pre_beta,
+        let preamble_witness = native_preamble::Witness::new(
+            &left,
+            &right,
+            &application.left_header,
+            &application.right_header,
+        )?;
+        let query_witness = _07_query::query_witness(
+            self.num_application_steps,
+            challenges.w,
+            challenges.x,
+            challenges.x * challenges.z,
+            &query.registry_xy_poly,
+            &error_m.registry_wy_poly,
+            &left,
+            &right,
         );
+        let eval_witness =
+            _09_eval::eval_witness(challenges.u, &left, &right, &s_prime, &error_m, &ab, &query);
 
         let circuits = self.compute_internal_circuits(
             rng,
@@ -145,23 +237,188 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
             &challenges,
         )?;
 
-        Ok((
-            Proof {
-                application,
-                preamble,
-                s_prime,
-                error_n,
+        Ok(Proof {
+            digest: self.digest,
+            application,
+            preamble,
+            s_prime,
+            error_n,
+            error_m,
+            ab,
+            query,
+            f,
+            eval,
+            p,
+            challenges,
+            circuits,
+        })
+    }
+
+    /// Replays the transcript of `checkpoint`, running at most `phases` of
+    /// the phases that have not yet been completed.
+    ///
+    /// Returns the challenges of the proof once every phase before
+    /// `Phase::Finish` has been completed.
+    fn run_phases<RNG: Rng>(
+        &self,
+        rng: &mut RNG,
+        checkpoint: &mut Checkpoint<C, R>,
+        mut phases: usize,
+    ) -> Result<Option<proof::Challenges<C>>> {
+        let Checkpoint {
+            left,
+            right,
+            application,
+            preamble,
+            s_prime,
+            errors,
+            ab,
+            query,
+            f,
+            eval,
+        } = checkpoint;
+        let (left, right) = (&*left, &*right);
+        if left.digest != self.digest || right.digest != self.digest {
+            return Err(Error::ApplicationMismatch);
+        }
+
+        // Returns whether another phase may be run.
+        let mut run = || {
+            phases
+                .checked_sub(1)
+                .map(|remaining| phases = remaining)
+                .is_some()
+        };
+
+        let preamble_witness = native_preamble::Witness::new(
+            left,
+            right,
+            &application.left_header,
+            &application.right_header,
+        )?;
+
+        let mut dr = Emulator::execute();
+        let mut transcript = Sponge::new(&mut dr, C::circuit_poseidon(self.params));
+
+        if preamble.is_none() && run() {
+            *preamble = Some(self.compute_preamble(rng, left, right, &preamble_witness)?);
+        }
+        let Some(preamble) = preamble else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, preamble.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        let w = transcript.squeeze(&mut dr)?;
+
+        if s_prime.is_none() && run() {
+            *s_prime = Some(self.compute_s_prime(rng, &w, left, right)?);
+        }
+        let Some(s_prime) = s_prime else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, s_prime.nested_s_prime_commitment)?
+            .write(&mut dr, &mut transcript)?;
+        let y = transcript.squeeze(&mut dr)?;
+        let z = transcript.squeeze(&mut dr)?;
+
+        if errors.is_none() && run() {
+            let (error_m, error_m_witness, claims) =
+                self.compute_errors_m(rng, &w, &y, &z, left, right)?;
+
+            // The claims are consumed by the error_n stage, so both stages
+            // are computed in one phase, deriving mu and nu from a copy of
+            // the transcript that is replayed below.
+            let mut transcript = transcript.clone();
+            Point::constant(&mut dr, error_m.nested_commitment)?.write(&mut dr, &mut transcript)?;
+
+            let saved_transcript_state = transcript
+                .clone()
+                .save_state(&mut dr)
+                .expect("save_state should succeed after absorbing")
+                .into_elements()
+                .into_iter()
+                .map(|e| *e.value().take())
+                .collect_fixed()?;
+
+            let mu = transcript.squeeze(&mut dr)?;
+            let nu = transcript.squeeze(&mut dr)?;
+
+            let (error_n, error_n_witness, a, b) = self.compute_errors_n(
+                rng,
+                &preamble_witness,
+                &error_m_witness,
+                claims,
+                &y,
+                &mu,
+                &nu,
+                saved_transcript_state,
+            )?;
+
+            *errors = Some(Errors {
                 error_m,
-                ab,
-                query,
-                f,
-                eval,
-                p,
-                challenges,
-                circuits,
-            },
-            application_aux,
-        ))
+                error_m_witness,
+                error_n,
+                error_n_witness,
+                folded: Some((a, b)),
+            });
+        }
+        let Some(errors) = errors else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, errors.error_m.nested_commitment)?
+            .write(&mut dr, &mut transcript)?;
+        let mu = transcript.squeeze(&mut dr)?;
+        let nu = transcript.squeeze(&mut dr)?;
+        Point::constant(&mut dr, errors.error_n.nested_commitment)?
+            .write(&mut dr, &mut transcript)?;
+        let mu_prime = transcript.squeeze(&mut dr)?;
+        let nu_prime = transcript.squeeze(&mut dr)?;
+
+        if ab.is_none() && run() {
+            let (a, b) = errors
+                .folded
+                .take()
+                .expect("folded polynomials are kept until the AB phase");
+            *ab = Some(self.compute_ab(rng, a, b, &mu_prime, &nu_prime)?);
+        }
+        let Some(ab) = ab else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, ab.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        let x = transcript.squeeze(&mut dr)?;
+
+        let error_m = &errors.error_m;
+        if query.is_none() && run() {
+            *query = Some(self.compute_query(rng, &w, &x, &y, &z, error_m, left, right)?);
+        }
+        let Some(query) = query else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, query.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        let alpha = transcript.squeeze(&mut dr)?;
+
+        if f.is_none() && run() {
+            *f = Some(self.compute_f(
+                rng, &w, &y, &z, &x, &alpha, s_prime, error_m, ab, query, left, right,
+            )?);
+        }
+        let Some(f) = f else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, f.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        let u = transcript.squeeze(&mut dr)?;
+
+        if eval.is_none() && run() {
+            *eval = Some(self.compute_eval(rng, &u, left, right, s_prime, error_m, ab, query)?);
+        }
+        let Some(eval) = eval else {
+            return Ok(None);
+        };
+        Point::constant(&mut dr, eval.nested_commitment)?.write(&mut dr, &mut transcript)?;
+        let pre_beta = transcript.squeeze(&mut dr)?;
+
+        Ok(Some(proof::Challenges::new(
+            &w, &y, &z, &mu, &nu, &mu_prime, &nu_prime, &x, &alpha, &u, &pre_beta,
+        )))
     }
 }
 
@@ -3,7 +3,7 @@
 
 use ff::Field;
 use ragu_core::{
-    Result,
+    Error, Result,
     drivers::{Driver, DriverValue},
     gadgets::GadgetKind,
 };
@@ -38,7 +38,16 @@ pub struct Suffix {
 
 impl Suffix {
     /// Creates a new application-defined [`Header`] suffix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is so large that the suffix would wrap around into
+    /// the internal suffixes.
     pub const fn new(value: usize) -> Self {
+        if value > usize::MAX - NUM_INTERNAL_SUFFIXES as usize {
+            panic!("application header suffix is too large");
+        }
+
         Suffix {
             suffix: HeaderSuffix::Application(value),
         }
@@ -72,6 +81,13 @@ fn test_suffix_map() {
     assert_eq!(Suffix::internal(1).get(), 1);
     assert_eq!(Suffix::new(0).get(), 2);
     assert_eq!(Suffix::new(1).get(), 3);
+    assert_eq!(Suffix::new(usize::MAX - 2).get(), usize::MAX as u64);
+}
+
+#[test]
+#[should_panic(expected = "application header suffix is too large")]
+fn test_suffix_cannot_wrap() {
+    Suffix::new(usize::MAX - 1);
 }
 
 /// Headers are succinct representations of data, essentially used as public
@@ -93,6 +109,104 @@ pub trait Header<F: Field>: Send + Sync + Any {
         dr: &mut D,
         witness: DriverValue<D, Self::Data<'source>>,
     ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>>;
+
+    /// Decode the data for this header from the elements written by its
+    /// [`encode`](Header::encode)d gadget.
+    ///
+    /// `elements` begins with the encoding of the header and may be followed
+    /// by padding, which should be ignored. Decoding is used to recover the
+    /// data that a proof attests to, and is checked by encoding the result
+    /// again, so it only needs to invert [`Header::encode`]. Headers that
+    /// cannot be decoded need not implement this method, which fails with
+    /// [`Error::Unsupported`] by default.
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        let _ = elements;
+        Err(Error::Unsupported(
+            "this header does not support decoding".into(),
+        ))
+    }
+}
+
+/// Decodes a `bool` field of a [derived](macro@Header) header from the element
+/// that its [`Boolean`](ragu_primitives::Boolean) was allocated as.
+#[doc(hidden)]
+pub fn decode_bool<F: Field>(element: F) -> Result<bool> {
+    if element == F::ZERO {
+        Ok(false)
+    } else if element == F::ONE {
+        Ok(true)
+    } else {
+        Err(Error::MalformedEncoding(
+            "header boolean is not zero or one".into(),
+        ))
+    }
+}
+
+/// Derives [`Header`] for a struct whose fields are the data of the header.
+///
+/// The struct is its own [`Header::Data`], and so it must implement
+/// [`Clone`]. The suffix of the header is given by the `#[ragu(suffix = ...)]`
+/// attribute. The header is implemented for the struct's only generic
+/// parameter as the field, or for the field given by `#[ragu(field = ...)]` if
+/// the struct has no generic parameters.
+///
+/// The macro also defines the [`Header::Output`] gadget, named after the
+/// struct with a `Gadget` suffix, which derives
+/// [`Gadget`](ragu_core::gadgets::Gadget) and [`Write`]. Each field of the
+/// struct is allocated in order as a field of this gadget:
+/// * `bool` fields are allocated as a [`Boolean`].
+/// * All other fields must be of the header's field type, and are allocated
+///   as an [`Element`].
+///
+/// [`Header::decode`] is implemented by reading back the fields in the same
+/// order.
+///
+/// ## Example
+///
+/// ```rust
+/// # use ff::Field;
+/// use ragu_pcd::header::Header;
+///
+/// #[derive(Clone, Header)]
+/// #[ragu(suffix = 0)]
+/// struct Counter<F: Field> {
+///     /// The number of steps taken so far.
+///     count: F,
+///     /// Whether the computation has finished.
+///     done: bool,
+/// }
+/// ```
+///
+/// This defines a `CounterGadget<'dr, D>` with a `count` [`Element`] and a
+/// `done` [`Boolean`], and implements `Header<F>` for `Counter<F>`.
+///
+/// [`Element`]: ragu_primitives::Element
+/// [`Boolean`]: ragu_primitives::Boolean
+pub use ragu_macros::Header;
+
+#[test]
+fn test_default_decode_is_unsupported() {
+    use ragu_pasta::Fp;
+
+    struct Opaque;
+
+    impl<F: Field> Header<F> for Opaque {
+        const SUFFIX: Suffix = Suffix::new(0);
+        type Data<'source> = ();
+        type Output = ();
+
+        fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+            _: &mut D,
+            _: DriverValue<D, Self::Data<'source>>,
+        ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+            Ok(())
+        }
+    }
+
+    assert!(matches!(
+        <Opaque as Header<Fp>>::decode(&[Fp::ONE]),
+        Err(Error::Unsupported(_))
+    ));
 }
 
 /// Trivial header that encodes no data.
@@ -108,4 +222,8 @@ impl<F: Field> Header<F> for () {
     ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
         Ok(())
     }
+
+    fn decode<'source>(_: &[F]) -> Result<Self::Data<'source>> {
+        Ok(())
+    }
 }
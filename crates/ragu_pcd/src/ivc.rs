@@ -0,0 +1,277 @@
+//! Incrementally verifiable computation over a chain of proofs.
+//!
+//! Many applications are a single chain of proofs rather than a tree: a
+//! computation is seeded once and then repeatedly extended with the same
+//! [`UnaryStep`], such as in a hash chain. An [`Ivc`] owns the current [`Pcd`]
+//! of such a chain and extends it with [`Ivc::step`].
+//!
+//! The header of the chain is an [`Iteration`], which pairs the state
+//! [`Header`] of the steps with a counter of the steps taken since the chain
+//! was seeded. The counter is maintained by the [`Seed`] and [`Iterate`]
+//! adapters, which must be registered in place of the steps they wrap:
+//!
+//! * [`Seed`] wraps a [`UnaryStep`] that creates the initial state, and starts
+//!   the counter at zero.
+//! * [`Iterate`] wraps a [`UnaryStep`] from the state to the next state, and
+//!   increments the counter.
+//!
+//! The state is recovered from each new proof's header using
+//! [`Header::decode`], which the state header must implement, and together
+//! with the counter it must fit within `HEADER_SIZE - 1` elements.
+
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::Rank;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue},
+    gadgets::{GadgetKind, Kind},
+    maybe::Maybe,
+};
+use ragu_primitives::Element;
+use rand::Rng;
+
+use core::marker::PhantomData;
+
+use crate::{
+    Application, Pcd, Proof,
+    header::{Header, Suffix},
+    step::{Encoded, Index, UnaryStep, internal::padded},
+};
+
+/// The [`Header`] of an [`Ivc`] chain, which encodes the number of steps taken
+/// since the chain was seeded followed by the state header `H`.
+///
+/// An [`Iteration`] has the same suffix as `H`, and so `H` should not be used
+/// as a header of any other step in the same application.
+pub struct Iteration<H>(PhantomData<H>);
+
+impl<F: Field, H: Header<F>> Header<F> for Iteration<H> {
+    const SUFFIX: Suffix = H::SUFFIX;
+    type Data<'source> = (F, H::Data<'source>);
+    type Output = (
+        PhantomData<Kind![F; Element<'_, _>]>,
+        PhantomData<H::Output>,
+    );
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        let (count, data) = witness.cast();
+
+        Ok((Element::alloc(dr, count)?, H::encode(dr, data)?))
+    }
+
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        let Some((count, elements)) = elements.split_first() else {
+            return Err(Error::MalformedEncoding("iteration header is empty".into()));
+        };
+
+        Ok((*count, H::decode(elements)?))
+    }
+}
+
+/// Adapts a [`UnaryStep`] that creates the initial state of an [`Ivc`] chain
+/// into one whose output is an [`Iteration`] with a counter of zero.
+pub struct Seed<S> {
+    step: S,
+}
+
+impl<S> Seed<S> {
+    /// Wraps a [`UnaryStep`] that seeds an [`Ivc`] chain.
+    pub fn new(step: S) -> Self {
+        Seed { step }
+    }
+}
+
+impl<C: Cycle, S: UnaryStep<C, Input = ()>> UnaryStep<C> for Seed<S> {
+    const INDEX: Index = S::INDEX;
+
+    type Witness<'source> = S::Witness<'source>;
+    type Aux<'source> = S::Aux<'source>;
+
+    type Input = ();
+    type Output = Iteration<S::Output>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        input: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let ((input, output), aux) = self.step.witness::<_, HEADER_SIZE>(dr, witness, input)?;
+        let count = Element::zero(dr);
+        let output = Encoded::from_gadget((count, output.as_gadget().clone()));
+
+        Ok(((input, output), aux))
+    }
+}
+
+/// Adapts a [`UnaryStep`] from the state of an [`Ivc`] chain to its next
+/// state into one that also increments the counter of the [`Iteration`].
+pub struct Iterate<S> {
+    step: S,
+}
+
+impl<S> Iterate<S> {
+    /// Wraps a [`UnaryStep`] that extends an [`Ivc`] chain.
+    pub fn new(step: S) -> Self {
+        Iterate { step }
+    }
+}
+
+impl<C: Cycle, H: Header<C::CircuitField>, S: UnaryStep<C, Input = H, Output = H>> UnaryStep<C>
+    for Iterate<S>
+{
+    const INDEX: Index = S::INDEX;
+
+    type Witness<'source> = S::Witness<'source>;
+    type Aux<'source> = S::Aux<'source>;
+
+    type Input = Iteration<H>;
+    type Output = Iteration<H>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        input: DriverValue<D, (C::CircuitField, H::Data<'source>)>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let (count, input) = input.cast();
+        let count = Element::alloc(dr, count)?;
+        let ((input, output), aux) = self.step.witness::<_, HEADER_SIZE>(dr, witness, input)?;
+        let next = count.add(dr, &Element::one());
+
+        let input = Encoded::from_gadget((count, input.as_gadget().clone()));
+        let output = Encoded::from_gadget((next, output.as_gadget().clone()));
+
+        Ok(((input, output), aux))
+    }
+}
+
+/// The current proof of a chain of [`UnaryStep`]s, created with an
+/// [`Application`] whose registered steps include [`Seed`] and [`Iterate`].
+///
+/// See the [module documentation](self) for details.
+pub struct Ivc<'app, 'params, 'source, C: Cycle, R: Rank, S: UnaryStep<C>, const HEADER_SIZE: usize>
+{
+    app: &'app Application<'params, C, R, HEADER_SIZE>,
+    step: S,
+    iterations: u64,
+    pcd: Pcd<'source, C, R, Iteration<S::Output>>,
+}
+
+impl<
+    'app,
+    'params,
+    'source,
+    C: Cycle,
+    R: Rank,
+    H: Header<C::CircuitField>,
+    S: UnaryStep<C, Input = H, Output = H> + Clone,
+    const HEADER_SIZE: usize,
+> Ivc<'app, 'params, 'source, C, R, S, HEADER_SIZE>
+{
+    /// Seeds a new chain by running `seed` (registered as a [`Seed`]) on a
+    /// trivial input, to be extended by `step` (registered as an [`Iterate`]).
+    ///
+    /// Returns the chain along with the auxiliary data of `seed`.
+    pub fn seed<'seed, RNG: Rng, T: UnaryStep<C, Input = (), Output = H>>(
+        app: &'app Application<'params, C, R, HEADER_SIZE>,
+        rng: &mut RNG,
+        seed: T,
+        witness: T::Witness<'seed>,
+        step: S,
+    ) -> Result<(Self, T::Aux<'seed>)> {
+        let (proof, aux) = app.seed_unary(rng, Seed::new(seed), witness)?;
+        let ivc = Ivc {
+            app,
+            step,
+            iterations: 0,
+            pcd: carry::<C, R, H, HEADER_SIZE>(proof)?,
+        };
+
+        Ok((ivc, aux))
+    }
+
+    /// Extends the chain by one step with the provided `witness`, returning
+    /// the step's auxiliary data.
+    ///
+    /// The chain is left unchanged if the step fails.
+    pub fn step<RNG: Rng>(
+        &mut self,
+        rng: &mut RNG,
+        witness: S::Witness<'source>,
+    ) -> Result<S::Aux<'source>> {
+        let (proof, aux) = self.app.extend(
+            rng,
+            Iterate::new(self.step.clone()),
+            witness,
+            self.pcd.clone(),
+        )?;
+        self.pcd = carry::<C, R, H, HEADER_SIZE>(proof)?;
+        self.iterations += 1;
+
+        Ok(aux)
+    }
+
+    /// Returns the number of steps taken since the chain was seeded.
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// Returns the current state of the chain.
+    pub fn state(&self) -> &H::Data<'source> {
+        &self.pcd.data.1
+    }
+
+    /// Returns the current proof-carrying data of the chain.
+    pub fn proof(&self) -> &Pcd<'source, C, R, Iteration<H>> {
+        &self.pcd
+    }
+
+    /// Verifies the current proof of the chain.
+    pub fn verify<RNG: Rng>(&self, rng: RNG) -> Result<bool> {
+        self.app.verify(&self.pcd, rng)
+    }
+
+    /// Returns a [rerandomized](Application::rerandomize) copy of the current
+    /// proof-carrying data of the chain, which reveals nothing about the
+    /// chain besides its header.
+    pub fn export<RNG: Rng>(&self, rng: &mut RNG) -> Result<Pcd<'source, C, R, Iteration<H>>> {
+        self.app.rerandomize(self.pcd.clone(), rng)
+    }
+
+    /// Consumes the chain, returning its current proof-carrying data.
+    pub fn into_pcd(self) -> Pcd<'source, C, R, Iteration<H>> {
+        self.pcd
+    }
+}
+
+/// Carries a new proof of a chain with the header data decoded from the proof.
+fn carry<'source, C: Cycle, R: Rank, H: Header<C::CircuitField>, const HEADER_SIZE: usize>(
+    proof: Proof<C, R>,
+) -> Result<Pcd<'source, C, R, Iteration<H>>> {
+    let data = padded::decode::<Iteration<H>, HEADER_SIZE, _>(&proof.application.output_header)?;
+
+    Ok(proof.carry(data))
+}
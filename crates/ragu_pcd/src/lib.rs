@@ -8,35 +8,59 @@
 #![doc(html_logo_url = "https://tachyon.z.cash/assets/ragu/v1/rustdoc-128x128.png")]
 
 extern crate alloc;
+#[cfg(feature = "parallel")]
+extern crate std;
 
+mod cache;
+mod check;
+pub mod checkpoint;
 mod circuits;
 mod components;
+mod compress;
+pub mod deterministic;
 mod fuse;
 pub mod header;
+pub mod ivc;
+pub mod mock;
+mod parallel;
 mod proof;
+pub mod report;
+pub mod scheduler;
 pub mod step;
 mod verify;
+mod verifying_key;
 
-use arithmetic::Cycle;
+use arithmetic::{Cycle, PoseidonPermutation};
+use ff::{Field, PrimeField};
 use ragu_circuits::{
     polynomials::Rank,
-    registry::{Registry, RegistryBuilder},
+    registry::{Registry, RegistryBuilder, RegistryCache},
 };
-use ragu_core::{Error, Result};
+use ragu_core::{Error, Result, drivers::emulator::Emulator, maybe::Maybe};
+use ragu_primitives::{Element, poseidon::Sponge};
 use rand::Rng;
 
-use alloc::collections::BTreeMap;
-use core::{any::TypeId, cell::OnceCell, marker::PhantomData};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    any::{TypeId, type_name},
+    iter::once,
+    marker::PhantomData,
+};
 
 use header::Header;
-pub use proof::{Pcd, Proof};
-use step::{Step, internal::adapter::Adapter};
+pub use proof::{CompressedPcd, CompressedProof, ErasedPcd, Pcd, Proof};
+use step::{
+    BoxedStep, Step, UnaryStep,
+    internal::{adapter::Adapter, unary::Unary},
+};
+pub use verifying_key::VerifyingKey;
 
 /// Builder for an [`Application`] for proof-carrying data.
 pub struct ApplicationBuilder<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
     native_registry: RegistryBuilder<'params, C::CircuitField, R>,
     nested_registry: RegistryBuilder<'params, C::ScalarField, R>,
     num_application_steps: usize,
+    step_names: Vec<&'static str>,
     header_map: BTreeMap<header::Suffix, TypeId>,
     _marker: PhantomData<[(); HEADER_SIZE]>,
 }
@@ -58,6 +82,7 @@ impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
             native_registry: RegistryBuilder::new(),
             nested_registry: RegistryBuilder::new(),
             num_application_steps: 0,
+            step_names: Vec::new(),
             header_map: BTreeMap::new(),
             _marker: PhantomData,
         }
@@ -66,17 +91,37 @@ impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
     /// Register a new application-defined [`Step`] in this context. The
     /// provided [`Step`]'s [`INDEX`](Step::INDEX) should be the next sequential
     /// index that has not been inserted yet.
-    pub fn register<S: Step<C> + 'params>(mut self, step: S) -> Result<Self> {
-        S::INDEX.assert_index(self.num_application_steps)?;
+    pub fn register<S: Step<C> + 'params>(self, step: S) -> Result<Self> {
+        self.register_named(step, type_name::<S>())
+    }
+
+    /// Register a new application-defined [`UnaryStep`] in this context. Unary
+    /// steps share the same sequential index space as [`Step`]s registered
+    /// with [`ApplicationBuilder::register`].
+    pub fn register_unary<S: UnaryStep<C> + 'params>(self, step: S) -> Result<Self> {
+        self.register_named(Unary::new(step), type_name::<S>())
+    }
+
+    /// Register an application-defined [`Step`] whose type has been erased
+    /// into a [`BoxedStep`], as [`ApplicationBuilder::register`] would.
+    pub fn register_boxed(self, step: BoxedStep<'params, C, R, HEADER_SIZE>) -> Result<Self> {
+        step.register(self)
+    }
+
+    /// Registers a [`Step`], which is named `name` in
+    /// [constraint reports](report).
+    fn register_named<S: Step<C> + 'params>(mut self, step: S, name: &'static str) -> Result<Self> {
+        step.index().assert_index(self.num_application_steps)?;
 
-        self.prevent_duplicate_suffixes::<S::Output>()?;
-        self.prevent_duplicate_suffixes::<S::Left>()?;
-        self.prevent_duplicate_suffixes::<S::Right>()?;
+        prevent_duplicate_suffixes::<C::CircuitField, S::Output>(&mut self.header_map)?;
+        prevent_duplicate_suffixes::<C::CircuitField, S::Left>(&mut self.header_map)?;
+        prevent_duplicate_suffixes::<C::CircuitField, S::Right>(&mut self.header_map)?;
 
         self.native_registry =
             self.native_registry
                 .register_circuit(Adapter::<C, S, R, HEADER_SIZE>::new(step))?;
         self.num_application_steps += 1;
+        self.step_names.push(name);
 
         Ok(self)
     }
@@ -92,6 +137,7 @@ impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
         for _ in 0..count {
             self.native_registry = self.native_registry.register_circuit(())?;
             self.num_application_steps += 1;
+            self.step_names.push("dummy");
         }
         Ok(self)
     }
@@ -99,9 +145,79 @@ impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
     /// Perform finalization and optimization steps to produce the
     /// [`Application`].
     pub fn finalize(
+        self,
+        params: &'params C::Params,
+    ) -> Result<Application<'params, C, R, HEADER_SIZE>> {
+        self.finalize_with(params, None)
+    }
+
+    /// Performs finalization, using the provided [`RegistryCache`]s (if any)
+    /// to finalize the native and nested registries, along with the digest of
+    /// the application they were created for.
+    pub(crate) fn finalize_with(
         mut self,
         params: &'params C::Params,
+        cache: Option<(
+            &RegistryCache<C::CircuitField>,
+            &RegistryCache<C::ScalarField>,
+            C::CircuitField,
+        )>,
     ) -> Result<Application<'params, C, R, HEADER_SIZE>> {
+        let num_application_steps = self.num_application_steps;
+        let step_names = core::mem::take(&mut self.step_names);
+        let (native_registry, nested_registry) = self.internal_registries(params)?;
+        let (native_registry, nested_registry) = match cache {
+            Some((native, nested, _)) => (
+                native_registry.finalize_cached(C::circuit_poseidon(params), native)?,
+                nested_registry.finalize_cached(C::scalar_poseidon(params), nested)?,
+            ),
+            None => (
+                native_registry.finalize(C::circuit_poseidon(params))?,
+                nested_registry.finalize(C::scalar_poseidon(params))?,
+            ),
+        };
+
+        // The digest is only taken from the cache if both registries were.
+        let digest = match cache {
+            Some((native, nested, digest))
+                if native_registry.cache().as_ref() == Some(native)
+                    && nested_registry.cache().as_ref() == Some(nested) =>
+            {
+                digest
+            }
+            _ => application_digest::<C, HEADER_SIZE>(
+                params,
+                native_registry.get_key(),
+                nested_registry.get_key(),
+                num_application_steps,
+                &verifying_key::evaluation_data(
+                    &native_registry,
+                    &nested_registry,
+                    num_application_steps,
+                )?,
+            ),
+        };
+
+        Ok(Application {
+            native_registry,
+            nested_registry,
+            params,
+            num_application_steps,
+            step_names,
+            digest,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Inserts the internal steps and circuits, returning the native and
+    /// nested registries to be finalized.
+    pub(crate) fn internal_registries(
+        mut self,
+        params: &'params C::Params,
+    ) -> Result<(
+        RegistryBuilder<'params, C::CircuitField, R>,
+        RegistryBuilder<'params, C::ScalarField, R>,
+    )> {
         // First, insert all of the internal steps.
         {
             self.native_registry =
@@ -144,32 +260,29 @@ impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
         // Register nested internal circuits (no application steps, no headers).
         self.nested_registry = circuits::nested::register_all::<C, R>(self.nested_registry)?;
 
-        Ok(Application {
-            native_registry: self.native_registry.finalize(C::circuit_poseidon(params))?,
-            nested_registry: self.nested_registry.finalize(C::scalar_poseidon(params))?,
-            params,
-            num_application_steps: self.num_application_steps,
-            seeded_trivial: OnceCell::new(),
-            _marker: PhantomData,
-        })
+        Ok((self.native_registry, self.nested_registry))
     }
+}
 
-    fn prevent_duplicate_suffixes<H: Header<C::CircuitField>>(&mut self) -> Result<()> {
-        match self.header_map.get(&H::SUFFIX) {
-            Some(ty) => {
-                if *ty != TypeId::of::<H>() {
-                    return Err(Error::Initialization(
-                        "two different Header implementations using the same suffix".into(),
-                    ));
-                }
-            }
-            None => {
-                self.header_map.insert(H::SUFFIX, TypeId::of::<H>());
+/// Records the suffix of `H` in `header_map`, failing if a different
+/// [`Header`] implementation has already used it.
+pub(crate) fn prevent_duplicate_suffixes<F: Field, H: Header<F>>(
+    header_map: &mut BTreeMap<header::Suffix, TypeId>,
+) -> Result<()> {
+    match header_map.get(&H::SUFFIX) {
+        Some(ty) => {
+            if *ty != TypeId::of::<H>() {
+                return Err(Error::Initialization(
+                    "two different Header implementations using the same suffix".into(),
+                ));
             }
         }
-
-        Ok(())
+        None => {
+            header_map.insert(H::SUFFIX, TypeId::of::<H>());
+        }
     }
+
+    Ok(())
 }
 
 /// The recursion context that is used to create and verify proof-carrying data.
@@ -178,12 +291,27 @@ pub struct Application<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
     nested_registry: Registry<'params, C::ScalarField, R>,
     params: &'params C::Params,
     num_application_steps: usize,
-    /// Cached seeded trivial proof for rerandomization.
-    seeded_trivial: OnceCell<Proof<C, R>>,
+    /// Names of the application steps, in order of their indices.
+    step_names: Vec<&'static str>,
+    /// Identifies this application, and is embedded in every proof it creates.
+    digest: C::CircuitField,
     _marker: PhantomData<[(); HEADER_SIZE]>,
 }
 
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
+    /// Returns the digest that identifies this application.
+    ///
+    /// The digest commits to the native and nested registries (and so to
+    /// every registered [`Step`]), the number of application steps,
+    /// `HEADER_SIZE` and the data that a [`VerifyingKey`] uses to evaluate the
+    /// registries. It is embedded in every proof created by this
+    /// application (see [`Proof::application_digest`]) so that proofs created
+    /// by a different application are rejected with
+    /// [`Error::ApplicationMismatch`].
+    pub fn digest(&self) -> C::CircuitField {
+        self.digest
+    }
+
     /// Seed a new computation by running a step with trivial inputs.
     ///
     /// This is the entry point for creating leaf nodes in a PCD tree.
@@ -198,27 +326,45 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
         self.fuse(rng, step, witness, self.trivial_pcd(), self.trivial_pcd())
     }
 
-    /// Returns a seeded trivial proof for use in rerandomization.
-    ///
-    /// A seeded trivial is a trivial proof that has been through `seed()`
-    /// (folded with itself). This gives it valid proof structure, avoiding
-    /// base case detection issues.
+    /// Seed a new incremental computation by running a [`UnaryStep`] on a
+    /// trivial input.
+    pub fn seed_unary<'source, RNG: Rng, S: UnaryStep<C, Input = ()>>(
+        &self,
+        rng: &mut RNG,
+        step: S,
+        witness: S::Witness<'source>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        self.fuse(
+            rng,
+            Unary::new(step),
+            witness,
+            self.trivial_pcd(),
+            self.trivial_pcd(),
+        )
+    }
+
+    /// Extend some [`Pcd`] with a [`UnaryStep`], producing a proof that
+    /// depends only on this single predecessor.
     ///
-    /// The proof is lazily created on first use and cached. *Importantly*,
-    /// note that this may return the same proof on subsequent calls, and
-    /// is not random.
-    fn seeded_trivial_pcd<'source, RNG: Rng>(&self, rng: &mut RNG) -> Pcd<'source, C, R, ()> {
-        let proof = self.seeded_trivial.get_or_init(|| {
-            self.seed(rng, step::internal::trivial::Trivial::new(), ())
-                .expect("seeded trivial seed should not fail")
-                .0
-        });
-        proof.clone().carry(())
+    /// The recursion circuits always fold two child proofs, so the
+    /// predecessor is folded together with the empty trivial proof. That
+    /// proof does not have to be created, and folding it is cheap: its
+    /// polynomials are empty, so evaluating them and forming revdot products
+    /// with them is trivial, and the $s(X, y)$ restrictions of the internal
+    /// circuits are shared with the claims about the predecessor.
+    pub fn extend<'source, RNG: Rng, S: UnaryStep<C>>(
+        &self,
+        rng: &mut RNG,
+        step: S,
+        witness: S::Witness<'source>,
+        input: Pcd<'source, C, R, S::Input>,
+    ) -> Result<(Proof<C, R>, S::Aux<'source>)> {
+        self.fuse(rng, Unary::new(step), witness, input, self.trivial_pcd())
     }
 
     /// Rerandomize proof-carrying data.
     ///
-    /// This will internally fold the [`Pcd`] with a seeded trivial proof
+    /// This will internally fold the [`Pcd`] with the empty trivial proof
     /// using an internal rerandomization step, such that the resulting proof
     /// is valid for the same [`Header`] but reveals nothing else about the
     /// original proof. As a result, [`Application::verify`] should produce the
@@ -230,17 +376,78 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_S
     ) -> Result<Pcd<'source, C, R, H>> {
         let data = pcd.data.clone();
 
-        // Seed a trivial proof for rerandomization.
-        // TODO: this is a temporary hack that allows the base case logic to be simple
-        let seeded_trivial = self.seeded_trivial_pcd(rng);
         let rerandomized_proof = self.fuse(
             rng,
             step::internal::rerandomize::Rerandomize::new(),
             (),
             pcd,
-            seeded_trivial,
+            self.trivial_pcd(),
         )?;
 
         Ok(rerandomized_proof.0.carry(data))
     }
 }
+
+/// Computes the digest that identifies an application from the keys of its
+/// registries, number of application steps, header size and the
+/// [evaluation data](verifying_key::evaluation_data) of its verifying key.
+pub(crate) fn application_digest<C: Cycle, const HEADER_SIZE: usize>(
+    params: &C::Params,
+    native_key: C::CircuitField,
+    nested_key: C::ScalarField,
+    num_application_steps: usize,
+    evaluation_data: &[u8],
+) -> C::CircuitField {
+    // The nested registry key is not an element of the circuit field, so it is
+    // absorbed in limbs, as is the evaluation data.
+    let nested_key = nested_key.to_repr();
+
+    let inputs = once(native_key)
+        .chain(limbs(nested_key.as_ref()))
+        .chain([
+            C::CircuitField::from(num_application_steps as u64),
+            C::CircuitField::from(HEADER_SIZE as u64),
+            C::CircuitField::from(evaluation_data.len() as u64),
+        ])
+        .chain(limbs(evaluation_data));
+
+    let [digest] = hash(C::circuit_poseidon(params), inputs);
+    digest
+}
+
+/// Hashes `inputs` with a Poseidon sponge outside of any circuit, squeezing
+/// `N` outputs.
+pub(crate) fn hash<F: PrimeField, P: PoseidonPermutation<F>, const N: usize>(
+    poseidon: &P,
+    inputs: impl IntoIterator<Item = F>,
+) -> [F; N] {
+    Emulator::emulate_wireless((), |dr, _| {
+        let mut sponge = Sponge::new(dr, poseidon);
+        for input in inputs {
+            let input = Element::constant(dr, input);
+            sponge.absorb(dr, &input)?;
+        }
+
+        let mut outputs = [F::ZERO; N];
+        for output in &mut outputs {
+            *output = *sponge.squeeze(dr)?.value().take();
+        }
+        Ok(outputs)
+    })
+    .expect("hashing constants should always succeed")
+}
+
+/// Splits `bytes` into little-endian 248-bit limbs, so that data which is not
+/// an element of `F` can be absorbed into a sponge over `F`. Both fields of a
+/// [`Cycle`] are large enough to hold each limb.
+pub(crate) fn limbs<F: PrimeField>(bytes: &[u8]) -> impl Iterator<Item = F> + '_ {
+    let shift = F::from_u128(1 << 64).square();
+    bytes.chunks(31).map(move |chunk| {
+        let mut limb = [0; 32];
+        limb[..chunk.len()].copy_from_slice(chunk);
+        let (lo, hi) = limb.split_at(16);
+        let lo = u128::from_le_bytes(lo.try_into().expect("16 bytes"));
+        let hi = u128::from_le_bytes(hi.try_into().expect("16 bytes"));
+        F::from_u128(lo) + F::from_u128(hi) * shift
+    })
+}
@@ -0,0 +1,213 @@
+//! Mock applications for quickly testing [`Step`] logic.
+//!
+//! Creating an [`Application`] requires public parameters and a finalized
+//! registry, and every call to [`Application::fuse`] spends seconds computing
+//! commitments and recursion circuits. A [`MockApplication`] accepts the same
+//! [`Step`]s and [`Header`]s, but [`MockApplication::fuse`] only runs the
+//! step circuit, checking that it is satisfied as
+//! [`Application::check_step`] does, and carries the encoded headers between
+//! [`MockPcd`]s.
+//!
+//! Mock proofs prove nothing and cannot be verified by an [`Application`].
+//!
+//! [`Application`]: crate::Application
+//! [`Application::fuse`]: crate::Application::fuse
+//! [`Application::check_step`]: crate::Application::check_step
+
+use arithmetic::Cycle;
+use ragu_circuits::{CircuitExt, polynomials::Rank};
+use ragu_core::{Error, Result};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{any::TypeId, marker::PhantomData};
+
+use crate::{
+    check,
+    header::{Header, Suffix},
+    prevent_duplicate_suffixes,
+    step::{
+        Step, UnaryStep,
+        internal::{adapter::Adapter, padded, unary::Unary},
+    },
+};
+
+/// A mock of an [`Application`](crate::Application) that checks the
+/// constraints of its [`Step`]s without creating proofs.
+///
+/// See the [module documentation](self) for details.
+pub struct MockApplication<C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    num_application_steps: usize,
+    header_map: BTreeMap<Suffix, TypeId>,
+    _marker: PhantomData<(C, R)>,
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Default for MockApplication<C, R, HEADER_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A mock of a [`Proof`](crate::Proof), which only records the encoded
+/// output header of the step that created it.
+pub struct MockProof<C: Cycle> {
+    output_header: Vec<C::CircuitField>,
+}
+
+impl<C: Cycle> Clone for MockProof<C> {
+    fn clone(&self) -> Self {
+        MockProof {
+            output_header: self.output_header.clone(),
+        }
+    }
+}
+
+impl<C: Cycle> MockProof<C> {
+    /// Augment a mock proof with some data, described by a [`Header`].
+    pub fn carry<H: Header<C::CircuitField>>(self, data: H::Data<'_>) -> MockPcd<'_, C, H> {
+        MockPcd { proof: self, data }
+    }
+}
+
+/// A mock of a [`Pcd`](crate::Pcd), pairing a [`MockProof`] with the data of
+/// its [`Header`].
+pub struct MockPcd<'source, C: Cycle, H: Header<C::CircuitField>> {
+    /// The mock proof for the accompanying data.
+    pub proof: MockProof<C>,
+
+    /// Data needed to witness a [`Header`] within a [`Step`].
+    pub data: H::Data<'source>,
+}
+
+impl<C: Cycle, H: Header<C::CircuitField>> Clone for MockPcd<'_, C, H> {
+    fn clone(&self) -> Self {
+        MockPcd {
+            proof: self.proof.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> MockApplication<C, R, HEADER_SIZE> {
+    /// Create an empty [`MockApplication`].
+    pub fn new() -> Self {
+        MockApplication {
+            num_application_steps: 0,
+            header_map: BTreeMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Register a new application-defined [`Step`], as in
+    /// [`ApplicationBuilder::register`](crate::ApplicationBuilder::register).
+    ///
+    /// Fails if the [`Step`]'s index is not the next sequential index, if its
+    /// headers reuse the suffix of a different [`Header`], or if its circuit
+    /// does not fit within the bounds of the [`Rank`].
+    pub fn register<S: Step<C>>(mut self, step: S) -> Result<Self> {
+        step.index().assert_index(self.num_application_steps)?;
+
+        prevent_duplicate_suffixes::<C::CircuitField, S::Output>(&mut self.header_map)?;
+        prevent_duplicate_suffixes::<C::CircuitField, S::Left>(&mut self.header_map)?;
+        prevent_duplicate_suffixes::<C::CircuitField, S::Right>(&mut self.header_map)?;
+
+        Adapter::<C, S, R, HEADER_SIZE>::new(step).into_object::<R>()?;
+        self.num_application_steps += 1;
+
+        Ok(self)
+    }
+
+    /// Register a new application-defined [`UnaryStep`], as in
+    /// [`ApplicationBuilder::register_unary`](crate::ApplicationBuilder::register_unary).
+    pub fn register_unary<S: UnaryStep<C>>(self, step: S) -> Result<Self> {
+        self.register(Unary::new(step))
+    }
+
+    /// Seed a new computation by running a step with trivial inputs, as in
+    /// [`Application::seed`](crate::Application::seed).
+    pub fn seed<'source, S: Step<C, Left = (), Right = ()>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+    ) -> Result<(MockProof<C>, S::Aux<'source>)> {
+        self.fuse(step, witness, self.trivial_pcd(), self.trivial_pcd())
+    }
+
+    /// Seed a new incremental computation by running a [`UnaryStep`] on a
+    /// trivial input, as in
+    /// [`Application::seed_unary`](crate::Application::seed_unary).
+    pub fn seed_unary<'source, S: UnaryStep<C, Input = ()>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+    ) -> Result<(MockProof<C>, S::Aux<'source>)> {
+        self.fuse(
+            Unary::new(step),
+            witness,
+            self.trivial_pcd(),
+            self.trivial_pcd(),
+        )
+    }
+
+    /// Fuse two [`MockPcd`] into one using a provided [`Step`], as in
+    /// [`Application::fuse`](crate::Application::fuse).
+    ///
+    /// Returns [`Error::InvalidWitness`] if the step circuit is not satisfied,
+    /// naming the first constraint that is not, or if the headers that the
+    /// step encodes for its children differ from those their proofs were
+    /// created with.
+    pub fn fuse<'source, S: Step<C>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+        left: MockPcd<'source, C, S::Left>,
+        right: MockPcd<'source, C, S::Right>,
+    ) -> Result<(MockProof<C>, S::Aux<'source>)> {
+        step.index().circuit_index(self.num_application_steps)?;
+
+        let ((left_header, right_header, output_header), aux) =
+            check::check_step::<C, S, R, HEADER_SIZE>(step, (left.data, right.data, witness))?;
+        if left_header[..] != left.proof.output_header[..]
+            || right_header[..] != right.proof.output_header[..]
+        {
+            return Err(Error::InvalidWitness(
+                "child header does not match the output header of its proof".into(),
+            ));
+        }
+
+        Ok((
+            MockProof {
+                output_header: output_header.into_inner(),
+            },
+            aux,
+        ))
+    }
+
+    /// Extend some [`MockPcd`] with a [`UnaryStep`], as in
+    /// [`Application::extend`](crate::Application::extend).
+    pub fn extend<'source, S: UnaryStep<C>>(
+        &self,
+        step: S,
+        witness: S::Witness<'source>,
+        input: MockPcd<'source, C, S::Input>,
+    ) -> Result<(MockProof<C>, S::Aux<'source>)> {
+        self.fuse(Unary::new(step), witness, input, self.trivial_pcd())
+    }
+
+    /// Checks that the data of some [`MockPcd`] is the data that its proof
+    /// was created with.
+    pub fn verify<H: Header<C::CircuitField>>(&self, pcd: &MockPcd<'_, C, H>) -> Result<bool> {
+        let output_header = padded::encode::<H, HEADER_SIZE, _>(pcd.data.clone())?;
+
+        Ok(output_header[..] == pcd.proof.output_header[..])
+    }
+
+    fn trivial_pcd<'source>(&self) -> MockPcd<'source, C, ()> {
+        let output_header =
+            padded::encode::<(), HEADER_SIZE, _>(()).expect("the trivial header should always fit");
+
+        MockProof {
+            output_header: output_header.into_inner(),
+        }
+        .carry(())
+    }
+}
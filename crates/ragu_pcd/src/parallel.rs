@@ -0,0 +1,103 @@
+//! Helpers for running independent parts of proof generation concurrently.
+//!
+//! With the `parallel` feature enabled these defer to [`rayon`]; otherwise
+//! they run each task in turn on the current thread, so that the crate remains
+//! `no_std`. In both cases the bounds are those that `rayon` requires, so code
+//! that compiles without the feature also compiles with it.
+//!
+//! `ragu_circuits` has no `parallel` feature of its own: work over the
+//! circuits of a registry is split between threads here, and the
+//! multiscalar multiplications behind every commitment are split by the
+//! `parallel` feature of `ragu_arithmetic`, which this feature enables.
+
+/// Runs `a` and `b`, potentially in parallel, and returns both results.
+#[cfg(feature = "parallel")]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    rayon::join(a, b)
+}
+
+/// Runs `a` and `b`, potentially in parallel, and returns both results.
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    (a(), b())
+}
+
+/// A value that is produced by one task and then read by the tasks that run
+/// after it, which register themselves as readers beforehand so that the last
+/// of them can take the value rather than clone it.
+pub(crate) struct Slot<T> {
+    #[cfg(feature = "parallel")]
+    inner: std::sync::Mutex<Inner<T>>,
+    #[cfg(not(feature = "parallel"))]
+    inner: core::cell::RefCell<Inner<T>>,
+}
+
+struct Inner<T> {
+    value: Option<T>,
+    readers: usize,
+}
+
+impl<T> Slot<T> {
+    /// Creates a slot holding `value`, if any.
+    pub(crate) fn new(value: Option<T>) -> Self {
+        Slot {
+            inner: Inner { value, readers: 0 }.into(),
+        }
+    }
+
+    /// Stores the value of this slot, replacing any previous value.
+    pub(crate) fn set(&self, value: T) {
+        self.lock().value = Some(value);
+    }
+
+    /// Removes the value from this slot, if it has been produced.
+    pub(crate) fn take(&self) -> Option<T> {
+        self.lock().value.take()
+    }
+
+    /// Registers another reader of this slot.
+    pub(crate) fn add_reader(&self) {
+        self.lock().readers += 1;
+    }
+
+    /// Reads the value of this slot, if it has been produced, on behalf of
+    /// one of its registered readers. The last reader takes the value, while
+    /// the others receive a copy of it.
+    pub(crate) fn read(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let mut inner = self.lock();
+        inner.readers = inner
+            .readers
+            .checked_sub(1)
+            .expect("readers should be registered");
+        if inner.readers == 0 {
+            inner.value.take()
+        } else {
+            inner.value.clone()
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner<T>> {
+        self.inner.lock().expect("slot should not be poisoned")
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn lock(&self) -> core::cell::RefMut<'_, Inner<T>> {
+        self.inner.borrow_mut()
+    }
+}
@@ -14,11 +14,14 @@ use ragu_primitives::Element;
 
 use alloc::vec::Vec;
 
+use crate::{circuits::native::unified::Instance, components::ipa::Opening};
+
 #[derive(Clone)]
 pub(crate) struct Application<C: Cycle, R: Rank> {
     pub(crate) circuit_id: CircuitIndex,
     pub(crate) left_header: Vec<C::CircuitField>,
     pub(crate) right_header: Vec<C::CircuitField>,
+    pub(crate) output_header: Vec<C::CircuitField>,
     pub(crate) rx: structured::Polynomial<C::CircuitField, R>,
     pub(crate) blind: C::CircuitField,
     pub(crate) commitment: C::HostCurve,
@@ -211,3 +214,69 @@ pub(crate) struct InternalCircuits<C: Cycle, R: Rank> {
     pub(crate) compute_v_blind: C::CircuitField,
     pub(crate) compute_v_commitment: C::HostCurve,
 }
+
+/// The statement of a [`CompressedProof`](super::CompressedProof): everything
+/// that the verifier of the uncompressed proof reads other than polynomials,
+/// along with commitments to those polynomials.
+#[derive(Clone)]
+pub(crate) struct Statement<C: Cycle> {
+    pub(crate) digest: C::CircuitField,
+    pub(crate) circuit_id: CircuitIndex,
+    pub(crate) left_header: Vec<C::CircuitField>,
+    pub(crate) right_header: Vec<C::CircuitField>,
+    pub(crate) instance: Instance<C>,
+    /// Commitments to the native rx polynomials, in the order of
+    /// [`RxComponent::ALL`](crate::components::claims::native::RxComponent::ALL).
+    pub(crate) native_rx_commitments: Vec<C::HostCurve>,
+    pub(crate) registry_xy_commitment: C::HostCurve,
+    pub(crate) registry_wx0_commitment: C::HostCurve,
+    pub(crate) registry_wx1_commitment: C::HostCurve,
+    pub(crate) registry_wy_commitment: C::HostCurve,
+    pub(crate) p_commitment: C::HostCurve,
+    /// Commitments to the nested rx polynomials, in the order given by
+    /// [`RxComponent::index`](crate::components::claims::nested::RxComponent::index).
+    pub(crate) nested_rx_commitments: Vec<C::NestedCurve>,
+}
+
+/// Commitments to the low and high halves of the products whose middle
+/// coefficients are the folded revdot claims of a [`CompressedProof`](super::CompressedProof).
+#[derive(Clone)]
+pub(crate) struct Products<C: Cycle> {
+    pub(crate) native_low_commitment: C::HostCurve,
+    pub(crate) native_high_commitment: C::HostCurve,
+    pub(crate) nested_low_commitment: C::NestedCurve,
+    pub(crate) nested_high_commitment: C::NestedCurve,
+}
+
+/// Evaluations of the committed polynomials of a
+/// [`CompressedProof`](super::CompressedProof) at the challenge points $r$ and
+/// $zr$ (or their nested counterparts).
+#[derive(Clone)]
+pub(crate) struct Evaluations<C: Cycle> {
+    pub(crate) native_rx_at_r: Vec<C::CircuitField>,
+    pub(crate) native_rx_at_zr: Vec<C::CircuitField>,
+    pub(crate) native_low_at_r: C::CircuitField,
+    pub(crate) native_high_at_r: C::CircuitField,
+    pub(crate) registry_xy_at_r: C::CircuitField,
+    pub(crate) registry_wx0_at_r: C::CircuitField,
+    pub(crate) registry_wx1_at_r: C::CircuitField,
+    pub(crate) registry_wy_at_r: C::CircuitField,
+    /// The $x$ challenges of the child proofs, as witnessed in the preamble.
+    pub(crate) child_x: [C::CircuitField; 2],
+    pub(crate) nested_rx_at_r: Vec<C::ScalarField>,
+    pub(crate) nested_rx_at_zr: Vec<C::ScalarField>,
+    pub(crate) nested_low_at_r: C::ScalarField,
+    pub(crate) nested_high_at_r: C::ScalarField,
+}
+
+/// Openings of the commitments of a [`CompressedProof`](super::CompressedProof)
+/// to its [`Evaluations`].
+#[derive(Clone)]
+pub(crate) struct Openings<C: Cycle> {
+    pub(crate) native_at_r: Opening<C::HostCurve>,
+    pub(crate) native_at_zr: Opening<C::HostCurve>,
+    pub(crate) child_x: Opening<C::HostCurve>,
+    pub(crate) p: Opening<C::HostCurve>,
+    pub(crate) nested_at_r: Opening<C::NestedCurve>,
+    pub(crate) nested_at_zr: Opening<C::NestedCurve>,
+}
@@ -0,0 +1,848 @@
+//! Canonical byte encoding of [`Proof`]s and [`CompressedProof`]s.
+//!
+//! The encoding begins with a [`VERSION`] byte and the [`Rank`] of the proof,
+//! followed by each component of the proof in the order in which it appears in
+//! the [`Proof`] structure. Within a component, fields are written in their
+//! declaration order using the following primitives:
+//!
+//! * Field elements are written using their canonical [`PrimeField::Repr`].
+//! * Curve points are written using their canonical (compressed)
+//!   [`GroupEncoding::Repr`].
+//! * Lengths and circuit indices are written as little-endian `u32` values.
+//! * Headers are written as their `HEADER_SIZE` elements, with no length
+//!   prefix, so that a header of any other length cannot be decoded.
+//! * Unstructured polynomials are written as their `R::num_coeffs()`
+//!   coefficients, in ascending order of degree, with no length prefix.
+//! * Structured polynomials are written as their four coefficient vectors
+//!   $\mathbf{a}, \mathbf{b}, \mathbf{c}, \mathbf{d}$, each as a length prefix
+//!   followed by its coefficients. Trailing zero coefficients are never
+//!   written, so that every polynomial has exactly one encoding.
+//!
+//! Decoding rejects any input that would not be produced by encoding some
+//! proof, including non-canonical field elements and curve points, vectors that
+//! exceed their maximum length and trailing bytes.
+//!
+//! A [`CompressedProof`] is encoded in the same way, except that vectors whose
+//! lengths are fixed by the protocol (commitments and evaluations of the rx
+//! polynomials, and the rounds of each inner product argument) have no length
+//! prefix. Its components are written in the order in which the prover
+//! produces them, so that every prefix of the encoding is the transcript from
+//! which the next challenges are derived.
+
+use arithmetic::{CurveAffine, Cycle};
+use ff::PrimeField;
+use pasta_curves::group::GroupEncoding;
+use ragu_circuits::{
+    polynomials::{Rank, structured, unstructured},
+    registry::CircuitIndex,
+};
+use ragu_core::{Error, Result};
+use ragu_primitives::vec::Len;
+
+use alloc::vec::Vec;
+
+use super::*;
+use crate::components::{claims::nested, ipa::Opening};
+
+/// The version of the proof encoding produced by this crate.
+pub(crate) const VERSION: u8 = 0;
+
+/// Accumulates a canonical encoding.
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn new() -> Self {
+        Writer { bytes: Vec::new() }
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the encoding accumulated so far.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub(crate) fn byte(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn len(&mut self, len: usize) {
+        let len = u32::try_from(len).expect("lengths always fit in a u32");
+        self.bytes.extend_from_slice(&len.to_le_bytes());
+    }
+
+    fn circuit_index(&mut self, index: &CircuitIndex) {
+        self.len(index.get());
+    }
+
+    pub(crate) fn field<F: PrimeField>(&mut self, value: &F) {
+        self.bytes.extend_from_slice(value.to_repr().as_ref());
+    }
+
+    /// Writes a length-prefixed byte string.
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn point<P: CurveAffine>(&mut self, point: &P) {
+        self.bytes.extend_from_slice(point.to_bytes().as_ref());
+    }
+
+    pub(crate) fn fields_exact<F: PrimeField>(&mut self, values: &[F]) {
+        values.iter().for_each(|value| self.field(value));
+    }
+
+    fn opening<G: CurveAffine>(&mut self, opening: &Opening<G>) {
+        self.point(&opening.s_commitment);
+        opening.l.iter().zip(&opening.r).for_each(|(l, r)| {
+            self.point(l);
+            self.point(r);
+        });
+        self.field(&opening.a);
+        self.field(&opening.blind);
+    }
+
+    fn header<F: PrimeField>(&mut self, values: &[F]) {
+        self.fields_exact(values);
+    }
+
+    fn fields<F: PrimeField>(&mut self, values: &[F]) {
+        self.len(values.len());
+        values.iter().for_each(|value| self.field(value));
+    }
+
+    fn unstructured<F: PrimeField, R: Rank>(&mut self, poly: &unstructured::Polynomial<F, R>) {
+        poly.iter_coeffs().for_each(|coeff| self.field(&coeff));
+    }
+
+    pub(crate) fn structured<F: PrimeField, R: Rank>(
+        &mut self,
+        poly: &structured::Polynomial<F, R>,
+    ) {
+        for coeffs in poly.coeff_vectors() {
+            let trimmed = coeffs
+                .iter()
+                .rposition(|coeff| !bool::from(coeff.is_zero()))
+                .map_or(0, |last| last + 1);
+            self.fields(&coeffs[..trimmed]);
+        }
+    }
+}
+
+/// Reads a canonical encoding.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    header_size: Option<usize>,
+}
+
+pub(crate) fn malformed(msg: &'static str) -> Error {
+    Error::MalformedEncoding(msg.into())
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader {
+            bytes,
+            header_size: None,
+        }
+    }
+
+    /// Creates a reader for an encoding whose headers each consist of
+    /// `HEADER_SIZE` elements.
+    pub(crate) fn with_header_size<const HEADER_SIZE: usize>(bytes: &'a [u8]) -> Self {
+        Reader {
+            bytes,
+            header_size: Some(HEADER_SIZE),
+        }
+    }
+
+    /// Ensures that the entire input has been consumed.
+    pub(crate) fn finish(self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(malformed("trailing bytes after input"));
+        }
+
+        Ok(())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(malformed("unexpected end of input"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn len(&mut self) -> Result<usize> {
+        let bytes = self.take(4)?.try_into().expect("took exactly 4 bytes");
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn circuit_index(&mut self) -> Result<CircuitIndex> {
+        Ok(CircuitIndex::new(self.len()?))
+    }
+
+    pub(crate) fn field<F: PrimeField>(&mut self) -> Result<F> {
+        let mut repr = F::Repr::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(self.take(len)?);
+
+        Option::from(F::from_repr(repr)).ok_or_else(|| malformed("non-canonical field element"))
+    }
+
+    fn point<P: CurveAffine>(&mut self) -> Result<P> {
+        let mut repr = <P as GroupEncoding>::Repr::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(self.take(len)?);
+
+        Option::from(P::from_bytes(&repr)).ok_or_else(|| malformed("invalid curve point"))
+    }
+
+    /// Reads the length prefix of a vector whose elements each occupy at
+    /// least one byte, which bounds the allocation by the size of the input.
+    pub(crate) fn count(&mut self) -> Result<usize> {
+        let len = self.len()?;
+        if len > self.bytes.len() {
+            return Err(malformed("unexpected end of input"));
+        }
+
+        Ok(len)
+    }
+
+    /// Reads a byte string written by [`Writer::bytes`].
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.count()?;
+        self.take(len)
+    }
+
+    fn header<F: PrimeField>(&mut self) -> Result<Vec<F>> {
+        let len = self
+            .header_size
+            .expect("headers are only decoded by readers with a header size");
+
+        self.fields_exact(len)
+    }
+
+    fn fields<F: PrimeField>(&mut self) -> Result<Vec<F>> {
+        let len = self.count()?;
+
+        self.fields_exact(len)
+    }
+
+    fn fields_exact<F: PrimeField>(&mut self, len: usize) -> Result<Vec<F>> {
+        (0..len).map(|_| self.field()).collect()
+    }
+
+    fn points_exact<P: CurveAffine>(&mut self, len: usize) -> Result<Vec<P>> {
+        (0..len).map(|_| self.point()).collect()
+    }
+
+    fn opening<G: CurveAffine>(&mut self, rounds: usize) -> Result<Opening<G>> {
+        let s_commitment = self.point()?;
+        let (l, r) = (0..rounds)
+            .map(|_| -> Result<(G, G)> { Ok((self.point()?, self.point()?)) })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        Ok(Opening {
+            s_commitment,
+            l,
+            r,
+            a: self.field()?,
+            blind: self.field()?,
+        })
+    }
+
+    fn unstructured<F: PrimeField, R: Rank>(&mut self) -> Result<unstructured::Polynomial<F, R>> {
+        let coeffs = (0..R::num_coeffs())
+            .map(|_| self.field())
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(unstructured::Polynomial::from_coeffs(coeffs))
+    }
+
+    pub(crate) fn structured<F: PrimeField, R: Rank>(
+        &mut self,
+    ) -> Result<structured::Polynomial<F, R>> {
+        let mut vectors: [Vec<F>; 4] = Default::default();
+        for coeffs in vectors.iter_mut() {
+            *coeffs = self.fields()?;
+            if coeffs.len() > R::n() {
+                return Err(malformed("structured polynomial vector exceeds rank"));
+            }
+            if coeffs.last().is_some_and(|last| last.is_zero().into()) {
+                return Err(malformed("structured polynomial has trailing zero"));
+            }
+        }
+        let [a, b, c, d] = vectors;
+
+        let mut poly = structured::Polynomial::new();
+        {
+            let forward = poly.forward();
+            *forward.a = a;
+            *forward.b = b;
+            *forward.c = c;
+        }
+        *poly.backward().c = d;
+
+        Ok(poly)
+    }
+}
+
+/// Components of a [`Proof`] or [`CompressedProof`] that can be canonically
+/// encoded.
+pub(crate) trait Encode: Sized {
+    fn encode(&self, writer: &mut Writer);
+    fn decode(reader: &mut Reader<'_>) -> Result<Self>;
+}
+
+/// Implements [`Encode`] for a proof component by encoding each of the listed
+/// fields in order using the named [`Writer`] / [`Reader`] method.
+macro_rules! impl_encode {
+    ($component:ident { $($field:ident: $kind:ident),* $(,)? }) => {
+        impl<C: Cycle, R: Rank> Encode for $component<C, R> {
+            fn encode(&self, writer: &mut Writer) {
+                $(writer.$kind(&self.$field);)*
+            }
+
+            fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+                Ok($component {
+                    $($field: reader.$kind()?,)*
+                })
+            }
+        }
+    };
+}
+
+impl_encode!(Application {
+    circuit_id: circuit_index,
+    left_header: header,
+    right_header: header,
+    output_header: header,
+    rx: structured,
+    blind: field,
+    commitment: point,
+});
+
+impl_encode!(Preamble {
+    native_rx: structured,
+    native_blind: field,
+    native_commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(SPrime {
+    registry_wx0_poly: unstructured,
+    registry_wx0_blind: field,
+    registry_wx0_commitment: point,
+    registry_wx1_poly: unstructured,
+    registry_wx1_blind: field,
+    registry_wx1_commitment: point,
+    nested_s_prime_rx: structured,
+    nested_s_prime_blind: field,
+    nested_s_prime_commitment: point,
+});
+
+impl_encode!(ErrorN {
+    native_rx: structured,
+    native_blind: field,
+    native_commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(ErrorM {
+    registry_wy_poly: structured,
+    registry_wy_blind: field,
+    registry_wy_commitment: point,
+    native_rx: structured,
+    native_blind: field,
+    native_commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(AB {
+    a_poly: structured,
+    a_blind: field,
+    a_commitment: point,
+    b_poly: structured,
+    b_blind: field,
+    b_commitment: point,
+    c: field,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(Query {
+    registry_xy_poly: unstructured,
+    registry_xy_blind: field,
+    registry_xy_commitment: point,
+    native_rx: structured,
+    native_blind: field,
+    native_commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(F {
+    poly: unstructured,
+    blind: field,
+    commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(Eval {
+    native_rx: structured,
+    native_blind: field,
+    native_commitment: point,
+    nested_rx: structured,
+    nested_blind: field,
+    nested_commitment: point,
+});
+
+impl_encode!(InternalCircuits {
+    hashes_1_rx: structured,
+    hashes_1_blind: field,
+    hashes_1_commitment: point,
+    hashes_2_rx: structured,
+    hashes_2_blind: field,
+    hashes_2_commitment: point,
+    partial_collapse_rx: structured,
+    partial_collapse_blind: field,
+    partial_collapse_commitment: point,
+    full_collapse_rx: structured,
+    full_collapse_blind: field,
+    full_collapse_commitment: point,
+    compute_v_rx: structured,
+    compute_v_blind: field,
+    compute_v_commitment: point,
+});
+
+impl<C: Cycle, R: Rank> Encode for P<C, R> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.unstructured(&self.poly);
+        writer.field(&self.blind);
+        writer.point(&self.commitment);
+        writer.field(&self.v);
+        writer.structured(&self.endoscalar_rx);
+        writer.structured(&self.points_rx);
+        // The number of endoscaling steps is fixed, so no length prefix.
+        self.step_rxs
+            .iter()
+            .for_each(|step_rx| writer.structured(step_rx));
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(P {
+            poly: reader.unstructured()?,
+            blind: reader.field()?,
+            commitment: reader.point()?,
+            v: reader.field()?,
+            endoscalar_rx: reader.structured()?,
+            points_rx: reader.structured()?,
+            step_rxs: (0..NumStepsLen::<NUM_ENDOSCALING_POINTS>::len())
+                .map(|_| reader.structured())
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl<C: Cycle> Encode for Challenges<C> {
+    fn encode(&self, writer: &mut Writer) {
+        for challenge in [
+            &self.w,
+            &self.y,
+            &self.z,
+            &self.mu,
+            &self.nu,
+            &self.mu_prime,
+            &self.nu_prime,
+            &self.x,
+            &self.alpha,
+            &self.u,
+            &self.pre_beta,
+        ] {
+            writer.field(challenge);
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(Challenges {
+            w: reader.field()?,
+            y: reader.field()?,
+            z: reader.field()?,
+            mu: reader.field()?,
+            nu: reader.field()?,
+            mu_prime: reader.field()?,
+            nu_prime: reader.field()?,
+            x: reader.field()?,
+            alpha: reader.field()?,
+            u: reader.field()?,
+            pre_beta: reader.field()?,
+        })
+    }
+}
+
+impl<C: Cycle, R: Rank> Encode for Proof<C, R> {
+    fn encode(&self, writer: &mut Writer) {
+        write_version::<R>(writer);
+        writer.field(&self.digest);
+        self.application.encode(writer);
+        self.preamble.encode(writer);
+        self.s_prime.encode(writer);
+        self.error_n.encode(writer);
+        self.error_m.encode(writer);
+        self.ab.encode(writer);
+        self.query.encode(writer);
+        self.f.encode(writer);
+        self.eval.encode(writer);
+        self.p.encode(writer);
+        self.challenges.encode(writer);
+        self.circuits.encode(writer);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        read_version::<R>(reader)?;
+
+        Ok(Proof {
+            digest: reader.field()?,
+            application: Encode::decode(reader)?,
+            preamble: Encode::decode(reader)?,
+            s_prime: Encode::decode(reader)?,
+            error_n: Encode::decode(reader)?,
+            error_m: Encode::decode(reader)?,
+            ab: Encode::decode(reader)?,
+            query: Encode::decode(reader)?,
+            f: Encode::decode(reader)?,
+            eval: Encode::decode(reader)?,
+            p: Encode::decode(reader)?,
+            challenges: Encode::decode(reader)?,
+            circuits: Encode::decode(reader)?,
+        })
+    }
+}
+
+/// Writes the version and rank that every encoding begins with.
+pub(crate) fn write_version<R: Rank>(writer: &mut Writer) {
+    writer.byte(VERSION);
+    writer.byte(R::RANK as u8);
+}
+
+fn read_version<R: Rank>(reader: &mut Reader<'_>) -> Result<()> {
+    if reader.byte()? != VERSION {
+        return Err(malformed("unsupported proof encoding version"));
+    }
+    if reader.byte()? != R::RANK as u8 {
+        return Err(malformed("proof was created for a different rank"));
+    }
+
+    Ok(())
+}
+
+impl<C: Cycle> Encode for Instance<C> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.point(&self.nested_preamble_commitment);
+        writer.field(&self.w);
+        writer.point(&self.nested_s_prime_commitment);
+        writer.field(&self.y);
+        writer.field(&self.z);
+        writer.point(&self.nested_error_m_commitment);
+        writer.field(&self.mu);
+        writer.field(&self.nu);
+        writer.point(&self.nested_error_n_commitment);
+        writer.field(&self.mu_prime);
+        writer.field(&self.nu_prime);
+        writer.field(&self.c);
+        writer.point(&self.nested_ab_commitment);
+        writer.field(&self.x);
+        writer.point(&self.nested_query_commitment);
+        writer.field(&self.alpha);
+        writer.point(&self.nested_f_commitment);
+        writer.field(&self.u);
+        writer.point(&self.nested_eval_commitment);
+        writer.field(&self.pre_beta);
+        writer.field(&self.v);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(Instance {
+            nested_preamble_commitment: reader.point()?,
+            w: reader.field()?,
+            nested_s_prime_commitment: reader.point()?,
+            y: reader.field()?,
+            z: reader.field()?,
+            nested_error_m_commitment: reader.point()?,
+            mu: reader.field()?,
+            nu: reader.field()?,
+            nested_error_n_commitment: reader.point()?,
+            mu_prime: reader.field()?,
+            nu_prime: reader.field()?,
+            c: reader.field()?,
+            nested_ab_commitment: reader.point()?,
+            x: reader.field()?,
+            nested_query_commitment: reader.point()?,
+            alpha: reader.field()?,
+            nested_f_commitment: reader.point()?,
+            u: reader.field()?,
+            nested_eval_commitment: reader.point()?,
+            pre_beta: reader.field()?,
+            v: reader.field()?,
+        })
+    }
+}
+
+// The numbers of rx polynomials are fixed, so their commitments and
+// evaluations are written without length prefixes.
+
+impl<C: Cycle> Encode for Statement<C> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.field(&self.digest);
+        writer.circuit_index(&self.circuit_id);
+        writer.header(&self.left_header);
+        writer.header(&self.right_header);
+        self.instance.encode(writer);
+        self.native_rx_commitments
+            .iter()
+            .for_each(|commitment| writer.point(commitment));
+        writer.point(&self.registry_xy_commitment);
+        writer.point(&self.registry_wx0_commitment);
+        writer.point(&self.registry_wx1_commitment);
+        writer.point(&self.registry_wy_commitment);
+        writer.point(&self.p_commitment);
+        self.nested_rx_commitments
+            .iter()
+            .for_each(|commitment| writer.point(commitment));
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(Statement {
+            digest: reader.field()?,
+            circuit_id: reader.circuit_index()?,
+            left_header: reader.header()?,
+            right_header: reader.header()?,
+            instance: Encode::decode(reader)?,
+            native_rx_commitments: reader.points_exact(RxComponent::ALL.len())?,
+            registry_xy_commitment: reader.point()?,
+            registry_wx0_commitment: reader.point()?,
+            registry_wx1_commitment: reader.point()?,
+            registry_wy_commitment: reader.point()?,
+            p_commitment: reader.point()?,
+            nested_rx_commitments: reader.points_exact(nested::num_rx_components())?,
+        })
+    }
+}
+
+impl<C: Cycle> Encode for Products<C> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.point(&self.native_low_commitment);
+        writer.point(&self.native_high_commitment);
+        writer.point(&self.nested_low_commitment);
+        writer.point(&self.nested_high_commitment);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(Products {
+            native_low_commitment: reader.point()?,
+            native_high_commitment: reader.point()?,
+            nested_low_commitment: reader.point()?,
+            nested_high_commitment: reader.point()?,
+        })
+    }
+}
+
+impl<C: Cycle> Encode for Evaluations<C> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.fields_exact(&self.native_rx_at_r);
+        writer.fields_exact(&self.native_rx_at_zr);
+        writer.field(&self.native_low_at_r);
+        writer.field(&self.native_high_at_r);
+        writer.field(&self.registry_xy_at_r);
+        writer.field(&self.registry_wx0_at_r);
+        writer.field(&self.registry_wx1_at_r);
+        writer.field(&self.registry_wy_at_r);
+        writer.fields_exact(&self.child_x);
+        writer.fields_exact(&self.nested_rx_at_r);
+        writer.fields_exact(&self.nested_rx_at_zr);
+        writer.field(&self.nested_low_at_r);
+        writer.field(&self.nested_high_at_r);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        let num_native = RxComponent::ALL.len();
+        let num_nested = nested::num_rx_components();
+
+        Ok(Evaluations {
+            native_rx_at_r: reader.fields_exact(num_native)?,
+            native_rx_at_zr: reader.fields_exact(num_native)?,
+            native_low_at_r: reader.field()?,
+            native_high_at_r: reader.field()?,
+            registry_xy_at_r: reader.field()?,
+            registry_wx0_at_r: reader.field()?,
+            registry_wx1_at_r: reader.field()?,
+            registry_wy_at_r: reader.field()?,
+            child_x: [reader.field()?, reader.field()?],
+            nested_rx_at_r: reader.fields_exact(num_nested)?,
+            nested_rx_at_zr: reader.fields_exact(num_nested)?,
+            nested_low_at_r: reader.field()?,
+            nested_high_at_r: reader.field()?,
+        })
+    }
+}
+
+impl<C: Cycle, R: Rank> Encode for CompressedProof<C, R> {
+    fn encode(&self, writer: &mut Writer) {
+        write_version::<R>(writer);
+        self.statement.encode(writer);
+        self.products.encode(writer);
+        self.evaluations.encode(writer);
+        for opening in [
+            &self.openings.native_at_r,
+            &self.openings.native_at_zr,
+            &self.openings.child_x,
+            &self.openings.p,
+        ] {
+            writer.opening(opening);
+        }
+        writer.opening(&self.openings.nested_at_r);
+        writer.opening(&self.openings.nested_at_zr);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self> {
+        read_version::<R>(reader)?;
+
+        // Every opening is of a vector of `R::num_coeffs()` coefficients.
+        let rounds = R::RANK as usize;
+
+        Ok(CompressedProof {
+            statement: Encode::decode(reader)?,
+            products: Encode::decode(reader)?,
+            evaluations: Encode::decode(reader)?,
+            openings: Openings {
+                native_at_r: reader.opening(rounds)?,
+                native_at_zr: reader.opening(rounds)?,
+                child_x: reader.opening(rounds)?,
+                p: reader.opening(rounds)?,
+                nested_at_r: reader.opening(rounds)?,
+                nested_at_zr: reader.opening(rounds)?,
+            },
+            _marker: core::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::PrimeField;
+    use pasta_curves::{Fp, group::GroupEncoding};
+    use ragu_circuits::polynomials::R;
+    use ragu_core::{Error, Result};
+    use ragu_pasta::Pasta;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{ApplicationBuilder, Proof, step::internal::trivial::Trivial};
+
+    #[test]
+    fn test_proof_encoding() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let (proof, ()) = app.seed(&mut rng, Trivial::new(), ())?;
+        let bytes = proof.to_bytes();
+        let decoded = Proof::<Pasta, R<13>>::from_bytes::<4>(&bytes)?;
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert!(app.verify(&decoded.carry::<()>(()), &mut rng)?);
+
+        let assert_malformed = |bytes: &[u8]| {
+            assert!(matches!(
+                Proof::<Pasta, R<13>>::from_bytes::<4>(bytes),
+                Err(Error::MalformedEncoding(_))
+            ));
+        };
+
+        // Truncated and extended encodings.
+        assert_malformed(&bytes[..bytes.len() - 1]);
+        assert_malformed(&[&bytes[..], &[0]].concat());
+
+        // Unknown version.
+        let mut tampered = bytes.clone();
+        tampered[0] = super::VERSION + 1;
+        assert_malformed(&tampered);
+
+        // Proofs for a different rank.
+        assert!(matches!(
+            Proof::<Pasta, R<14>>::from_bytes::<4>(&bytes),
+            Err(Error::MalformedEncoding(_))
+        ));
+
+        // Headers of the wrong length, whether read with a different header
+        // size or encoded from a proof with a longer header.
+        assert!(matches!(
+            Proof::<Pasta, R<13>>::from_bytes::<3>(&bytes),
+            Err(Error::MalformedEncoding(_))
+        ));
+        assert!(matches!(
+            Proof::<Pasta, R<13>>::from_bytes::<5>(&bytes),
+            Err(Error::MalformedEncoding(_))
+        ));
+        let mut long_header = proof.clone();
+        long_header.application.output_header.push(Fp::from(1));
+        assert_malformed(&long_header.to_bytes());
+
+        // Overwrites the encoding of a known value with bytes that are not a
+        // canonical field element or curve point.
+        let tamper_at = |bytes: &[u8], needle: &[u8]| {
+            let offset = bytes
+                .windows(needle.len())
+                .position(|window| window == needle)
+                .expect("value is encoded exactly once");
+            let mut tampered = bytes.to_vec();
+            tampered[offset..offset + needle.len()].fill(0xff);
+            tampered
+        };
+
+        // The application digest.
+        assert_malformed(&tamper_at(&bytes, proof.digest.to_repr().as_ref()));
+
+        // An element of the left header, given a distinctive value so that its
+        // encoding can be found.
+        let mut marked = proof.clone();
+        let marker = Fp::from(0x1234_5678_9abc_def0);
+        marked.application.left_header[0] = marker;
+        let marked_bytes = marked.to_bytes();
+        assert_eq!(
+            Proof::<Pasta, R<13>>::from_bytes::<4>(&marked_bytes)?.to_bytes(),
+            marked_bytes
+        );
+        assert_malformed(&tamper_at(&marked_bytes, marker.to_repr().as_ref()));
+
+        // The application commitment.
+        assert_malformed(&tamper_at(
+            &bytes,
+            proof.application.commitment.to_bytes().as_ref(),
+        ));
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub(crate) mod components;
+pub(crate) mod encoding;
 pub(crate) use components::*;
 
 use arithmetic::Cycle;
@@ -9,13 +10,15 @@ use ragu_circuits::{
     polynomials::{Rank, structured, unstructured},
     registry::CircuitIndex,
 };
-use ragu_primitives::vec::Len;
+use ragu_core::Result;
+use ragu_primitives::vec::{ConstLen, FixedVec, Len};
 
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 
-use crate::circuits::nested::NUM_ENDOSCALING_POINTS;
-use crate::components::endoscalar::NumStepsLen;
+use crate::circuits::{native::unified::Instance, nested::NUM_ENDOSCALING_POINTS};
+use crate::components::{claims::native::RxComponent, endoscalar::NumStepsLen};
 use crate::header::Header;
+use crate::step::internal::padded;
 
 /// Represents proof-carrying data, a recursive proof for the correctness of
 /// some accompanying data.
@@ -36,9 +39,109 @@ impl<C: Cycle, R: Rank, H: Header<C::CircuitField>> Clone for Pcd<'_, C, R, H> {
     }
 }
 
+/// Proof-carrying data whose [`Header`] type has been erased by encoding its
+/// data, so that [`Pcd`] for different headers can be verified together with
+/// [`Application::verify_batch`](crate::Application::verify_batch).
+pub struct ErasedPcd<'a, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    pub(crate) proof: &'a Proof<C, R>,
+    pub(crate) output_header: FixedVec<C::CircuitField, ConstLen<HEADER_SIZE>>,
+}
+
+impl<'a, C: Cycle, R: Rank, const HEADER_SIZE: usize> ErasedPcd<'a, C, R, HEADER_SIZE> {
+    /// Erases the [`Header`] type of some [`Pcd`] by encoding its data.
+    ///
+    /// Fails if the header data cannot be encoded within `HEADER_SIZE`
+    /// elements.
+    pub fn new<H: Header<C::CircuitField>>(pcd: &'a Pcd<'_, C, R, H>) -> Result<Self> {
+        Self::from_parts::<H>(&pcd.proof, pcd.data.clone())
+    }
+
+    pub(crate) fn from_parts<H: Header<C::CircuitField>>(
+        proof: &'a Proof<C, R>,
+        data: H::Data<'_>,
+    ) -> Result<Self> {
+        Ok(ErasedPcd {
+            proof,
+            output_header: padded::encode::<H, HEADER_SIZE, _>(data)?,
+        })
+    }
+}
+
+/// Represents proof-carrying data whose proof has been compressed with
+/// [`Application::compress`](crate::Application::compress).
+pub struct CompressedPcd<'source, C: Cycle, R: Rank, H: Header<C::CircuitField>> {
+    /// The compressed recursive proof for the accompanying data.
+    pub proof: CompressedProof<C, R>,
+
+    /// Data needed to witness a [`Header`] within a [`Step`](super::Step).
+    pub data: H::Data<'source>,
+}
+
+impl<C: Cycle, R: Rank, H: Header<C::CircuitField>> Clone for CompressedPcd<'_, C, R, H> {
+    fn clone(&self) -> Self {
+        CompressedPcd {
+            proof: self.proof.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+/// A succinct form of a [`Proof`], whose size is logarithmic in the [`Rank`].
+///
+/// Every polynomial of the proof is replaced by its commitment, its
+/// evaluations at the points that the verifier needs and inner product
+/// arguments that open the commitments to those evaluations. The revdot
+/// claims of the proof are checked through the commitments to the low and
+/// high halves of a product of polynomials whose middle coefficient is their
+/// folded value.
+///
+/// Compressed proofs can only be checked with
+/// [`Application::verify_compressed`](crate::Application::verify_compressed),
+/// and can no longer be fused.
+#[derive(Clone)]
+pub struct CompressedProof<C: Cycle, R: Rank> {
+    pub(crate) statement: Statement<C>,
+    pub(crate) products: Products<C>,
+    pub(crate) evaluations: Evaluations<C>,
+    pub(crate) openings: Openings<C>,
+    pub(crate) _marker: core::marker::PhantomData<R>,
+}
+
+impl<C: Cycle, R: Rank> CompressedProof<C, R> {
+    /// Returns the [digest](crate::Application::digest) of the application
+    /// that created this proof.
+    pub fn application_digest(&self) -> C::CircuitField {
+        self.statement.digest
+    }
+
+    /// Serializes this proof into its canonical byte encoding.
+    ///
+    /// See [`Proof::to_bytes`] for the guarantees of the encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = encoding::Writer::new();
+        encoding::Encode::encode(self, &mut writer);
+        writer.into_bytes()
+    }
+
+    /// Deserializes a proof from the canonical byte encoding produced by
+    /// [`CompressedProof::to_bytes`].
+    ///
+    /// Returns [`Error::MalformedEncoding`](ragu_core::Error::MalformedEncoding)
+    /// under the same conditions as [`Proof::from_bytes`].
+    pub fn from_bytes<const HEADER_SIZE: usize>(bytes: &[u8]) -> Result<Self> {
+        let mut reader = encoding::Reader::with_header_size::<HEADER_SIZE>(bytes);
+        let proof = encoding::Encode::decode(&mut reader)?;
+        reader.finish()?;
+
+        Ok(proof)
+    }
+}
+
 /// Represents a recursive proof for the correctness of some computation.
 #[derive(Clone)]
 pub struct Proof<C: Cycle, R: Rank> {
+    /// The digest of the application that created this proof.
+    pub(crate) digest: C::CircuitField,
     pub(crate) application: Application<C, R>,
     pub(crate) preamble: Preamble<C, R>,
     pub(crate) s_prime: SPrime<C, R>,
@@ -58,6 +161,139 @@ impl<C: Cycle, R: Rank> Proof<C, R> {
     pub fn carry<H: Header<C::CircuitField>>(self, data: H::Data<'_>) -> Pcd<'_, C, R, H> {
         Pcd { proof: self, data }
     }
+
+    /// Returns the [digest](crate::Application::digest) of the application
+    /// that created this proof.
+    pub fn application_digest(&self) -> C::CircuitField {
+        self.digest
+    }
+
+    /// Returns the native rx polynomial of this proof for the given claim
+    /// component.
+    pub(crate) fn native_rx(
+        &self,
+        component: RxComponent,
+    ) -> &structured::Polynomial<C::CircuitField, R> {
+        use RxComponent::*;
+        match component {
+            AbA => &self.ab.a_poly,
+            AbB => &self.ab.b_poly,
+            Application => &self.application.rx,
+            Hashes1 => &self.circuits.hashes_1_rx,
+            Hashes2 => &self.circuits.hashes_2_rx,
+            PartialCollapse => &self.circuits.partial_collapse_rx,
+            FullCollapse => &self.circuits.full_collapse_rx,
+            ComputeV => &self.circuits.compute_v_rx,
+            Preamble => &self.preamble.native_rx,
+            ErrorM => &self.error_m.native_rx,
+            ErrorN => &self.error_n.native_rx,
+            Query => &self.query.native_rx,
+            Eval => &self.eval.native_rx,
+        }
+    }
+
+    /// Returns the native rx polynomial of this proof for the given claim
+    /// component, along with its blinding factor and commitment.
+    pub(crate) fn native_rx_commitment(
+        &self,
+        component: RxComponent,
+    ) -> (C::CircuitField, C::HostCurve) {
+        use RxComponent::*;
+        match component {
+            AbA => (self.ab.a_blind, self.ab.a_commitment),
+            AbB => (self.ab.b_blind, self.ab.b_commitment),
+            Application => (self.application.blind, self.application.commitment),
+            Hashes1 => (
+                self.circuits.hashes_1_blind,
+                self.circuits.hashes_1_commitment,
+            ),
+            Hashes2 => (
+                self.circuits.hashes_2_blind,
+                self.circuits.hashes_2_commitment,
+            ),
+            PartialCollapse => (
+                self.circuits.partial_collapse_blind,
+                self.circuits.partial_collapse_commitment,
+            ),
+            FullCollapse => (
+                self.circuits.full_collapse_blind,
+                self.circuits.full_collapse_commitment,
+            ),
+            ComputeV => (
+                self.circuits.compute_v_blind,
+                self.circuits.compute_v_commitment,
+            ),
+            Preamble => (self.preamble.native_blind, self.preamble.native_commitment),
+            ErrorM => (self.error_m.native_blind, self.error_m.native_commitment),
+            ErrorN => (self.error_n.native_blind, self.error_n.native_commitment),
+            Query => (self.query.native_blind, self.query.native_commitment),
+            Eval => (self.eval.native_blind, self.eval.native_commitment),
+        }
+    }
+
+    /// Returns the nested rx polynomials of this proof, in the order given by
+    /// [`RxComponent::index`](crate::components::claims::nested::RxComponent::index).
+    pub(crate) fn nested_rxs(&self) -> Vec<&structured::Polynomial<C::ScalarField, R>> {
+        [&self.p.endoscalar_rx, &self.p.points_rx]
+            .into_iter()
+            .chain(&self.p.step_rxs)
+            .collect()
+    }
+
+    /// Returns the unified instance of this proof, which the internal
+    /// circuits of the proof that fuses it take as public inputs.
+    pub(crate) fn unified_instance(&self) -> Instance<C> {
+        Instance {
+            nested_preamble_commitment: self.preamble.nested_commitment,
+            w: self.challenges.w,
+            nested_s_prime_commitment: self.s_prime.nested_s_prime_commitment,
+            y: self.challenges.y,
+            z: self.challenges.z,
+            nested_error_m_commitment: self.error_m.nested_commitment,
+            mu: self.challenges.mu,
+            nu: self.challenges.nu,
+            nested_error_n_commitment: self.error_n.nested_commitment,
+            mu_prime: self.challenges.mu_prime,
+            nu_prime: self.challenges.nu_prime,
+            c: self.ab.c,
+            nested_ab_commitment: self.ab.nested_commitment,
+            x: self.challenges.x,
+            nested_query_commitment: self.query.nested_commitment,
+            alpha: self.challenges.alpha,
+            nested_f_commitment: self.f.nested_commitment,
+            u: self.challenges.u,
+            nested_eval_commitment: self.eval.nested_commitment,
+            pre_beta: self.challenges.pre_beta,
+            v: self.p.v,
+        }
+    }
+
+    /// Serializes this proof into its canonical byte encoding.
+    ///
+    /// The encoding is versioned and depends only on the contents of the
+    /// proof, so that equal proofs always produce identical bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = encoding::Writer::new();
+        encoding::Encode::encode(self, &mut writer);
+        writer.into_bytes()
+    }
+
+    /// Deserializes a proof from the canonical byte encoding produced by
+    /// [`Proof::to_bytes`].
+    ///
+    /// Returns [`Error::MalformedEncoding`](ragu_core::Error::MalformedEncoding)
+    /// if the input is not exactly the encoding of some proof for this
+    /// [`Rank`] with headers of `HEADER_SIZE` elements, including if it
+    /// contains non-canonical field elements or curve points, vectors of the
+    /// wrong length or trailing bytes. Successful decoding does not imply that
+    /// the proof is valid.
+    pub fn from_bytes<const HEADER_SIZE: usize>(bytes: &[u8]) -> Result<Self> {
+        let mut reader = encoding::Reader::with_header_size::<HEADER_SIZE>(bytes);
+        let proof = encoding::Encode::decode(&mut reader)?;
+        reader.finish()?;
+
+        Ok(proof)
+    }
 }
 
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> crate::Application<'_, C, R, HEADER_SIZE> {
@@ -79,10 +315,12 @@ impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> crate::Application<'_, C, R, H
             zero_structured_nested.commit(C::nested_generators(self.params), nested_blind);
 
         Proof {
+            digest: self.digest,
             application: Application {
                 circuit_id: CircuitIndex::new(0),
                 left_header: vec![C::CircuitField::ZERO; HEADER_SIZE],
                 right_header: vec![C::CircuitField::ZERO; HEADER_SIZE],
+                output_header: vec![C::CircuitField::ZERO; HEADER_SIZE],
                 rx: zero_structured_host.clone(),
                 blind: host_blind,
                 commitment: host_commitment,
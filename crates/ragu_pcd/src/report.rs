@@ -0,0 +1,295 @@
+//! Reporting the constraint usage of the circuits in an [`Application`].
+//!
+//! Every [`Step`](crate::step::Step) is synthesized into a circuit that must
+//! fit within the bounds of the chosen [`Rank`], alongside the internal steps
+//! and recursion circuits that [`ApplicationBuilder::finalize`] registers. The
+//! [`ConstraintReport`] returned by [`Application::constraint_report`] lists
+//! every circuit in the application's registry with its constraint counts and
+//! its headroom against these bounds, along with the [`RoutineMetrics`] and
+//! [`NamespaceMetrics`] that break these counts down by routine and by
+//! namespace.
+//!
+//! [`ApplicationBuilder::finalize`]: crate::ApplicationBuilder::finalize
+
+use arithmetic::Cycle;
+use ragu_circuits::{
+    metrics::{NamespaceMetrics, RoutineMetrics},
+    polynomials::Rank,
+};
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    Application,
+    circuits::native::{InternalCircuitIndex, total_circuit_counts},
+    step::{InternalStepIndex, NUM_INTERNAL_STEPS},
+};
+
+/// The role of a circuit in an [`Application`]'s registry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitKind {
+    /// An application-defined [`Step`](crate::step::Step).
+    Step,
+    /// A step used internally, such as for rerandomization.
+    InternalStep,
+    /// A stage mask used by the recursion circuits.
+    Stage,
+    /// A recursion circuit.
+    Internal,
+}
+
+impl fmt::Display for CircuitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            CircuitKind::Step => "step",
+            CircuitKind::InternalStep => "internal step",
+            CircuitKind::Stage => "stage",
+            CircuitKind::Internal => "internal",
+        })
+    }
+}
+
+/// The constraint usage of a single circuit in a [`ConstraintReport`].
+#[derive(Clone, Debug)]
+pub struct CircuitReport {
+    /// The index of the circuit in the registry.
+    pub index: usize,
+    /// The name of the circuit. Application steps are named by their type.
+    pub name: &'static str,
+    /// The role of the circuit.
+    pub kind: CircuitKind,
+    /// The number of multiplication constraints.
+    pub multiplication_constraints: usize,
+    /// The number of linear constraints.
+    pub linear_constraints: usize,
+    /// The number of additional multiplication constraints that would fit
+    /// within [`ConstraintReport::multiplication_bound`].
+    pub multiplication_headroom: usize,
+    /// The number of additional linear constraints that would fit within
+    /// [`ConstraintReport::linear_bound`].
+    pub linear_headroom: usize,
+    /// The constraints of each routine invoked directly by the circuit. This
+    /// is empty for stage masks, which are not synthesized from circuit code.
+    pub routines: Vec<RoutineMetrics>,
+    /// The constraints of each namespace entered by the circuit. This is
+    /// empty for stage masks.
+    pub namespaces: Vec<NamespaceMetrics>,
+}
+
+/// The constraint usage of every circuit registered in an [`Application`].
+///
+/// See the [module documentation](self) for details. The [`Display`]
+/// implementation renders the report as a table.
+///
+/// [`Display`]: fmt::Display
+#[derive(Clone, Debug)]
+pub struct ConstraintReport {
+    circuits: Vec<CircuitReport>,
+    multiplication_bound: usize,
+    linear_bound: usize,
+    log2_domain_size: u32,
+}
+
+impl ConstraintReport {
+    /// Returns the report for each registered circuit, in order of index.
+    pub fn circuits(&self) -> &[CircuitReport] {
+        &self.circuits
+    }
+
+    /// Returns the maximum number of multiplication constraints in a circuit,
+    /// which is `R::n()` for the chosen [`Rank`].
+    pub fn multiplication_bound(&self) -> usize {
+        self.multiplication_bound
+    }
+
+    /// Returns the maximum number of linear constraints in a circuit, which is
+    /// `R::num_coeffs()` for the chosen [`Rank`].
+    pub fn linear_bound(&self) -> usize {
+        self.linear_bound
+    }
+
+    /// Returns the size of the registry's evaluation domain, the smallest
+    /// power of two that fits every registered circuit.
+    pub fn domain_size(&self) -> usize {
+        1 << self.log2_domain_size
+    }
+
+    /// Returns the base-2 logarithm of [`ConstraintReport::domain_size`].
+    pub fn log2_domain_size(&self) -> u32 {
+        self.log2_domain_size
+    }
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} circuits in a domain of size 2^{} = {}; at most {} multiplication and {} linear constraints per circuit",
+            self.circuits.len(),
+            self.log2_domain_size,
+            self.domain_size(),
+            self.multiplication_bound,
+            self.linear_bound,
+        )?;
+        writeln!(
+            f,
+            "{:>5}  {:<13}  {:>8}  {:>8}  {:>8}  {:>8}  name",
+            "index", "kind", "mul", "headroom", "linear", "headroom"
+        )?;
+        for circuit in &self.circuits {
+            writeln!(
+                f,
+                "{:>5}  {:<13}  {:>8}  {:>8}  {:>8}  {:>8}  {}",
+                circuit.index,
+                circuit.kind,
+                circuit.multiplication_constraints,
+                circuit.multiplication_headroom,
+                circuit.linear_constraints,
+                circuit.linear_headroom,
+                circuit.name,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
+    /// Returns a [`ConstraintReport`] describing the constraint usage of every
+    /// circuit registered in this application.
+    pub fn constraint_report(&self) -> ConstraintReport {
+        let internal_steps = InternalStepIndex::ALL
+            .into_iter()
+            .map(|step| (step.name(), CircuitKind::InternalStep));
+        let internal_circuits = InternalCircuitIndex::ALL.into_iter().map(|circuit| {
+            let kind = if circuit.is_stage() {
+                CircuitKind::Stage
+            } else {
+                CircuitKind::Internal
+            };
+            (circuit.name(), kind)
+        });
+        let names = self
+            .step_names
+            .iter()
+            .map(|&name| (name, CircuitKind::Step))
+            .chain(internal_steps)
+            .chain(internal_circuits);
+
+        let circuits = self.native_registry.circuits();
+        debug_assert_eq!(
+            circuits.len(),
+            self.num_application_steps + NUM_INTERNAL_STEPS + InternalCircuitIndex::ALL.len()
+        );
+        let circuits = circuits
+            .iter()
+            .zip(names)
+            .enumerate()
+            .map(|(index, (circuit, (name, kind)))| {
+                let (multiplication_constraints, linear_constraints) = circuit.constraint_counts();
+                let (routines, namespaces) = circuit
+                    .metrics()
+                    .map(|metrics| (metrics.routines, metrics.namespaces))
+                    .unwrap_or_default();
+                CircuitReport {
+                    index,
+                    name,
+                    kind,
+                    multiplication_constraints,
+                    linear_constraints,
+                    multiplication_headroom: R::n() - multiplication_constraints,
+                    linear_headroom: R::num_coeffs() - linear_constraints,
+                    routines,
+                    namespaces,
+                }
+            })
+            .collect();
+
+        ConstraintReport {
+            circuits,
+            multiplication_bound: R::n(),
+            linear_bound: R::num_coeffs(),
+            log2_domain_size: total_circuit_counts(self.num_application_steps).1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ragu_circuits::polynomials::{R, Rank};
+    use ragu_core::Result;
+    use ragu_pasta::Pasta;
+
+    use super::CircuitKind;
+    use crate::ApplicationBuilder;
+
+    #[test]
+    fn test_constraint_report() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(2)?
+            .finalize(pasta)?;
+        let report = app.constraint_report();
+
+        assert_eq!(report.circuits().len(), 17);
+        assert_eq!(report.domain_size(), 32);
+        assert_eq!(report.multiplication_bound(), R::<13>::n());
+
+        let kinds = |kind| {
+            report
+                .circuits()
+                .iter()
+                .filter(|circuit| circuit.kind == kind)
+                .count()
+        };
+        assert_eq!(kinds(CircuitKind::Step), 2);
+        assert_eq!(kinds(CircuitKind::InternalStep), 2);
+        assert_eq!(kinds(CircuitKind::Stage), 8);
+        assert_eq!(kinds(CircuitKind::Internal), 5);
+
+        for (index, circuit) in report.circuits().iter().enumerate() {
+            assert_eq!(circuit.index, index);
+            assert_eq!(
+                circuit.multiplication_constraints + circuit.multiplication_headroom,
+                report.multiplication_bound()
+            );
+            assert_eq!(
+                circuit.linear_constraints + circuit.linear_headroom,
+                report.linear_bound()
+            );
+        }
+        assert_eq!(report.circuits()[2].name, "rerandomize");
+        assert_eq!(report.circuits()[12].name, "hashes_1");
+        assert!(report.to_string().contains("compute_v"));
+
+        // Hashing circuits spend their constraints within Poseidon routines.
+        let hashes = &report.circuits()[12];
+        assert!(!hashes.routines.is_empty());
+        assert!(
+            hashes
+                .routines
+                .iter()
+                .map(|routine| routine.num_multiplication_constraints)
+                .sum::<usize>()
+                <= hashes.multiplication_constraints
+        );
+        assert!(hashes.routines.iter().all(|routine| routine.calls > 0));
+
+        // ...and name the namespaces of each permutation round.
+        let round = hashes
+            .namespaces
+            .iter()
+            .find(|namespace| namespace.path == "hashes_1/sponge/permutation/round 3")
+            .expect("permutation rounds should be namespaced");
+        assert!(round.calls > 0);
+        assert!(round.num_multiplication_constraints > 0);
+        assert_eq!(hashes.namespaces[0].path, "hashes_1");
+        assert!(
+            hashes.namespaces[0].num_multiplication_constraints
+                >= round.num_multiplication_constraints
+        );
+
+        Ok(())
+    }
+}
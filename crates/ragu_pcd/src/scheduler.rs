@@ -0,0 +1,545 @@
+//! Scheduling the creation of a whole tree (or DAG) of proof-carrying data.
+//!
+//! A [`Scheduler`] is obtained from [`Application::scheduler`]. Each node of
+//! the computation is added with [`Scheduler::seed`], [`Scheduler::fuse`],
+//! [`Scheduler::extend`] or [`Scheduler::rerandomize`], which return a
+//! [`Node`] handle that can be used as the child of later nodes. Nothing is
+//! proven until [`Scheduler::execute`] is called, which runs every node that
+//! the requested root depends on once its children are available. With the
+//! `parallel` feature enabled, nodes that do not depend on one another are
+//! proven concurrently.
+//!
+//! The [`Step::Aux`] produced by each node is turned into the
+//! [`Header::Data`] of its output by a function supplied when the node is
+//! added, so that the resulting [`Pcd`] can be handed to parent nodes.
+
+use arithmetic::Cycle;
+use ragu_circuits::polynomials::Rank;
+use ragu_core::{Error, Result};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{
+    Application, Pcd,
+    header::Header,
+    parallel::Slot,
+    step::{Step, UnaryStep},
+};
+
+/// A handle to a node that has been added to a [`Scheduler`], which produces
+/// [`Pcd`] for the header `H`.
+pub struct Node<'source, C: Cycle, R: Rank, H: Header<C::CircuitField>> {
+    index: usize,
+    output: Arc<Slot<Pcd<'source, C, R, H>>>,
+}
+
+impl<C: Cycle, R: Rank, H: Header<C::CircuitField>> Clone for Node<'_, C, R, H> {
+    fn clone(&self) -> Self {
+        Node {
+            index: self.index,
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl<C: Cycle, R: Rank, H: Header<C::CircuitField>> Node<'_, C, R, H> {
+    /// Returns the index of this node, in the order that nodes were added to
+    /// the [`Scheduler`]. This is the index reported by [`NodeError`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// The error produced by a node of a [`Scheduler`] that failed.
+#[derive(Debug)]
+pub struct NodeError {
+    /// The [`index`](Node::index) of the node that failed.
+    pub node: usize,
+
+    /// The error returned while proving the node.
+    pub error: Error,
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} failed: {}", self.node, self.error)
+    }
+}
+
+impl core::error::Error for NodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A unit of work that proves a single node, reading the outputs of its
+/// children and storing its own output.
+trait Task<C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    /// Registers this task as a reader of the outputs of its children, which
+    /// must happen before any task is run.
+    fn read_children(&self);
+
+    fn run(
+        self: Box<Self>,
+        app: &Application<'_, C, R, HEADER_SIZE>,
+        rng: &mut StdRng,
+    ) -> Result<()>;
+}
+
+#[cfg(feature = "parallel")]
+type BoxedTask<'source, C, R, const HEADER_SIZE: usize> =
+    Box<dyn Task<C, R, HEADER_SIZE> + Send + 'source>;
+
+#[cfg(not(feature = "parallel"))]
+type BoxedTask<'source, C, R, const HEADER_SIZE: usize> =
+    Box<dyn Task<C, R, HEADER_SIZE> + 'source>;
+
+struct Entry<'source, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    /// The work needed to produce this node, or `None` if its output is
+    /// already available.
+    task: Option<BoxedTask<'source, C, R, HEADER_SIZE>>,
+    children: Vec<usize>,
+}
+
+/// Builds and executes a graph of [`Step`]s over an [`Application`].
+///
+/// See the [module documentation](self) for details.
+pub struct Scheduler<'app, 'params, 'source, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    app: &'app Application<'params, C, R, HEADER_SIZE>,
+    entries: Vec<Entry<'source, C, R, HEADER_SIZE>>,
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'params, C, R, HEADER_SIZE> {
+    /// Returns an empty [`Scheduler`] for building proof-carrying data with
+    /// this application.
+    pub fn scheduler<'source>(&self) -> Scheduler<'_, 'params, 'source, C, R, HEADER_SIZE> {
+        Scheduler {
+            app: self,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<'source, C: Cycle, R: Rank, const HEADER_SIZE: usize>
+    Scheduler<'_, '_, 'source, C, R, HEADER_SIZE>
+{
+    /// Adds existing proof-carrying data to the graph.
+    pub fn input<H: Header<C::CircuitField>>(
+        &mut self,
+        pcd: Pcd<'source, C, R, H>,
+    ) -> Node<'source, C, R, H> {
+        let output = Arc::new(Slot::new(Some(pcd)));
+        self.push(None, vec![], output)
+    }
+
+    /// Adds a leaf node that proves `step` with trivial inputs, as
+    /// [`Application::seed`] would.
+    ///
+    /// The [`Step::Aux`] of the step is converted into the data of its output
+    /// header with `data`.
+    pub fn seed<S, D>(
+        &mut self,
+        step: S,
+        witness: S::Witness<'source>,
+        data: D,
+    ) -> Node<'source, C, R, S::Output>
+    where
+        S: Step<C, Left = (), Right = ()> + 'source,
+        D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>
+            + Send
+            + 'source,
+    {
+        let output = Arc::new(Slot::new(None));
+        let task = Box::new(SeedTask {
+            step,
+            witness,
+            data,
+            output: output.clone(),
+        });
+        self.push(Some(task), vec![], output)
+    }
+
+    /// Adds a node that fuses the outputs of `left` and `right` using `step`,
+    /// as [`Application::fuse`] would.
+    ///
+    /// The [`Step::Aux`] of the step is converted into the data of its output
+    /// header with `data`.
+    pub fn fuse<S, D>(
+        &mut self,
+        step: S,
+        witness: S::Witness<'source>,
+        left: &Node<'source, C, R, S::Left>,
+        right: &Node<'source, C, R, S::Right>,
+        data: D,
+    ) -> Node<'source, C, R, S::Output>
+    where
+        S: Step<C> + 'source,
+        D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>
+            + Send
+            + 'source,
+    {
+        let output = Arc::new(Slot::new(None));
+        let task = Box::new(FuseTask {
+            step,
+            witness,
+            data,
+            left: left.output.clone(),
+            right: right.output.clone(),
+            output: output.clone(),
+        });
+        self.push(Some(task), vec![left.index, right.index], output)
+    }
+
+    /// Adds a node that extends the output of `input` using the
+    /// [`UnaryStep`] `step`, as [`Application::extend`] would.
+    ///
+    /// The [`UnaryStep::Aux`] of the step is converted into the data of its
+    /// output header with `data`.
+    pub fn extend<S, D>(
+        &mut self,
+        step: S,
+        witness: S::Witness<'source>,
+        input: &Node<'source, C, R, S::Input>,
+        data: D,
+    ) -> Node<'source, C, R, S::Output>
+    where
+        S: UnaryStep<C> + 'source,
+        D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>
+            + Send
+            + 'source,
+    {
+        let output = Arc::new(Slot::new(None));
+        let task = Box::new(ExtendTask {
+            step,
+            witness,
+            data,
+            input: input.output.clone(),
+            output: output.clone(),
+        });
+        self.push(Some(task), vec![input.index], output)
+    }
+
+    /// Adds a node that rerandomizes the output of `input`, as
+    /// [`Application::rerandomize`] would.
+    pub fn rerandomize<H: Header<C::CircuitField>>(
+        &mut self,
+        input: &Node<'source, C, R, H>,
+    ) -> Node<'source, C, R, H> {
+        let output = Arc::new(Slot::new(None));
+        let task = Box::new(RerandomizeTask {
+            input: input.output.clone(),
+            output: output.clone(),
+        });
+        self.push(Some(task), vec![input.index], output)
+    }
+
+    fn push<H: Header<C::CircuitField>>(
+        &mut self,
+        task: Option<BoxedTask<'source, C, R, HEADER_SIZE>>,
+        children: Vec<usize>,
+        output: Arc<Slot<Pcd<'source, C, R, H>>>,
+    ) -> Node<'source, C, R, H> {
+        let index = self.entries.len();
+        self.entries.push(Entry { task, children });
+
+        Node { index, output }
+    }
+
+    /// Proves every node that `root` depends on, and then `root` itself,
+    /// returning the proof-carrying data produced by `root`.
+    ///
+    /// Each node is proven as soon as all of its children have been, and
+    /// nodes that do not depend on one another may be proven concurrently.
+    /// The output of a child is moved into the last of its parents to be
+    /// proven, and only copied for the others. Each node samples its
+    /// randomness from a generator seeded by `rng`, in the order that nodes
+    /// were added, so the result does not depend on the order in which nodes
+    /// finish.
+    ///
+    /// If any node fails, the nodes that depend on it are not proven and an
+    /// error is returned for each failed node. Nodes that `root` does not
+    /// depend on are never proven.
+    pub fn execute<RNG: Rng, H: Header<C::CircuitField>>(
+        mut self,
+        rng: &mut RNG,
+        root: Node<'source, C, R, H>,
+    ) -> core::result::Result<Pcd<'source, C, R, H>, Vec<NodeError>> {
+        // Find the nodes that the root depends on.
+        let mut needed = vec![false; self.entries.len()];
+        let mut stack = vec![root.index];
+        while let Some(index) = stack.pop() {
+            if !needed[index] {
+                needed[index] = true;
+                stack.extend(self.entries[index].children.iter().copied());
+            }
+        }
+
+        // Children are always added before their parents, so the tasks of the
+        // children of a node have been scheduled by the time it is reached.
+        let mut tasks = Vec::with_capacity(self.entries.len());
+        let mut parents = vec![vec![]; self.entries.len()];
+        let mut remaining = Vec::with_capacity(self.entries.len());
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let task = entry.task.take().filter(|_| needed[index]).map(|task| {
+                task.read_children();
+                let mut seed = <StdRng as SeedableRng>::Seed::default();
+                rng.fill_bytes(&mut seed);
+                (task, StdRng::from_seed(seed))
+            });
+
+            let mut waiting = 0;
+            if task.is_some() {
+                for &child in &entry.children {
+                    if tasks.get(child).is_some_and(Option::is_some) {
+                        parents[child].push(index);
+                        waiting += 1;
+                    }
+                }
+            }
+            remaining.push(AtomicUsize::new(waiting));
+            tasks.push(task);
+        }
+
+        let ready = (0..tasks.len())
+            .filter(|&index| {
+                tasks[index].is_some() && remaining[index].load(Ordering::Relaxed) == 0
+            })
+            .collect();
+        let execution = Execution {
+            app: self.app,
+            failed: tasks.iter().map(|_| AtomicBool::new(false)).collect(),
+            errors: tasks.iter().map(|_| Slot::new(None)).collect(),
+            tasks: tasks.into_iter().map(Slot::new).collect(),
+            parents,
+            remaining,
+        };
+        execution.run(ready);
+
+        let errors: Vec<_> = execution
+            .errors
+            .iter()
+            .enumerate()
+            .filter_map(|(node, error)| {
+                Some(NodeError {
+                    node,
+                    error: error.take()?,
+                })
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(root
+            .output
+            .take()
+            .expect("root is proven when no node has failed"))
+    }
+}
+
+/// The state shared by the nodes of a [`Scheduler`] while it executes.
+struct Execution<'a, 'params, 'source, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    app: &'a Application<'params, C, R, HEADER_SIZE>,
+    /// The work needed to produce each node that is to be proven.
+    tasks: Vec<Slot<(BoxedTask<'source, C, R, HEADER_SIZE>, StdRng)>>,
+    /// The parents that are waiting for each node, once for each time that
+    /// they use it as a child.
+    parents: Vec<Vec<usize>>,
+    /// The number of children that each node is still waiting for.
+    remaining: Vec<AtomicUsize>,
+    /// Whether each node (or one of the nodes it depends on) has failed.
+    failed: Vec<AtomicBool>,
+    errors: Vec<Slot<Error>>,
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Execution<'_, '_, '_, C, R, HEADER_SIZE> {
+    /// Proves the node `index`, whose children have all finished, unless one
+    /// of them has failed. Returns the parents of the node that are no longer
+    /// waiting for any children.
+    fn complete(&self, index: usize) -> Vec<usize> {
+        let (task, mut rng) = self.tasks[index]
+            .take()
+            .expect("each node is proven at most once");
+
+        // Nodes whose children failed are skipped, and treated as failed so
+        // that their own parents are skipped as well.
+        let failed = self.failed[index].load(Ordering::Acquire)
+            || match task.run(self.app, &mut rng) {
+                Ok(()) => false,
+                Err(error) => {
+                    self.errors[index].set(error);
+                    true
+                }
+            };
+
+        self.parents[index]
+            .iter()
+            .copied()
+            .filter(|&parent| {
+                if failed {
+                    self.failed[parent].store(true, Ordering::Release);
+                }
+                self.remaining[parent].fetch_sub(1, Ordering::AcqRel) == 1
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn run(&self, ready: Vec<usize>) {
+        rayon::scope(|scope| {
+            for index in ready {
+                self.spawn(scope, index);
+            }
+        });
+    }
+
+    #[cfg(feature = "parallel")]
+    fn spawn<'scope>(&'scope self, scope: &rayon::Scope<'scope>, index: usize) {
+        scope.spawn(move |scope| {
+            for parent in self.complete(index) {
+                self.spawn(scope, parent);
+            }
+        });
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn run(&self, mut ready: Vec<usize>) {
+        while let Some(index) = ready.pop() {
+            ready.extend(self.complete(index));
+        }
+    }
+}
+
+fn child<T: Clone>(slot: &Slot<T>) -> T {
+    slot.read()
+        .expect("children are proven before their parents")
+}
+
+struct SeedTask<'source, C: Cycle, R: Rank, S: Step<C>, D> {
+    step: S,
+    witness: S::Witness<'source>,
+    data: D,
+    output: Arc<Slot<Pcd<'source, C, R, S::Output>>>,
+}
+
+impl<'source, C, R, S, D, const HEADER_SIZE: usize> Task<C, R, HEADER_SIZE>
+    for SeedTask<'source, C, R, S, D>
+where
+    C: Cycle,
+    R: Rank,
+    S: Step<C, Left = (), Right = ()>,
+    D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>,
+{
+    fn read_children(&self) {}
+
+    fn run(
+        self: Box<Self>,
+        app: &Application<'_, C, R, HEADER_SIZE>,
+        rng: &mut StdRng,
+    ) -> Result<()> {
+        let (proof, aux) = app.seed(rng, self.step, self.witness)?;
+        self.output.set(proof.carry((self.data)(aux)?));
+        Ok(())
+    }
+}
+
+struct FuseTask<'source, C: Cycle, R: Rank, S: Step<C>, D> {
+    step: S,
+    witness: S::Witness<'source>,
+    data: D,
+    left: Arc<Slot<Pcd<'source, C, R, S::Left>>>,
+    right: Arc<Slot<Pcd<'source, C, R, S::Right>>>,
+    output: Arc<Slot<Pcd<'source, C, R, S::Output>>>,
+}
+
+impl<'source, C, R, S, D, const HEADER_SIZE: usize> Task<C, R, HEADER_SIZE>
+    for FuseTask<'source, C, R, S, D>
+where
+    C: Cycle,
+    R: Rank,
+    S: Step<C>,
+    D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>,
+{
+    fn read_children(&self) {
+        self.left.add_reader();
+        self.right.add_reader();
+    }
+
+    fn run(
+        self: Box<Self>,
+        app: &Application<'_, C, R, HEADER_SIZE>,
+        rng: &mut StdRng,
+    ) -> Result<()> {
+        let (proof, aux) = app.fuse(
+            rng,
+            self.step,
+            self.witness,
+            child(&self.left),
+            child(&self.right),
+        )?;
+        self.output.set(proof.carry((self.data)(aux)?));
+        Ok(())
+    }
+}
+
+struct ExtendTask<'source, C: Cycle, R: Rank, S: UnaryStep<C>, D> {
+    step: S,
+    witness: S::Witness<'source>,
+    data: D,
+    input: Arc<Slot<Pcd<'source, C, R, S::Input>>>,
+    output: Arc<Slot<Pcd<'source, C, R, S::Output>>>,
+}
+
+impl<'source, C, R, S, D, const HEADER_SIZE: usize> Task<C, R, HEADER_SIZE>
+    for ExtendTask<'source, C, R, S, D>
+where
+    C: Cycle,
+    R: Rank,
+    S: UnaryStep<C>,
+    D: FnOnce(S::Aux<'source>) -> Result<<S::Output as Header<C::CircuitField>>::Data<'source>>,
+{
+    fn read_children(&self) {
+        self.input.add_reader();
+    }
+
+    fn run(
+        self: Box<Self>,
+        app: &Application<'_, C, R, HEADER_SIZE>,
+        rng: &mut StdRng,
+    ) -> Result<()> {
+        let (proof, aux) = app.extend(rng, self.step, self.witness, child(&self.input))?;
+        self.output.set(proof.carry((self.data)(aux)?));
+        Ok(())
+    }
+}
+
+struct RerandomizeTask<'source, C: Cycle, R: Rank, H: Header<C::CircuitField>> {
+    input: Arc<Slot<Pcd<'source, C, R, H>>>,
+    output: Arc<Slot<Pcd<'source, C, R, H>>>,
+}
+
+impl<C, R, H, const HEADER_SIZE: usize> Task<C, R, HEADER_SIZE> for RerandomizeTask<'_, C, R, H>
+where
+    C: Cycle,
+    R: Rank,
+    H: Header<C::CircuitField>,
+{
+    fn read_children(&self) {
+        self.input.add_reader();
+    }
+
+    fn run(
+        self: Box<Self>,
+        app: &Application<'_, C, R, HEADER_SIZE>,
+        rng: &mut StdRng,
+    ) -> Result<()> {
+        let pcd = app.rerandomize(child(&self.input), rng)?;
+        self.output.set(pcd);
+        Ok(())
+    }
+}
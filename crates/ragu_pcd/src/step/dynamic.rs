@@ -0,0 +1,418 @@
+//! Steps built at runtime from closures.
+
+use arithmetic::{Coeff, Cycle};
+use ff::Field;
+use ragu_circuits::polynomials::Rank;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue, LinearExpression},
+    maybe::Maybe,
+};
+use ragu_primitives::{Element, GadgetExt};
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::marker::PhantomData;
+
+use super::{Encoded, Index, Step};
+use crate::{ApplicationBuilder, Header};
+
+/// A wire of the circuit being synthesized through a [`DynamicDriver`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wire(usize);
+
+/// The [`Wire`]s of the elements that encode the headers of a [`Dynamic`]
+/// step, in the order they are written by each [`Header::Output`] gadget.
+#[derive(Clone, Debug)]
+pub struct HeaderWires {
+    /// The wires of the left header.
+    pub left: Vec<Wire>,
+    /// The wires of the right header.
+    pub right: Vec<Wire>,
+    /// The wires of the output header.
+    pub output: Vec<Wire>,
+}
+
+/// An object-safe interface to a [`Driver`], through which a [`Dynamic`]
+/// step synthesizes its constraints.
+///
+/// Closures passed to this driver to compute wire assignments are only called
+/// if the underlying driver has a witness.
+pub trait DynamicDriver<F: Field> {
+    /// Returns the wire that has the constant value one.
+    fn one(&self) -> Wire;
+
+    /// Returns the value assigned to a wire, if the driver has a witness.
+    fn value(&self, wire: Wire) -> Option<F>;
+
+    /// Allocates a wire with the provided assignment.
+    fn alloc(&mut self, value: &dyn Fn() -> Result<F>) -> Result<Wire>;
+
+    /// Allocates the wires $(A, B, C)$ with the constraint $A \cdot B = C$.
+    fn mul(&mut self, values: &dyn Fn() -> Result<(F, F, F)>) -> Result<(Wire, Wire, Wire)>;
+
+    /// Creates a virtual wire that is the linear combination of the provided
+    /// `(wire, coefficient)` terms.
+    fn add(&mut self, terms: &[(Wire, F)]) -> Wire;
+
+    /// Enforces that the linear combination of the provided
+    /// `(wire, coefficient)` terms is zero.
+    fn enforce_zero(&mut self, terms: &[(Wire, F)]) -> Result<()>;
+}
+
+/// The data of the left and right child headers of a [`Dynamic`] step.
+pub struct Children<'source, F: Field, L: Header<F>, R: Header<F>> {
+    /// The data of the left header.
+    pub left: L::Data<'source>,
+    /// The data of the right header.
+    pub right: R::Data<'source>,
+}
+
+/// The native function of a [`Dynamic`] step, which computes its output
+/// header data and auxiliary data.
+type Compute<F, W, A, L, R, O> = dyn for<'source> Fn(&W, Children<'source, F, L, R>) -> Result<Computed<'source, F, O, A>>
+    + Send
+    + Sync;
+
+/// The output header data and auxiliary data computed by a [`Dynamic`] step.
+type Computed<'source, F, O, A> = (<O as Header<F>>::Data<'source>, A);
+
+/// The synthesis function of a [`Dynamic`] step.
+type Synthesize<F, W> =
+    dyn Fn(&mut dyn DynamicDriver<F>, Option<&W>, &HeaderWires) -> Result<()> + Send + Sync;
+
+/// A [`Step`] whose index is assigned at runtime and whose logic is defined by
+/// closures.
+///
+/// Each [`Step`] is usually a distinct Rust type with a compile-time
+/// [`INDEX`](Step::INDEX), which is awkward for applications whose steps are
+/// loaded from configuration or otherwise only known at runtime. A [`Dynamic`]
+/// step is instead assigned its index when it is constructed, and is defined
+/// by two closures:
+///
+/// * a native function that computes the output header data and auxiliary
+///   data of the step from its witness and the data of its [`Children`], and
+/// * a synthesis function that enforces the step's constraints through a
+///   [`DynamicDriver`], an object-safe interface to the underlying [`Driver`].
+///
+/// The synthesis function is given the [`Wire`]s of the encoded left, right
+/// and output headers, and must constrain the output header wires; those are
+/// otherwise unconstrained. The header types of a [`Dynamic`] step are still
+/// type parameters, so the same [`Header`] checks apply to it as to any other
+/// [`Step`] when it is registered.
+///
+/// Cloning a [`Dynamic`] step is cheap, so that the same step can be
+/// registered in an application and later used to create proofs. Steps with
+/// different header types are different Rust types, but can be registered
+/// together from a runtime collection by first erasing them into
+/// [`BoxedStep`]s; creating proofs still requires the [`Dynamic`] step itself,
+/// as the types of its headers determine those of its inputs and output.
+pub struct Dynamic<
+    C: Cycle,
+    L: Header<C::CircuitField>,
+    R: Header<C::CircuitField>,
+    O: Header<C::CircuitField>,
+    W,
+    A,
+> {
+    index: usize,
+    compute: Arc<Compute<C::CircuitField, W, A, L, R, O>>,
+    synthesize: Arc<Synthesize<C::CircuitField, W>>,
+    _marker: PhantomData<fn() -> (C, L, R, O)>,
+}
+
+impl<
+    C: Cycle,
+    L: Header<C::CircuitField>,
+    R: Header<C::CircuitField>,
+    O: Header<C::CircuitField>,
+    W,
+    A,
+> Clone for Dynamic<C, L, R, O, W, A>
+{
+    fn clone(&self) -> Self {
+        Dynamic {
+            index: self.index,
+            compute: self.compute.clone(),
+            synthesize: self.synthesize.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<
+    C: Cycle,
+    L: Header<C::CircuitField>,
+    R: Header<C::CircuitField>,
+    O: Header<C::CircuitField>,
+    W: Send + Sync,
+    A: Send,
+> Dynamic<C, L, R, O, W, A>
+{
+    /// Creates a step with the application-defined index `index` from its
+    /// native function `compute` and its synthesis function `synthesize`.
+    ///
+    /// The witness is only passed to `synthesize` if the underlying driver
+    /// has a witness.
+    pub fn new(
+        index: usize,
+        compute: impl for<'source> Fn(
+            &W,
+            Children<'source, C::CircuitField, L, R>,
+        ) -> Result<(O::Data<'source>, A)>
+        + Send
+        + Sync
+        + 'static,
+        synthesize: impl Fn(
+            &mut dyn DynamicDriver<C::CircuitField>,
+            Option<&W>,
+            &HeaderWires,
+        ) -> Result<()>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        Dynamic {
+            index,
+            compute: Arc::new(compute),
+            synthesize: Arc::new(synthesize),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<
+    C: Cycle,
+    L: Header<C::CircuitField>,
+    R: Header<C::CircuitField>,
+    O: Header<C::CircuitField>,
+    W: Send + Sync,
+    A: Send,
+> Step<C> for Dynamic<C, L, R, O, W, A>
+{
+    const INDEX: Index = Index::runtime();
+
+    type Witness<'source> = W;
+    type Aux<'source> = A;
+    type Left = L;
+    type Right = R;
+    type Output = O;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, W>,
+        left: DriverValue<D, L::Data<'source>>,
+        right: DriverValue<D, R::Data<'source>>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, L, HEADER_SIZE>,
+            Encoded<'dr, D, R, HEADER_SIZE>,
+            Encoded<'dr, D, O, HEADER_SIZE>,
+        ),
+        DriverValue<D, A>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let (output, aux) = D::with(|| {
+            let children = Children {
+                left: left.clone().take(),
+                right: right.clone().take(),
+            };
+            (self.compute)(witness.snag(), children)
+        })?
+        .cast();
+
+        let left = Encoded::new(dr, left)?;
+        let right = Encoded::new(dr, right)?;
+        let output = Encoded::new(dr, output)?;
+
+        let mut erased = Erased::new(dr);
+        let headers = HeaderWires {
+            left: erased.header::<L>(left.as_gadget())?,
+            right: erased.header::<R>(right.as_gadget())?,
+            output: erased.header::<O>(output.as_gadget())?,
+        };
+        (self.synthesize)(&mut erased, into_option(witness.view()), &headers)?;
+
+        Ok(((left, right, output), aux))
+    }
+
+    fn index(&self) -> Index {
+        Index::new(self.index)
+    }
+}
+
+/// Converts a [`Maybe`] value into an [`Option`], which is `None` if the
+/// value does not exist.
+fn into_option<T: Send>(maybe: impl Maybe<T>) -> Option<T> {
+    let mut value = None;
+    maybe.map(|inner| value = Some(inner));
+    value
+}
+
+/// Implements [`DynamicDriver`] by recording the wires it creates on the
+/// underlying driver, along with their values.
+struct Erased<'a, 'dr, D: Driver<'dr>> {
+    dr: &'a mut D,
+    witnessed: bool,
+    wires: Vec<D::Wire>,
+    values: Vec<Option<D::F>>,
+}
+
+impl<'a, 'dr, D: Driver<'dr>> Erased<'a, 'dr, D> {
+    fn new(dr: &'a mut D) -> Self {
+        let witnessed = into_option(D::just(|| ())).is_some();
+        Erased {
+            dr,
+            witnessed,
+            wires: Vec::from([D::ONE]),
+            values: Vec::from([witnessed.then_some(D::F::ONE)]),
+        }
+    }
+
+    fn push(&mut self, wire: D::Wire, value: Option<D::F>) -> Wire {
+        self.wires.push(wire);
+        self.values.push(value);
+        Wire(self.wires.len() - 1)
+    }
+
+    /// Records the wires of the elements that encode a header gadget.
+    fn header<H: Header<D::F>>(
+        &mut self,
+        gadget: &<H::Output as ragu_core::gadgets::GadgetKind<D::F>>::Rebind<'dr, D>,
+    ) -> Result<Vec<Wire>> {
+        let mut elements: Vec<Element<'dr, D>> = Vec::new();
+        gadget.write(self.dr, &mut elements)?;
+
+        Ok(elements
+            .iter()
+            .map(|element| {
+                let value = into_option(element.value()).copied();
+                self.push(element.wire().clone(), value)
+            })
+            .collect())
+    }
+
+    /// Computes a value only if the underlying driver has a witness.
+    fn compute<T>(&self, value: impl FnOnce() -> Result<T>) -> Result<Option<T>> {
+        self.witnessed.then(value).transpose()
+    }
+
+    fn evaluate(&self, terms: &[(Wire, D::F)]) -> Option<D::F> {
+        terms.iter().try_fold(D::F::ZERO, |sum, &(wire, coeff)| {
+            Some(sum + self.values[wire.0]? * coeff)
+        })
+    }
+}
+
+/// Error for wire assignments that are requested from a [`DynamicDriver`]
+/// without a witness, which should never happen.
+fn missing_witness() -> Error {
+    Error::InvalidWitness("assignment requested from a driver without a witness".into())
+}
+
+impl<'dr, D: Driver<'dr>> DynamicDriver<D::F> for Erased<'_, 'dr, D> {
+    fn one(&self) -> Wire {
+        Wire(0)
+    }
+
+    fn value(&self, wire: Wire) -> Option<D::F> {
+        self.values[wire.0]
+    }
+
+    fn alloc(&mut self, value: &dyn Fn() -> Result<D::F>) -> Result<Wire> {
+        let value = self.compute(value)?;
+        let wire = self
+            .dr
+            .alloc(|| value.map(Coeff::Arbitrary).ok_or_else(missing_witness))?;
+
+        Ok(self.push(wire, value))
+    }
+
+    fn mul(
+        &mut self,
+        values: &dyn Fn() -> Result<(D::F, D::F, D::F)>,
+    ) -> Result<(Wire, Wire, Wire)> {
+        let values = self.compute(values)?;
+        let (a, b, c) = self.dr.mul(|| {
+            let (a, b, c) = values.ok_or_else(missing_witness)?;
+            Ok((
+                Coeff::Arbitrary(a),
+                Coeff::Arbitrary(b),
+                Coeff::Arbitrary(c),
+            ))
+        })?;
+
+        Ok((
+            self.push(a, values.map(|(a, _, _)| a)),
+            self.push(b, values.map(|(_, b, _)| b)),
+            self.push(c, values.map(|(_, _, c)| c)),
+        ))
+    }
+
+    fn add(&mut self, terms: &[(Wire, D::F)]) -> Wire {
+        let wires = &self.wires;
+        let wire = self.dr.add(|lc| {
+            terms.iter().fold(lc, |lc, &(wire, coeff)| {
+                lc.add_term(&wires[wire.0], Coeff::Arbitrary(coeff))
+            })
+        });
+        let value = self.evaluate(terms);
+
+        self.push(wire, value)
+    }
+
+    fn enforce_zero(&mut self, terms: &[(Wire, D::F)]) -> Result<()> {
+        let wires = &self.wires;
+        self.dr.enforce_zero(|lc| {
+            terms.iter().fold(lc, |lc, &(wire, coeff)| {
+                lc.add_term(&wires[wire.0], Coeff::Arbitrary(coeff))
+            })
+        })
+    }
+}
+
+/// A [`Step`] whose type has been erased, so that steps with different header
+/// types (such as [`Dynamic`] steps loaded at runtime) can be collected
+/// together and registered with
+/// [`ApplicationBuilder::register_boxed`].
+pub struct BoxedStep<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    step: Box<dyn Register<'params, C, R, HEADER_SIZE> + 'params>,
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> BoxedStep<'params, C, R, HEADER_SIZE> {
+    /// Erases the type of `step`.
+    pub fn new<S: Step<C> + 'params>(step: S) -> Self {
+        BoxedStep {
+            step: Box::new(step),
+        }
+    }
+
+    pub(crate) fn register(
+        self,
+        builder: ApplicationBuilder<'params, C, R, HEADER_SIZE>,
+    ) -> Result<ApplicationBuilder<'params, C, R, HEADER_SIZE>> {
+        self.step.register(builder)
+    }
+}
+
+/// The operations of a [`Step`] that remain available once it has been erased
+/// into a [`BoxedStep`].
+trait Register<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    fn register(
+        self: Box<Self>,
+        builder: ApplicationBuilder<'params, C, R, HEADER_SIZE>,
+    ) -> Result<ApplicationBuilder<'params, C, R, HEADER_SIZE>>;
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize, S: Step<C> + 'params>
+    Register<'params, C, R, HEADER_SIZE> for S
+{
+    fn register(
+        self: Box<Self>,
+        builder: ApplicationBuilder<'params, C, R, HEADER_SIZE>,
+    ) -> Result<ApplicationBuilder<'params, C, R, HEADER_SIZE>> {
+        builder.register(*self)
+    }
+}
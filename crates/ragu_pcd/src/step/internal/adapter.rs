@@ -58,6 +58,7 @@ impl<C: Cycle, S: Step<C>, R: Rank, const HEADER_SIZE: usize> Circuit<C::Circuit
         (
             FixedVec<C::CircuitField, ConstLen<HEADER_SIZE>>,
             FixedVec<C::CircuitField, ConstLen<HEADER_SIZE>>,
+            FixedVec<C::CircuitField, ConstLen<HEADER_SIZE>>,
         ),
         S::Aux<'source>,
     );
@@ -103,7 +104,12 @@ impl<C: Cycle, S: Step<C>, R: Rank, const HEADER_SIZE: usize> Circuit<C::Circuit
                 .map(|e| *e.value().take())
                 .collect_fixed()?;
 
-            Ok(((left_header, right_header), aux.take()))
+            let output_header = elements[HEADER_SIZE * 2..]
+                .iter()
+                .map(|e| *e.value().take())
+                .collect_fixed()?;
+
+            Ok(((left_header, right_header, output_header), aux.take()))
         })?;
 
         Ok((FixedVec::try_from(elements)?, aux))
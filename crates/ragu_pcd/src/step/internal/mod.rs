@@ -2,3 +2,4 @@ pub(crate) mod adapter;
 pub(crate) mod padded;
 pub(crate) mod rerandomize;
 pub(crate) mod trivial;
+pub(crate) mod unary;
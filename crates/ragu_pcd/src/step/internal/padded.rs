@@ -1,14 +1,17 @@
 use ff::{Field, PrimeField};
 use ragu_core::{
-    Result,
-    drivers::Driver,
+    Error, Result,
+    drivers::{Driver, emulator::Emulator},
     gadgets::{Gadget, GadgetKind, Kind},
+    maybe::Maybe,
 };
 use ragu_primitives::{
     Element, GadgetExt,
     io::{Buffer, Write},
+    vec::{CollectFixed, ConstLen, FixedVec},
 };
 
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use crate::Header;
@@ -49,6 +52,57 @@ pub(crate) fn for_header<
     })
 }
 
+/// Computes the padded encoding of some [`Header`] data, including the header
+/// suffix.
+pub(crate) fn encode<H: Header<F>, const HEADER_SIZE: usize, F: PrimeField>(
+    data: H::Data<'_>,
+) -> Result<FixedVec<F, ConstLen<HEADER_SIZE>>> {
+    Emulator::emulate_wireless(data, |dr, data| {
+        let output = H::encode(dr, data)?;
+        let output = for_header::<H, HEADER_SIZE, _>(dr, output)?;
+
+        let mut elements = Vec::with_capacity(HEADER_SIZE);
+        output.write(dr, &mut elements)?;
+
+        elements
+            .into_iter()
+            .map(|e| *e.value().take())
+            .collect_fixed()
+    })
+}
+
+/// Recovers [`Header`] data from its padded encoding.
+///
+/// Fails unless the final element is the suffix of `H` and the remaining
+/// elements are exactly the encoding of the decoded data followed by zero
+/// padding.
+pub(crate) fn decode<'source, H: Header<F>, const HEADER_SIZE: usize, F: PrimeField>(
+    elements: &[F],
+) -> Result<H::Data<'source>> {
+    let Some((suffix, content)) = elements.split_last() else {
+        return Err(Error::MalformedEncoding("header is empty".into()));
+    };
+    if elements.len() != HEADER_SIZE {
+        return Err(Error::MalformedEncoding(
+            "header length does not match HEADER_SIZE".into(),
+        ));
+    }
+    if *suffix != F::from(H::SUFFIX.get()) {
+        return Err(Error::MalformedEncoding(
+            "header suffix does not match the requested header".into(),
+        ));
+    }
+
+    let data = H::decode(content)?;
+    if encode::<H, HEADER_SIZE, F>(data.clone())?[..] != *elements {
+        return Err(Error::MalformedEncoding(
+            "header is not the padded encoding of its decoded data".into(),
+        ));
+    }
+
+    Ok(data)
+}
+
 /// Inner gadget that writes the header gadget followed by zero padding up to
 /// `HEADER_SIZE - 1` elements (reserving space for the suffix).
 #[derive(Gadget)]
@@ -119,6 +173,7 @@ where
 
 #[cfg(test)]
 mod tests {
+    use ff::Field;
     use ragu_core::{
         Result,
         drivers::{Driver, emulator::Emulator},
@@ -198,6 +253,22 @@ mod tests {
             assert!(padded_gadget.write(dr, &mut buffer).is_err());
         }
 
+        Ok(())
+    }
+    #[test]
+    fn test_decode() -> Result<()> {
+        let encoded = super::encode::<(), 4, F>(())?;
+        super::decode::<(), 4, F>(&encoded)?;
+
+        // Nonzero padding, a foreign suffix and a short header are rejected.
+        let mut tampered = encoded.clone();
+        tampered[0] = F::ONE;
+        assert!(super::decode::<(), 4, F>(&tampered).is_err());
+        let mut tampered = encoded.clone();
+        tampered[3] += F::ONE;
+        assert!(super::decode::<(), 4, F>(&tampered).is_err());
+        assert!(super::decode::<(), 4, F>(&encoded[1..]).is_err());
+
         Ok(())
     }
 }
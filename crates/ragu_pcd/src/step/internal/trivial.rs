@@ -1,7 +1,4 @@
 //! Internal step that produces a valid proof with trivial header.
-//!
-//! Used in rerandomization to create a properly-structured trivial proof that
-//! can be folded with a valid proof without causing C value mismatches.
 
 use arithmetic::Cycle;
 use ragu_core::{
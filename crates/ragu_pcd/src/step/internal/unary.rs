@@ -0,0 +1,59 @@
+//! Adapts a [`UnaryStep`] into a [`Step`].
+//!
+//! The recursion circuits always fold two child proofs, so a unary step is
+//! treated as a step whose right header is the trivial header `()`. The
+//! recursion circuits make no claims about such a child, so it is filled in
+//! with the empty trivial proof rather than a proof that has to be created.
+
+use arithmetic::Cycle;
+use ragu_core::{
+    Result,
+    drivers::{Driver, DriverValue},
+};
+
+use super::super::{Encoded, Index, Step, UnaryStep};
+use crate::Header;
+
+pub(crate) struct Unary<S> {
+    step: S,
+}
+
+impl<S> Unary<S> {
+    pub fn new(step: S) -> Self {
+        Unary { step }
+    }
+}
+
+impl<C: Cycle, S: UnaryStep<C>> Step<C> for Unary<S> {
+    const INDEX: Index = S::INDEX;
+
+    type Witness<'source> = S::Witness<'source>;
+    type Aux<'source> = S::Aux<'source>;
+
+    type Left = S::Input;
+    type Right = ();
+    type Output = S::Output;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        left: DriverValue<D, <S::Input as Header<C::CircuitField>>::Data<'source>>,
+        right: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let ((input, output), aux) = self.step.witness::<_, HEADER_SIZE>(dr, witness, left)?;
+        let right = Encoded::new(dr, right)?;
+
+        Ok(((input, right, output), aux))
+    }
+}
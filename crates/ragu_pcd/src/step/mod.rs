@@ -1,5 +1,6 @@
 //! Merging operations defined for the proof-carrying data computational graph.
 
+mod dynamic;
 mod encoder;
 pub(crate) mod internal;
 
@@ -12,6 +13,7 @@ use ragu_core::{
 
 use super::header::Header;
 
+pub use dynamic::{BoxedStep, Children, Dynamic, DynamicDriver, HeaderWires, Wire};
 pub use encoder::Encoded;
 
 #[derive(Copy, Clone)]
@@ -23,11 +25,26 @@ pub(crate) enum InternalStepIndex {
     Trivial = 1,
 }
 
+impl InternalStepIndex {
+    /// Every internal step, in the order in which they are registered.
+    pub(crate) const ALL: [Self; NUM_INTERNAL_STEPS] = [Self::Rerandomize, Self::Trivial];
+
+    /// Returns a human-readable name for this step.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Rerandomize => "rerandomize",
+            Self::Trivial => "trivial",
+        }
+    }
+}
+
 /// Internal representation of a [`Step`] index distinguishing internal vs.
 /// application steps.
 enum StepIndex {
     Internal(InternalStepIndex),
     Application(usize),
+    /// Placeholder for steps whose index is only known at runtime.
+    Runtime,
 }
 
 /// The number of internal steps used by Ragu for things like rerandomization or
@@ -68,6 +85,16 @@ impl Index {
 
                 Ok(CircuitIndex::new(i))
             }
+            StepIndex::Runtime => Err(runtime_index()),
+        }
+    }
+
+    /// Creates the placeholder [`Step::INDEX`] of a [`Step`] whose index is
+    /// only known at runtime, such as a [`Dynamic`] step. Such steps override
+    /// [`Step::index`] to return their actual index.
+    pub const fn runtime() -> Self {
+        Index {
+            index: StepIndex::Runtime,
         }
     }
 
@@ -97,10 +124,19 @@ impl Index {
                 Ok(())
             }
             StepIndex::Internal(_) => panic!("step should be application-defined"),
+            StepIndex::Runtime => Err(runtime_index()),
         }
     }
 }
 
+/// Error for uses of the placeholder [`Index::runtime`] in place of the index
+/// returned by [`Step::index`].
+fn runtime_index() -> ragu_core::Error {
+    ragu_core::Error::Initialization(
+        "the index of this step is only known at runtime; use Step::index".into(),
+    )
+}
+
 #[test]
 fn test_index_map() -> Result<()> {
     let num_application_steps = 10;
@@ -125,6 +161,12 @@ fn test_index_map() -> Result<()> {
 
 /// Represents a node in the computational graph (or the proof-carrying data
 /// tree) that represents the merging of two pieces of proof-carrying data.
+///
+/// Every step folds exactly two child proofs, its [`Left`](Step::Left) and
+/// [`Right`](Step::Right) inputs. Merging more children in one step is not
+/// supported, because the recursion circuits and the proof format are laid
+/// out for a pair of children; wider merges are expressed as a tree of
+/// steps. Steps with a single predecessor use [`UnaryStep`] instead.
 pub trait Step<C: Cycle>: Sized + Send + Sync {
     /// Each unique [`Step`] implementation within a provided context must have
     /// a unique index.
@@ -163,4 +205,52 @@ pub trait Step<C: Cycle>: Sized + Send + Sync {
     )>
     where
         Self: 'dr;
+
+    /// Returns the index of this step, which is [`Step::INDEX`] unless the
+    /// index is only known at runtime.
+    fn index(&self) -> Index {
+        Self::INDEX
+    }
+}
+
+/// Represents a node in the computational graph (or the proof-carrying data
+/// tree) that extends a single piece of proof-carrying data, such as a step of
+/// an incremental computation.
+///
+/// Unary steps are registered with
+/// [`ApplicationBuilder::register_unary`](crate::ApplicationBuilder::register_unary)
+/// and share the same index space as [`Step`]s.
+pub trait UnaryStep<C: Cycle>: Sized + Send + Sync {
+    /// Each unique [`Step`] or [`UnaryStep`] implementation within a provided
+    /// context must have a unique index.
+    const INDEX: Index;
+
+    /// The witness data needed to construct a proof for this step.
+    type Witness<'source>: Send;
+
+    /// Auxiliary information produced during circuit synthesis. This may be
+    /// necessary to construct the [`Header::Data`] for the resulting proof.
+    type Aux<'source>: Send;
+
+    /// The header expected of the predecessor during this step.
+    type Input: Header<C::CircuitField>;
+
+    /// The header produced during this step.
+    type Output: Header<C::CircuitField>;
+
+    /// The main synthesis method that checks the validity of this step.
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        input: DriverValue<D, <Self::Input as Header<C::CircuitField>>::Data<'source>>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr;
 }
@@ -1,137 +1,581 @@
-//! This module provides the [`Application::verify`] method implementation.
+//! This module provides the [`Application::verify`] and
+//! [`Application::verify_batch`] method implementations, along with their
+//! deterministic counterparts.
 
-use arithmetic::Cycle;
-use ff::Field;
+use arithmetic::{Cycle, FixedGenerators};
+use ff::{Field, PrimeField};
+use pasta_curves::group::prime::PrimeCurveAffine;
 use ragu_circuits::{
-    polynomials::{Rank, structured},
-    registry::CircuitIndex,
+    polynomials::{Rank, structured, unstructured},
+    registry::{CircuitIndex, Registry},
+};
+use ragu_core::{Error, Result, drivers::emulator::Emulator, maybe::Maybe};
+use ragu_primitives::{
+    Element,
+    vec::{ConstLen, FixedVec},
 };
-use ragu_core::{Result, drivers::emulator::Emulator, maybe::Maybe};
-use ragu_primitives::Element;
 use rand::Rng;
 
+use alloc::{borrow::Cow, vec::Vec};
 use core::iter::once;
 
 use crate::{
-    Application, Pcd, Proof, circuits::native::stages::preamble::ProofInputs, components::claims,
+    Application, ErasedPcd, Pcd, Proof,
+    circuits::native::{
+        stages::preamble::{self, ProofInputs},
+        unified,
+    },
+    components::claims,
+    hash,
     header::Header,
+    limbs,
+    step::internal::padded,
 };
 
+/// Challenges sampled by the verifier, which are shared by every proof that
+/// is verified together.
+pub(crate) struct VerifierChallenges<C: Cycle> {
+    w: C::CircuitField,
+    x: C::CircuitField,
+    y: C::CircuitField,
+    z: C::CircuitField,
+    /// Used to fold the native revdot claims of a proof together.
+    fold: C::CircuitField,
+    y_nested: C::ScalarField,
+    z_nested: C::ScalarField,
+    /// Used to fold the nested revdot claims of a proof together.
+    fold_nested: C::ScalarField,
+    /// Used to combine the checks of the $p(X)$ commitments of every proof.
+    beta: C::CircuitField,
+}
+
+impl<C: Cycle> VerifierChallenges<C> {
+    pub(crate) fn sample<RNG: Rng>(mut rng: RNG) -> Self {
+        VerifierChallenges {
+            w: C::CircuitField::random(&mut rng),
+            x: C::CircuitField::random(&mut rng),
+            y: C::CircuitField::random(&mut rng),
+            z: C::CircuitField::random(&mut rng),
+            fold: C::CircuitField::random(&mut rng),
+            y_nested: C::ScalarField::random(&mut rng),
+            z_nested: C::ScalarField::random(&mut rng),
+            fold_nested: C::ScalarField::random(&mut rng),
+            beta: C::CircuitField::random(&mut rng),
+        }
+    }
+
+    /// Derives the challenges from the application digest and the proofs
+    /// being verified (along with their output headers).
+    ///
+    /// Proofs are hashed by their full [encoding](Proof::to_bytes), because the
+    /// verifier evaluates polynomials of the proof (such as the registry
+    /// polynomials and the rx polynomials of the nested curve) that are not
+    /// bound by any commitment it checks.
+    ///
+    /// These are hashed with the Poseidon sponge over the circuit field, from
+    /// which the challenges in the circuit field are squeezed. The challenges
+    /// in the scalar field are squeezed from the Poseidon sponge over the
+    /// scalar field after absorbing one further output of the first sponge.
+    pub(crate) fn derive<R: Rank, const HEADER_SIZE: usize>(
+        params: &C::Params,
+        digest: C::CircuitField,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+    ) -> Self {
+        let mut bytes = Vec::new();
+        for pcd in pcds {
+            bytes.extend(pcd.proof.to_bytes());
+            for element in pcd.output_header.iter() {
+                bytes.extend_from_slice(element.to_repr().as_ref());
+            }
+        }
+
+        let inputs = [digest, C::CircuitField::from(bytes.len() as u64)]
+            .into_iter()
+            .chain(limbs(&bytes));
+        let [w, x, y, z, fold, beta, nested] = hash(C::circuit_poseidon(params), inputs);
+        let [y_nested, z_nested, fold_nested] =
+            hash(C::scalar_poseidon(params), limbs(nested.to_repr().as_ref()));
+
+        VerifierChallenges {
+            w,
+            x,
+            y,
+            z,
+            fold,
+            y_nested,
+            z_nested,
+            fold_nested,
+            beta,
+        }
+    }
+}
+
+/// Folds revdot claims $\langle \mathbf{a}_i, \mathbf{b}_i \rangle = k_i$
+/// into a single claim using powers of a random challenge, returning the
+/// folded residual $\sum_i c^{n - 1 - i} (\langle \mathbf{a}_i, \mathbf{b}_i
+/// \rangle - k_i)$, which is zero if the claims hold.
+fn fold_revdot_claims<F: Field, R: Rank>(
+    ky: impl Iterator<Item = F>,
+    a: &[Cow<'_, structured::Polynomial<F, R>>],
+    b: &[Cow<'_, structured::Polynomial<F, R>>],
+    challenge: F,
+) -> F {
+    ky.zip(a.iter().zip(b.iter()))
+        .fold(F::ZERO, |acc, (ky, (a, b))| {
+            acc * challenge + (a.revdot(b) - ky)
+        })
+}
+
+/// The native and nested revdot claims of a proof, each folded into a single
+/// residual by [`fold_revdot_claims`].
+struct FoldedClaims<C: Cycle> {
+    native: C::CircuitField,
+    num_native: usize,
+    nested: C::ScalarField,
+    num_nested: usize,
+}
+
+impl<C: Cycle> FoldedClaims<C> {
+    /// Checks the revdot claims of many proofs at once.
+    ///
+    /// The residuals of each proof are folded together by continuing to fold
+    /// with the same challenges, so that this checks a single random linear
+    /// combination of the revdot claims of every proof.
+    fn hold<'a>(claims: impl Iterator<Item = &'a Self>, challenges: &VerifierChallenges<C>) -> bool
+    where
+        C: 'a,
+    {
+        let (native, nested) = claims.fold(
+            (C::CircuitField::ZERO, C::ScalarField::ZERO),
+            |(native, nested), claims| {
+                (
+                    native * challenges.fold.pow_vartime([claims.num_native as u64])
+                        + claims.native,
+                    nested
+                        * challenges
+                            .fold_nested
+                            .pow_vartime([claims.num_nested as u64])
+                        + claims.nested,
+                )
+            },
+        );
+
+        native.is_zero_vartime() && nested.is_zero_vartime()
+    }
+}
+
+/// Appends to `failed` the items of `batch` that do not pass `check`, which
+/// is applied to the whole batch at once and only bisected upon failure.
+fn bisect(batch: &[usize], check: &impl Fn(&[usize]) -> bool, failed: &mut Vec<usize>) {
+    if batch.is_empty() || check(batch) {
+        return;
+    }
+
+    if let [index] = batch {
+        failed.push(*index);
+    } else {
+        let (left, right) = batch.split_at(batch.len() / 2);
+        bisect(left, check, failed);
+        bisect(right, check, failed);
+    }
+}
+
 impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'_, C, R, HEADER_SIZE> {
     /// Verifies some [`Pcd`] for the provided [`Header`].
+    ///
+    /// Returns [`Error::ApplicationMismatch`] if the proof was created by a
+    /// different [`Application`].
     pub fn verify<RNG: Rng, H: Header<C::CircuitField>>(
         &self,
         pcd: &Pcd<'_, C, R, H>,
-        mut rng: RNG,
+        rng: RNG,
+    ) -> Result<bool> {
+        self.verifier().verify(pcd, rng)
+    }
+
+    /// Verifies many [`Pcd`], possibly for different [`Header`] types, at
+    /// once.
+    ///
+    /// All proofs are checked against the same verifier challenges, the
+    /// revdot claims of every proof are folded into a single claim, and the
+    /// commitments to the final accumulated polynomials $p(X)$ of every proof
+    /// are checked with a single multiscalar multiplication. This is
+    /// substantially faster than calling [`Application::verify`] for each
+    /// proof. Only if one of these batched checks fails is the batch bisected
+    /// in order to identify the invalid proofs.
+    ///
+    /// Returns the (ascending) indices of the proofs in `pcds` that are not
+    /// valid, so the batch is accepted if and only if the result is empty.
+    /// Proofs created by a different [`Application`] are never valid, and so
+    /// are reported among these indices rather than failing the whole batch.
+    pub fn verify_batch<RNG: Rng>(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+        rng: RNG,
+    ) -> Result<Vec<usize>> {
+        self.verifier().verify_batch(pcds, rng)
+    }
+
+    /// Verifies a bare [`Proof`] against the output header recorded in it and,
+    /// if the proof is valid, returns the [`Header::Data`] for `H` that it
+    /// attests to.
+    ///
+    /// Returns `Ok(None)` if the proof is not valid, and
+    /// [`Error::ApplicationMismatch`] if it was created by a different
+    /// [`Application`]. Returns [`Error::MalformedEncoding`] if the proof is
+    /// valid but its output
+    /// header is not an encoding of `H`, including if its suffix or padding
+    /// do not match, and [`Error::Unsupported`] if `H` does not implement
+    /// [`Header::decode`].
+    pub fn verified_header<'source, RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        proof: &Proof<C, R>,
+        rng: RNG,
+    ) -> Result<Option<H::Data<'source>>> {
+        self.verifier().verified_header::<_, H>(proof, rng)
+    }
+
+    /// Verifies some [`Pcd`] for the provided [`Header`], as
+    /// [`Application::verify`] would, without a random number generator.
+    ///
+    /// The verifier's challenges are instead derived by hashing the proof,
+    /// its output header and the application digest, so that the result is a
+    /// deterministic function of its inputs.
+    pub fn verify_deterministic<H: Header<C::CircuitField>>(
+        &self,
+        pcd: &Pcd<'_, C, R, H>,
     ) -> Result<bool> {
-        // Sample verification challenges w, y, and z.
-        let w = C::CircuitField::random(&mut rng);
-        let y = C::CircuitField::random(&mut rng);
-        let z = C::CircuitField::random(&mut rng);
+        self.verifier().verify_deterministic(pcd)
+    }
+
+    /// Verifies many [`Pcd`] at once, as [`Application::verify_batch`] would,
+    /// without a random number generator.
+    ///
+    /// The verifier's challenges are derived by hashing every proof in the
+    /// batch, as in [`Application::verify_deterministic`].
+    pub fn verify_batch_deterministic(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+    ) -> Result<Vec<usize>> {
+        self.verifier().verify_batch_deterministic(pcds)
+    }
+
+    pub(crate) fn verifier(&self) -> Verifier<'_, C, R, HEADER_SIZE> {
+        Verifier {
+            native_registry: &self.native_registry,
+            nested_registry: &self.nested_registry,
+            params: self.params,
+            num_application_steps: self.num_application_steps,
+            digest: self.digest,
+        }
+    }
+}
+
+/// Everything needed to verify proofs, borrowed from an [`Application`] or a
+/// [`VerifyingKey`](crate::VerifyingKey).
+pub(crate) struct Verifier<'a, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    pub(crate) native_registry: &'a Registry<'a, C::CircuitField, R>,
+    pub(crate) nested_registry: &'a Registry<'a, C::ScalarField, R>,
+    pub(crate) params: &'a C::Params,
+    pub(crate) num_application_steps: usize,
+    pub(crate) digest: C::CircuitField,
+}
+
+impl<C: Cycle, R: Rank, const HEADER_SIZE: usize> Verifier<'_, C, R, HEADER_SIZE> {
+    pub(crate) fn verify<RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        pcd: &Pcd<'_, C, R, H>,
+        rng: RNG,
+    ) -> Result<bool> {
+        self.check_digest(&pcd.proof)?;
+
+        Ok(self.verify_batch(&[ErasedPcd::new(pcd)?], rng)?.is_empty())
+    }
+
+    /// Proofs created by another application can never be valid here, and
+    /// are reported distinctly from proofs that are simply invalid when they
+    /// are verified alone.
+    fn check_digest(&self, proof: &Proof<C, R>) -> Result<()> {
+        if proof.digest != self.digest {
+            return Err(Error::ApplicationMismatch);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn verified_header<'source, RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        proof: &Proof<C, R>,
+        rng: RNG,
+    ) -> Result<Option<H::Data<'source>>> {
+        self.check_digest(proof)?;
+
+        let Ok(output_header) = FixedVec::try_from(proof.application.output_header.clone()) else {
+            return Ok(None);
+        };
+        let pcd = ErasedPcd {
+            proof,
+            output_header,
+        };
+        if !self.verify_batch(&[pcd], rng)?.is_empty() {
+            return Ok(None);
+        }
+
+        padded::decode::<H, HEADER_SIZE, _>(&proof.application.output_header).map(Some)
+    }
+
+    pub(crate) fn verify_deterministic<H: Header<C::CircuitField>>(
+        &self,
+        pcd: &Pcd<'_, C, R, H>,
+    ) -> Result<bool> {
+        self.check_digest(&pcd.proof)?;
+
+        Ok(self
+            .verify_batch_deterministic(&[ErasedPcd::new(pcd)?])?
+            .is_empty())
+    }
+
+    pub(crate) fn verify_batch<RNG: Rng>(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+        rng: RNG,
+    ) -> Result<Vec<usize>> {
+        self.verify_batch_with(pcds, &VerifierChallenges::sample(rng))
+    }
+
+    pub(crate) fn verify_batch_deterministic(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+    ) -> Result<Vec<usize>> {
+        let challenges = VerifierChallenges::derive(self.params, self.digest, pcds);
+        self.verify_batch_with(pcds, &challenges)
+    }
+
+    fn verify_batch_with(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+        challenges: &VerifierChallenges<C>,
+    ) -> Result<Vec<usize>> {
+        let mut failed = Vec::new();
+        let mut pending = Vec::with_capacity(pcds.len());
+        let mut folded = Vec::with_capacity(pcds.len());
+        for (i, pcd) in pcds.iter().enumerate() {
+            // Check polynomial evaluation claim alongside the other claims;
+            // only the revdot claims and the commitment check are batched.
+            let claims = if pcd.proof.digest == self.digest
+                && pcd.proof.p.poly.eval(pcd.proof.challenges.u) == pcd.proof.p.v
+            {
+                self.fold_claims(pcd.proof, &pcd.output_header, challenges)?
+            } else {
+                None
+            };
+            if claims.is_some() {
+                pending.push(i);
+            } else {
+                failed.push(i);
+            }
+            folded.push(claims);
+        }
+
+        // Check the revdot claims of every proof at once, and then check that
+        // the P commitments correspond to polynomials and blinds. If either
+        // check rejects the batch, it is bisected in order to identify the
+        // invalid proofs.
+        let mut rejected = Vec::new();
+        bisect(
+            &pending,
+            &|batch| {
+                FoldedClaims::hold(batch.iter().filter_map(|&i| folded[i].as_ref()), challenges)
+            },
+            &mut rejected,
+        );
+        pending.retain(|i| !rejected.contains(i));
+        bisect(
+            &pending,
+            &|batch| {
+                self.check_p_commitments(batch.iter().map(|&i| pcds[i].proof), challenges.beta)
+            },
+            &mut rejected,
+        );
+
+        failed.extend(rejected);
+        failed.sort_unstable();
+
+        Ok(failed)
+    }
+
+    /// Checks that the commitment to $p(X)$ of every proof corresponds to its
+    /// polynomial and blinding factor, using a random linear combination
+    /// $\sum_i \beta^i (\mathbf{p}_i, r_i)$ so that only a single multiscalar
+    /// multiplication is needed.
+    fn check_p_commitments<'a>(
+        &self,
+        proofs: impl Iterator<Item = &'a Proof<C, R>>,
+        beta: C::CircuitField,
+    ) -> bool
+    where
+        C: 'a,
+        R: 'a,
+    {
+        let mut poly = unstructured::Polynomial::<C::CircuitField, R>::new();
+        let mut blind = C::CircuitField::ZERO;
+        let mut commitments = Vec::new();
+        for proof in proofs {
+            poly.scale(beta);
+            poly += &proof.p.poly;
+            blind = beta * blind + proof.p.blind;
+            commitments.push(proof.p.commitment);
+        }
+
+        // The most recent commitment has the lowest power of beta.
+        commitments.reverse();
+        let powers: Vec<_> =
+            core::iter::successors(Some(-C::CircuitField::ONE), |power| Some(*power * beta))
+                .take(commitments.len())
+                .collect();
+
+        let generators = C::host_generators(self.params);
+        let result = arithmetic::mul(
+            poly.iter().chain(once(&blind)).chain(powers.iter()),
+            generators
+                .g()
+                .iter()
+                .take(poly.len())
+                .chain(once(generators.h()))
+                .chain(commitments.iter()),
+        );
+
+        bool::from(C::HostCurve::from(result).is_identity())
+    }
+
+    /// Checks every claim of a proof except for those about the final
+    /// accumulated polynomial $p(X)$. Its revdot claims are only folded, so
+    /// that they can be checked together with those of other proofs. Returns
+    /// `None` if any of the other claims do not hold.
+    fn fold_claims(
+        &self,
+        proof: &Proof<C, R>,
+        output_header: &FixedVec<C::CircuitField, ConstLen<HEADER_SIZE>>,
+        challenges: &VerifierChallenges<C>,
+    ) -> Result<Option<FoldedClaims<C>>> {
+        let VerifierChallenges { w, x, y, z, .. } = *challenges;
+
+        self.check_digest(proof)?;
 
         // Validate that the application circuit_id is within the registry domain.
         // (Internal circuit IDs are constants and don't need this check.)
         if !self
             .native_registry
-            .circuit_in_domain(pcd.proof.application.circuit_id)
+            .circuit_in_domain(proof.application.circuit_id)
         {
-            return Ok(false);
+            return Ok(None);
         }
 
         // Validate that the `left_header` and `right_header` lengths match
         // `HEADER_SIZE`. Alternatively, the `Proof` structure could be
         // parameterized on the `HEADER_SIZE`, but this appeared to be simpler.
-        if pcd.proof.application.left_header.len() != HEADER_SIZE
-            || pcd.proof.application.right_header.len() != HEADER_SIZE
+        if proof.application.left_header.len() != HEADER_SIZE
+            || proof.application.right_header.len() != HEADER_SIZE
         {
-            return Ok(false);
+            return Ok(None);
         }
 
         // Compute unified k(y), unified_bridge k(y), and application k(y).
-        let (unified_ky, unified_bridge_ky, application_ky) =
-            Emulator::emulate_wireless((&pcd.proof, pcd.data.clone(), y), |dr, witness| {
-                let (proof, data, y) = witness.cast();
-                let y = Element::alloc(dr, y)?;
-                let proof_inputs =
-                    ProofInputs::<_, C, HEADER_SIZE>::alloc_for_verify::<R, H>(dr, proof, data)?;
-
-                let (unified_ky, unified_bridge_ky) = proof_inputs.unified_ky_values(dr, &y)?;
-                let unified_ky = *unified_ky.value().take();
-                let unified_bridge_ky = *unified_bridge_ky.value().take();
-                let application_ky = *proof_inputs.application_ky(dr, &y)?.value().take();
-
-                Ok((unified_ky, unified_bridge_ky, application_ky))
-            })?;
+        let ky_source = native::SingleProofKySource::new::<C, HEADER_SIZE>(
+            [
+                &proof.application.left_header,
+                &proof.application.right_header,
+            ],
+            output_header,
+            proof.application.circuit_id,
+            &proof.unified_instance(),
+            y,
+        )?;
 
         // Build a and b polynomials for each revdot claim.
-        let source = native::SingleProofSource { proof: &pcd.proof };
+        let source = native::SingleProofSource { proof };
         let mut builder =
-            claims::Builder::new(&self.native_registry, self.num_application_steps, y, z);
+            claims::Builder::new(self.native_registry, self.num_application_steps, y, z);
         claims::native::build(&source, &mut builder)?;
 
-        // Check all native revdot claims.
-        let native_revdot_claims = {
-            let ky_source = native::SingleProofKySource {
-                raw_c: pcd.proof.ab.c,
-                application_ky,
-                unified_bridge_ky,
-                unified_ky,
-            };
-
-            native::ky_values(&ky_source)
-                .zip(builder.a.iter().zip(builder.b.iter()))
-                .all(|(ky, (a, b))| a.revdot(b) == ky)
+        // Fold all native revdot claims.
+        let native = {
+            fold_revdot_claims(
+                native::ky_values(&ky_source),
+                &builder.a,
+                &builder.b,
+                challenges.fold,
+            )
         };
 
-        // Check all nested revdot claims.
-        let nested_revdot_claims = {
-            let nested_source = nested::SingleProofSource { proof: &pcd.proof };
-            let y_nested = C::ScalarField::random(&mut rng);
-            let z_nested = C::ScalarField::random(&mut rng);
-            let mut nested_builder =
-                claims::Builder::new(&self.nested_registry, 0, y_nested, z_nested);
+        // Fold all nested revdot claims.
+        let (nested, num_nested) = {
+            let nested_source = nested::SingleProofSource { proof };
+            let mut nested_builder = claims::Builder::new(
+                self.nested_registry,
+                0,
+                challenges.y_nested,
+                challenges.z_nested,
+            );
             claims::nested::build(&nested_source, &mut nested_builder)?;
 
             let ky_source = nested::SingleProofKySource::<C::ScalarField>::new();
-            nested::ky_values(&ky_source)
-                .zip(nested_builder.a.iter().zip(nested_builder.b.iter()))
-                .all(|(ky, (a, b))| a.revdot(b) == ky)
+            let nested = fold_revdot_claims(
+                nested::ky_values(&ky_source),
+                &nested_builder.a,
+                &nested_builder.b,
+                challenges.fold_nested,
+            );
+            (nested, nested_builder.a.len())
         };
 
-        // Check polynomial evaluation claim.
-        let p_eval_claim = pcd.proof.p.poly.eval(pcd.proof.challenges.u) == pcd.proof.p.v;
-
-        // Check P commitment corresponds to polynomial and blind.
-        let p_commitment_claim = pcd
-            .proof
-            .p
-            .poly
-            .commit(C::host_generators(self.params), pcd.proof.p.blind)
-            == pcd.proof.p.commitment;
-
         // Check registry_xy polynomial evaluation at the sampled w.
         // registry_xy_poly is m(W, x, y) - the registry evaluated at current x, y, free in W.
         let registry_xy_claim = {
-            let x = pcd.proof.challenges.x;
-            let y = pcd.proof.challenges.y;
-            let poly_eval = pcd.proof.query.registry_xy_poly.eval(w);
+            let x = proof.challenges.x;
+            let y = proof.challenges.y;
+            let poly_eval = proof.query.registry_xy_poly.eval(w);
             let expected = self.native_registry.wxy(w, x, y);
             poly_eval == expected
         };
 
-        // TODO: Add checks for registry_wx0_poly, registry_wx1_poly, and registry_wy_poly.
-        // - registry_wx0/wx1: need child proof x challenges (x₀, x₁) which "disappear" in preamble
-        // - registry_wy: interstitial value that will be elided later
+        // Check registry_wx0 and registry_wx1 polynomial evaluations at the sampled y.
+        // registry_wx{i}_poly is m(w, x_i, Y) - the registry evaluated at current w and
+        // the ith child's x, free in Y.
+        let [x0, x1] = preamble::child_x::<C, R, HEADER_SIZE>(&proof.preamble.native_rx);
+        let registry_wx_claims = [
+            (&proof.s_prime.registry_wx0_poly, x0),
+            (&proof.s_prime.registry_wx1_poly, x1),
+        ]
+        .into_iter()
+        .all(|(poly, child_x)| {
+            poly.eval(y) == self.native_registry.wxy(proof.challenges.w, child_x, y)
+        });
+
+        // Check registry_wy polynomial evaluation at the sampled x.
+        // registry_wy_poly is m(w, X, y) - the registry evaluated at current w, y, free in X.
+        let registry_wy_claim = {
+            let w = proof.challenges.w;
+            let y = proof.challenges.y;
+            let poly_eval = proof.error_m.registry_wy_poly.eval(x);
+            let expected = self.native_registry.wxy(w, x, y);
+            poly_eval == expected
+        };
 
-        Ok(native_revdot_claims
-            && nested_revdot_claims
-            && p_eval_claim
-            && p_commitment_claim
-            && registry_xy_claim)
+        Ok(
+            (registry_xy_claim && registry_wx_claims && registry_wy_claim).then_some(
+                FoldedClaims {
+                    native,
+                    num_native: builder.a.len(),
+                    nested,
+                    num_nested,
+                },
+            ),
+        )
     }
 }
 
-mod native {
+pub(crate) mod native {
     use super::*;
     use crate::components::claims::{
         Source,
@@ -182,6 +626,39 @@ mod native {
         pub unified_ky: F,
     }
 
+    impl<F: PrimeField> SingleProofKySource<F> {
+        /// Computes the k(y) values of a proof from its headers, its
+        /// application circuit and its unified instance.
+        pub fn new<C: Cycle<CircuitField = F>, const HEADER_SIZE: usize>(
+            children: [&[F]; 2],
+            output_header: &[F],
+            circuit_id: CircuitIndex,
+            instance: &unified::Instance<C>,
+            y: F,
+        ) -> Result<Self> {
+            Emulator::emulate_wireless(y, |dr, y| {
+                let y = Element::alloc(dr, y)?;
+                let proof_inputs = ProofInputs::<_, C, HEADER_SIZE>::alloc_from_instance(
+                    dr,
+                    children,
+                    output_header,
+                    circuit_id,
+                    instance,
+                )?;
+
+                let (unified_ky, unified_bridge_ky) = proof_inputs.unified_ky_values(dr, &y)?;
+                let application_ky = proof_inputs.application_ky(dr, &y)?;
+
+                Ok(SingleProofKySource {
+                    raw_c: instance.c,
+                    application_ky: *application_ky.value().take(),
+                    unified_bridge_ky: *unified_bridge_ky.value().take(),
+                    unified_ky: *unified_ky.value().take(),
+                })
+            })
+        }
+    }
+
     impl<F: Field> KySource for SingleProofKySource<F> {
         type Ky = F;
 
@@ -207,7 +684,7 @@ mod native {
     }
 }
 
-mod nested {
+pub(crate) mod nested {
     use super::*;
     use crate::components::claims::{
         Source,
@@ -262,3 +739,358 @@ mod nested {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use ragu_circuits::polynomials::R;
+    use ragu_core::{Error, Result};
+    use ragu_pasta::{Fp, Pasta};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use core::iter::{empty, once};
+
+    use super::{FoldedClaims, VerifierChallenges};
+    use crate::{
+        ApplicationBuilder, ErasedPcd,
+        circuits::native::{stages::preamble, unified},
+        step::internal::trivial::Trivial,
+    };
+
+    #[test]
+    fn test_batched_p_commitments() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let beta = Fp::random(&mut rng);
+
+        let proof = app.trivial_proof();
+        let mut tampered = app.trivial_proof();
+        tampered.p.blind += Fp::ONE;
+
+        assert!(app.verifier().check_p_commitments(empty(), beta));
+        assert!(
+            app.verifier()
+                .check_p_commitments([&proof, &proof, &proof].into_iter(), beta)
+        );
+        assert!(!app.verifier().check_p_commitments(once(&tampered), beta));
+        assert!(
+            !app.verifier()
+                .check_p_commitments([&proof, &tampered, &proof].into_iter(), beta)
+        );
+
+        // Errors in different proofs must not cancel out.
+        let mut negated = app.trivial_proof();
+        negated.p.blind -= Fp::ONE;
+        assert!(
+            !app.verifier()
+                .check_p_commitments([&tampered, &negated].into_iter(), beta)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batched_revdot_claims() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        // Changing c only changes a k(y) value, so that only the revdot
+        // claims fail.
+        let pcd = app.seed(&mut rng, Trivial::new(), ())?.0.carry::<()>(());
+        let mut tampered = pcd.clone();
+        tampered.proof.ab.c += Fp::ONE;
+        let mut negated = pcd.clone();
+        negated.proof.ab.c -= Fp::ONE;
+
+        let verifier = app.verifier();
+        let challenges = VerifierChallenges::sample(&mut rng);
+        let claims = [&pcd, &tampered, &negated].map(|pcd| {
+            let pcd = ErasedPcd::new(pcd).expect("header fits");
+            verifier
+                .fold_claims(pcd.proof, &pcd.output_header, &challenges)
+                .expect("same application")
+                .expect("only revdot claims are tampered")
+        });
+        assert!(FoldedClaims::hold(once(&claims[0]), &challenges));
+        assert!(!FoldedClaims::hold(once(&claims[1]), &challenges));
+
+        // Errors in different proofs must not cancel out.
+        assert!(!FoldedClaims::hold(claims[1..].iter(), &challenges));
+
+        let batch = [&tampered, &pcd, &negated, &pcd, &pcd]
+            .map(|pcd| ErasedPcd::new(pcd).expect("header fits"));
+        assert_eq!(app.verify_batch(&batch, &mut rng)?, [0, 2]);
+        assert_eq!(app.verify_batch_deterministic(&batch)?, [0, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_polynomials_are_checked() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let pcd = app.seed(&mut rng, Trivial::new(), ())?.0.carry::<()>(());
+        assert!(app.verify(&pcd, &mut rng)?);
+
+        let w = pcd.proof.challenges.w;
+        let y = pcd.proof.challenges.y;
+        let [x0, x1] = preamble::child_x::<Pasta, R<13>, 4>(&pcd.proof.preamble.native_rx);
+
+        // Each polynomial is replaced with the registry restricted at the
+        // wrong point, which must be caught by the verifier.
+        let mut tampered = pcd.clone();
+        tampered.proof.s_prime.registry_wx0_poly = app.native_registry.wx(w, x0 + Fp::ONE);
+        assert!(!app.verify(&tampered, &mut rng)?);
+
+        let mut tampered = pcd.clone();
+        tampered.proof.s_prime.registry_wx1_poly = app.native_registry.wx(w, x1 + Fp::ONE);
+        assert!(!app.verify(&tampered, &mut rng)?);
+
+        let mut tampered = pcd.clone();
+        tampered.proof.error_m.registry_wy_poly = app.native_registry.wy(w, y + Fp::ONE);
+        assert!(!app.verify(&tampered, &mut rng)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_child_x_is_bound_to_preamble() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let left = app.seed(&mut rng, Trivial::new(), ())?.0.carry::<()>(());
+        let right = app.rerandomize(left.clone(), &mut rng)?;
+        let (proof, ()) = app.fuse(&mut rng, Trivial::new(), (), left.clone(), right.clone())?;
+        let pcd = proof.carry::<()>(());
+        assert!(app.verify(&pcd, &mut rng)?);
+
+        // The child x challenges are read from the preamble, which witnesses
+        // the children's unified instances.
+        let [x0, x1] = preamble::child_x::<Pasta, R<13>, 4>(&pcd.proof.preamble.native_rx);
+        assert_eq!(x0, left.proof.challenges.x);
+        assert_eq!(x1, right.proof.challenges.x);
+
+        // Claiming a different left child x, with a registry polynomial and
+        // commitment that are consistent with it, must be caught.
+        let mut tampered = pcd.clone();
+        let index = 3 * 4 + 1 + unified::X_WIRE;
+        let gate = 1 + index / 2;
+        let rx = tampered.proof.preamble.native_rx.forward();
+        if index.is_multiple_of(2) {
+            rx.a[gate] += Fp::ONE;
+        } else {
+            rx.b[gate] += Fp::ONE;
+        }
+        rx.c[gate] = rx.a[gate] * rx.b[gate];
+        assert_eq!(
+            preamble::child_x::<Pasta, R<13>, 4>(&tampered.proof.preamble.native_rx)[0],
+            x0 + Fp::ONE
+        );
+
+        let generators = <Pasta as arithmetic::Cycle>::host_generators(pasta);
+        let s_prime = &mut tampered.proof.s_prime;
+        s_prime.registry_wx0_poly = app.native_registry.wx(pcd.proof.challenges.w, x0 + Fp::ONE);
+        s_prime.registry_wx0_commitment = s_prime
+            .registry_wx0_poly
+            .commit(generators, s_prime.registry_wx0_blind);
+        assert!(!app.verify(&tampered, &mut rng)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_verification() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let pcd = app.seed(&mut rng, Trivial::new(), ())?.0.carry::<()>(());
+        let mut tampered = pcd.clone();
+        tampered.proof.p.blind += Fp::ONE;
+
+        // The deterministic verifier agrees with the randomized verifier on
+        // valid and invalid proofs.
+        assert!(app.verify(&pcd, &mut rng)?);
+        assert!(app.verify_deterministic(&pcd)?);
+
+        let batch = [ErasedPcd::new(&tampered)?, ErasedPcd::new(&pcd)?];
+        assert_eq!(app.verify_batch(&batch, &mut rng)?, [0]);
+        assert_eq!(app.verify_batch_deterministic(&batch)?, [0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_verification_of_fused_proof() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let (proof, ()) = app.fuse(
+            &mut rng,
+            Trivial::new(),
+            (),
+            app.trivial_pcd(),
+            app.trivial_pcd(),
+        )?;
+        let pcd = proof.carry::<()>(());
+        assert!(app.verify(&pcd, &mut rng)?);
+        assert!(app.verify_deterministic(&pcd)?);
+
+        let derive = |pcd| {
+            let batch = [ErasedPcd::new(pcd).expect("header fits")];
+            VerifierChallenges::<Pasta>::derive::<R<13>, 4>(pasta, app.digest(), &batch).w
+        };
+
+        // The challenges depend on the commitments of the proof and on its
+        // polynomials.
+        let mut tampered = pcd.clone();
+        tampered.proof.f.commitment = tampered.proof.eval.native_commitment;
+        assert_ne!(derive(&tampered), derive(&pcd));
+
+        let mut tampered = pcd.clone();
+        tampered.proof.query.registry_xy_poly[0] += Fp::ONE;
+        assert_ne!(derive(&tampered), derive(&pcd));
+        assert!(!app.verify_deterministic(&tampered)?);
+
+        let batch = [ErasedPcd::new(&pcd)?, ErasedPcd::new(&tampered)?];
+        assert_eq!(app.verify_batch_deterministic(&batch)?, [1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_application_mismatch() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let same = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        let other = ApplicationBuilder::<Pasta, R<13>, 5>::new().finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        assert_eq!(app.digest(), same.digest());
+        assert_ne!(app.digest(), other.digest());
+        assert_eq!(app.verifying_key()?.digest(), app.digest());
+
+        let proof = app.trivial_proof();
+        assert_eq!(proof.application_digest(), app.digest());
+
+        let pcd = proof.carry::<()>(());
+        assert!(matches!(
+            other.verify(&pcd, &mut rng),
+            Err(Error::ApplicationMismatch)
+        ));
+        assert!(matches!(
+            other.verify_deterministic(&pcd),
+            Err(Error::ApplicationMismatch)
+        ));
+
+        // In a batch, a foreign proof is reported like any other invalid proof.
+        let own = other.seed(&mut rng, Trivial::new(), ())?.0.carry::<()>(());
+        let batch = [ErasedPcd::new(&own)?, ErasedPcd::new(&pcd)?];
+        assert_eq!(other.verify_batch(&batch, &mut rng)?, [1]);
+        assert_eq!(other.verify_batch_deterministic(&batch)?, [1]);
+        assert!(matches!(
+            other.fuse(
+                &mut rng,
+                Trivial::new(),
+                (),
+                pcd.clone(),
+                other.trivial_pcd()
+            ),
+            Err(Error::ApplicationMismatch)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_children_are_gated_by_header() -> Result<()> {
+        use ragu_core::{
+            drivers::{Driver, DriverValue},
+            gadgets::{GadgetKind, Kind},
+            maybe::Maybe,
+        };
+        use ragu_primitives::Element;
+
+        use crate::{
+            header::{Header, Suffix},
+            step::{Encoded, Index, Step},
+        };
+
+        struct Value;
+
+        impl Header<Fp> for Value {
+            const SUFFIX: Suffix = Suffix::new(0);
+            type Data<'source> = Fp;
+            type Output = Kind![Fp; Element<'_, _>];
+
+            fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>>(
+                dr: &mut D,
+                witness: DriverValue<D, Self::Data<'source>>,
+            ) -> Result<<Self::Output as GadgetKind<Fp>>::Rebind<'dr, D>> {
+                Element::alloc(dr, witness)
+            }
+        }
+
+        struct Leaf;
+
+        impl Step<Pasta> for Leaf {
+            const INDEX: Index = Index::new(0);
+            type Witness<'source> = Fp;
+            type Aux<'source> = Fp;
+            type Left = ();
+            type Right = ();
+            type Output = Value;
+
+            fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = Fp>, const HEADER_SIZE: usize>(
+                &self,
+                dr: &mut D,
+                witness: DriverValue<D, Fp>,
+                left: DriverValue<D, ()>,
+                right: DriverValue<D, ()>,
+            ) -> Result<(
+                (
+                    Encoded<'dr, D, (), HEADER_SIZE>,
+                    Encoded<'dr, D, (), HEADER_SIZE>,
+                    Encoded<'dr, D, Value, HEADER_SIZE>,
+                ),
+                DriverValue<D, Fp>,
+            )> {
+                let left = Encoded::new(dr, left)?;
+                let right = Encoded::new(dr, right)?;
+                let value = Element::alloc(dr, witness)?;
+                let aux = D::just(|| *value.value().take());
+
+                Ok(((left, right, Encoded::from_gadget(value)), aux))
+            }
+        }
+
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register(Leaf)?
+            .finalize(pasta)?;
+        let mut rng = StdRng::seed_from_u64(1234);
+
+        let (proof, value) = app.seed(&mut rng, Leaf, Fp::from(7u64))?;
+        let pcd = proof.carry::<Value>(value);
+
+        // Rerandomization folds the proof with the empty trivial proof, whose
+        // claims only hold because its `()` header makes them vacuous.
+        let rerandomized = app.rerandomize(pcd, &mut rng)?;
+        assert!(app.verify(&rerandomized, &mut rng)?);
+
+        // The empty trivial proof cannot stand in for a child whose header
+        // carries data.
+        let forged = app.trivial_proof().carry::<Value>(value);
+        let rerandomized = app.rerandomize(forged, &mut rng)?;
+        assert!(!app.verify(&rerandomized, &mut rng)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! This module provides the [`VerifyingKey`] type, which verifies proofs
+//! without the [`Step`](crate::step::Step)s of an [`Application`].
+
+use arithmetic::Cycle;
+use ff::PrimeField;
+use ragu_circuits::{
+    polynomials::Rank,
+    registry::Registry,
+    wiring::{Terms, Wire, Wiring},
+};
+use ragu_core::{Error, Result};
+use rand::Rng;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{
+    Application, ApplicationBuilder, ErasedPcd, Pcd, Proof, application_digest,
+    header::Header,
+    proof::encoding::{Reader, VERSION, Writer, malformed},
+    verify::Verifier,
+};
+
+/// Everything needed to verify proof-carrying data for an [`Application`].
+///
+/// Unlike an [`Application`], a verifying key does not need the
+/// [`Step`](crate::step::Step)s that it was created for. It can be serialized
+/// with [`VerifyingKey::to_bytes`] and loaded with
+/// [`VerifyingKey::from_bytes`] by nodes that only verify proofs.
+///
+/// The encoding records the keys of both registries and the data needed to
+/// evaluate them: the constraint counts of every circuit and the wiring of
+/// each application step. The internal circuits are not serialized, as they
+/// are determined by the number of application steps. Loading a verifying key
+/// therefore does not evaluate or synthesize any circuit, and is instead
+/// authenticated by the [application digest](Application::digest), which
+/// commits to all of this data.
+pub struct VerifyingKey<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> {
+    native_registry: Registry<'params, C::CircuitField, R>,
+    nested_registry: Registry<'params, C::ScalarField, R>,
+    params: &'params C::Params,
+    num_application_steps: usize,
+    digest: C::CircuitField,
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize> Application<'params, C, R, HEADER_SIZE> {
+    /// Returns the [`VerifyingKey`] for this application.
+    pub fn verifying_key(&self) -> Result<VerifyingKey<'params, C, R, HEADER_SIZE>> {
+        let bytes = encode::<C, R, HEADER_SIZE>(
+            &self.native_registry,
+            &self.nested_registry,
+            self.num_application_steps,
+        )?;
+
+        VerifyingKey::from_bytes(self.params, &bytes, self.digest)
+    }
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
+    ApplicationBuilder<'params, C, R, HEADER_SIZE>
+{
+    /// Perform finalization as [`ApplicationBuilder::finalize`] does, but
+    /// produce only the [`VerifyingKey`] of the application.
+    pub fn finalize_verifying_key(
+        self,
+        params: &'params C::Params,
+    ) -> Result<VerifyingKey<'params, C, R, HEADER_SIZE>> {
+        self.finalize(params)?.verifying_key()
+    }
+
+    /// Creates a builder in which each application step is represented by its
+    /// wiring.
+    fn from_wirings(steps: impl IntoIterator<Item = Wiring<C::CircuitField, R>>) -> Result<Self> {
+        steps
+            .into_iter()
+            .try_fold(Self::new(), |mut builder, wiring| {
+                builder.native_registry = builder
+                    .native_registry
+                    .register_circuit_object(Box::new(wiring))?;
+                builder.num_application_steps += 1;
+                builder.step_names.push("wiring");
+                Ok(builder)
+            })
+    }
+}
+
+impl<'params, C: Cycle, R: Rank, const HEADER_SIZE: usize>
+    VerifyingKey<'params, C, R, HEADER_SIZE>
+{
+    /// Verifies some [`Pcd`] for the provided [`Header`], exactly as
+    /// [`Application::verify`] would.
+    pub fn verify<RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        pcd: &Pcd<'_, C, R, H>,
+        rng: RNG,
+    ) -> Result<bool> {
+        self.verifier().verify(pcd, rng)
+    }
+
+    /// Verifies many [`Pcd`] at once, exactly as [`Application::verify_batch`]
+    /// would.
+    pub fn verify_batch<RNG: Rng>(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+        rng: RNG,
+    ) -> Result<Vec<usize>> {
+        self.verifier().verify_batch(pcds, rng)
+    }
+
+    /// Verifies some [`Pcd`] without a random number generator, exactly as
+    /// [`Application::verify_deterministic`] would.
+    pub fn verify_deterministic<H: Header<C::CircuitField>>(
+        &self,
+        pcd: &Pcd<'_, C, R, H>,
+    ) -> Result<bool> {
+        self.verifier().verify_deterministic(pcd)
+    }
+
+    /// Verifies many [`Pcd`] at once without a random number generator,
+    /// exactly as [`Application::verify_batch_deterministic`] would.
+    pub fn verify_batch_deterministic(
+        &self,
+        pcds: &[ErasedPcd<'_, C, R, HEADER_SIZE>],
+    ) -> Result<Vec<usize>> {
+        self.verifier().verify_batch_deterministic(pcds)
+    }
+
+    /// Verifies a bare [`Proof`] and returns the [`Header::Data`] for `H` that
+    /// it attests to, exactly as [`Application::verified_header`] would.
+    pub fn verified_header<'source, RNG: Rng, H: Header<C::CircuitField>>(
+        &self,
+        proof: &Proof<C, R>,
+        rng: RNG,
+    ) -> Result<Option<H::Data<'source>>> {
+        self.verifier().verified_header::<_, H>(proof, rng)
+    }
+
+    /// Serializes this verifying key.
+    ///
+    /// The encoding begins with a version byte, the [`Rank`], the header size
+    /// and the number of application steps, followed by the keys of the
+    /// native and nested registries. The rest of the encoding is
+    /// length-prefixed and contains the number of multiplication and linear
+    /// constraints of every circuit of each registry, followed by the wiring
+    /// of each application step.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode::<C, R, HEADER_SIZE>(
+            &self.native_registry,
+            &self.nested_registry,
+            self.num_application_steps,
+        )
+    }
+
+    /// Deserializes a verifying key produced by [`VerifyingKey::to_bytes`],
+    /// checking that it was created for the application whose
+    /// [digest](Application::digest) is `digest`.
+    ///
+    /// No circuit is evaluated or synthesized. The application digest commits
+    /// to the registry keys and the evaluation data of the verifying key, and
+    /// so this only needs to hash the input to authenticate it.
+    ///
+    /// Returns [`Error::MalformedEncoding`] if the input is not the encoding of
+    /// a verifying key for this [`Rank`] and header size, and
+    /// [`Error::ApplicationMismatch`] if it is not the verifying key of the
+    /// application with the given digest.
+    pub fn from_bytes(
+        params: &'params C::Params,
+        bytes: &[u8],
+        digest: C::CircuitField,
+    ) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.byte()? != VERSION {
+            return Err(malformed("unsupported verifying key encoding version"));
+        }
+        if reader.byte()? != R::RANK as u8 {
+            return Err(malformed("verifying key was created for a different rank"));
+        }
+        if reader.len()? != HEADER_SIZE {
+            return Err(malformed(
+                "verifying key was created for a different header size",
+            ));
+        }
+        let num_application_steps = reader.len()?;
+        let native_key = reader.field()?;
+        let nested_key = reader.field()?;
+        let evaluation_data = reader.bytes()?;
+        reader.finish()?;
+
+        if application_digest::<C, HEADER_SIZE>(
+            params,
+            native_key,
+            nested_key,
+            num_application_steps,
+            evaluation_data,
+        ) != digest
+        {
+            return Err(Error::ApplicationMismatch);
+        }
+
+        let mut reader = Reader::new(evaluation_data);
+        let native_counts = decode_counts(&mut reader)?;
+        let nested_counts = decode_counts(&mut reader)?;
+        let steps = (0..num_application_steps)
+            .map(|_| decode_wiring(&mut reader))
+            .collect::<Result<Vec<_>>>()?;
+        reader.finish()?;
+
+        let (native_registry, nested_registry) =
+            ApplicationBuilder::<C, R, HEADER_SIZE>::from_wirings(steps)?
+                .internal_registries(params)?;
+
+        Ok(VerifyingKey {
+            native_registry: native_registry.finalize_with_key(native_key, &native_counts)?,
+            nested_registry: nested_registry.finalize_with_key(nested_key, &nested_counts)?,
+            params,
+            num_application_steps,
+            digest,
+        })
+    }
+
+    /// Returns the [digest](Application::digest) of the application that this
+    /// verifying key was created for.
+    pub fn digest(&self) -> C::CircuitField {
+        self.digest
+    }
+
+    fn verifier(&self) -> Verifier<'_, C, R, HEADER_SIZE> {
+        Verifier {
+            native_registry: &self.native_registry,
+            nested_registry: &self.nested_registry,
+            params: self.params,
+            num_application_steps: self.num_application_steps,
+            digest: self.digest,
+        }
+    }
+}
+
+/// Encodes the verifying key of the application with the given registries.
+fn encode<C: Cycle, R: Rank, const HEADER_SIZE: usize>(
+    native_registry: &Registry<'_, C::CircuitField, R>,
+    nested_registry: &Registry<'_, C::ScalarField, R>,
+    num_application_steps: usize,
+) -> Result<Vec<u8>> {
+    let mut writer = Writer::new();
+    writer.byte(VERSION);
+    writer.byte(R::RANK as u8);
+    writer.len(HEADER_SIZE);
+    writer.len(num_application_steps);
+    writer.field(&native_registry.get_key());
+    writer.field(&nested_registry.get_key());
+    writer.bytes(&evaluation_data(
+        native_registry,
+        nested_registry,
+        num_application_steps,
+    )?);
+    Ok(writer.into_bytes())
+}
+
+/// Encodes the data that a [`VerifyingKey`] needs to evaluate the registries
+/// of an application, besides their keys: the constraint counts of every
+/// circuit of the native and nested registries, followed by the wiring of each
+/// application step.
+///
+/// Returns an error if the wiring of a step cannot be recorded.
+pub(crate) fn evaluation_data<F: PrimeField, G: PrimeField, R: Rank>(
+    native_registry: &Registry<'_, F, R>,
+    nested_registry: &Registry<'_, G, R>,
+    num_application_steps: usize,
+) -> Result<Vec<u8>> {
+    let mut writer = Writer::new();
+    for counts in [
+        native_registry
+            .circuits()
+            .iter()
+            .map(|circuit| circuit.constraint_counts())
+            .collect::<Vec<_>>(),
+        nested_registry
+            .circuits()
+            .iter()
+            .map(|circuit| circuit.constraint_counts())
+            .collect(),
+    ] {
+        writer.len(counts.len());
+        for (num_multiplication_constraints, num_linear_constraints) in counts {
+            writer.len(num_multiplication_constraints);
+            writer.len(num_linear_constraints);
+        }
+    }
+    for circuit in &native_registry.circuits()[..num_application_steps] {
+        encode_wiring(&mut writer, &circuit.wiring()?);
+    }
+    Ok(writer.into_bytes())
+}
+
+/// Reads the constraint counts of the circuits of a registry, as written by
+/// [`evaluation_data`].
+fn decode_counts(reader: &mut Reader<'_>) -> Result<Vec<(usize, usize)>> {
+    (0..reader.count()?)
+        .map(|_| Ok((reader.len()?, reader.len()?)))
+        .collect()
+}
+
+/// Tag bit of a wiring term whose coefficient is encoded as a `u32` rather
+/// than as a field element, as are those of almost every constraint.
+const SMALL_COEFF: u8 = 1 << 2;
+
+/// As [`SMALL_COEFF`], for the negations of such coefficients.
+const SMALL_NEGATED_COEFF: u8 = 2 << 2;
+
+/// Returns `value` as an integer if it is one that fits in a `u32`.
+fn small<F: PrimeField>(value: F) -> Option<u32> {
+    let repr = value.to_repr();
+    let small = u32::from_le_bytes(repr.as_ref()[..4].try_into().ok()?);
+    (F::from(u64::from(small)) == value).then_some(small)
+}
+
+/// Writes the wiring of a circuit. Each term is written as a tag byte, which
+/// identifies the kind of wire and how its coefficient is encoded, followed by
+/// the index of the wire and the coefficient.
+fn encode_wiring<F: PrimeField, R: Rank>(writer: &mut Writer, wiring: &Wiring<F, R>) {
+    writer.len(wiring.num_multiplications());
+    for list in [wiring.sums(), wiring.constraints()] {
+        writer.len(list.len());
+        for terms in list {
+            writer.len(terms.len());
+            for &(wire, coeff) in terms {
+                let (tag, index) = match wire {
+                    Wire::A(i) => (0, i),
+                    Wire::B(i) => (1, i),
+                    Wire::C(i) => (2, i),
+                    Wire::Sum(i) => (3, i),
+                };
+                match (small(coeff), small(-coeff)) {
+                    (Some(value), _) => {
+                        writer.byte(tag | SMALL_COEFF);
+                        writer.len(index);
+                        writer.len(value as usize);
+                    }
+                    (None, Some(value)) => {
+                        writer.byte(tag | SMALL_NEGATED_COEFF);
+                        writer.len(index);
+                        writer.len(value as usize);
+                    }
+                    (None, None) => {
+                        writer.byte(tag);
+                        writer.len(index);
+                        writer.field(&coeff);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Reads a wiring written by [`encode_wiring`], rejecting coefficients that
+/// are not written in their shortest form.
+fn decode_wiring<F: PrimeField, R: Rank>(reader: &mut Reader<'_>) -> Result<Wiring<F, R>> {
+    fn terms_list<F: PrimeField>(reader: &mut Reader<'_>) -> Result<Vec<Terms<F>>> {
+        (0..reader.count()?)
+            .map(|_| {
+                (0..reader.count()?)
+                    .map(|_| {
+                        let tag = reader.byte()?;
+                        let index = reader.len()?;
+                        let wire = match tag & 3 {
+                            0 => Wire::A(index),
+                            1 => Wire::B(index),
+                            2 => Wire::C(index),
+                            _ => Wire::Sum(index),
+                        };
+                        let coeff = match tag & !3 {
+                            0 => {
+                                let coeff: F = reader.field()?;
+                                if small(coeff).is_some() || small(-coeff).is_some() {
+                                    return Err(malformed("non-canonical wiring coefficient"));
+                                }
+                                coeff
+                            }
+                            SMALL_COEFF => F::from(reader.len()? as u64),
+                            SMALL_NEGATED_COEFF => match reader.len()? {
+                                0 => return Err(malformed("non-canonical wiring coefficient")),
+                                value => -F::from(value as u64),
+                            },
+                            _ => return Err(malformed("invalid wiring term")),
+                        };
+                        Ok((wire, coeff))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    let num_multiplications = reader.len()?;
+    let sums = terms_list(reader)?;
+    let constraints = terms_list(reader)?;
+    Wiring::new(num_multiplications, sums, constraints)
+        .map_err(|_| malformed("invalid circuit wiring"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ragu_circuits::polynomials::R;
+    use ragu_core::{Error, Result};
+    use ragu_pasta::Pasta;
+
+    use super::VerifyingKey;
+    use crate::ApplicationBuilder;
+
+    #[test]
+    fn test_verifying_key_encoding() -> Result<()> {
+        let pasta = Pasta::baked();
+        let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(1)?
+            .finalize(pasta)?;
+
+        let vk = app.verifying_key()?;
+        assert_eq!(vk.digest(), app.digest());
+        assert_eq!(vk.native_registry.get_key(), app.native_registry.get_key());
+        assert_eq!(vk.nested_registry.get_key(), app.nested_registry.get_key());
+
+        // The verifying key is the same when produced by the builder.
+        let built = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+            .register_dummy_circuits(1)?
+            .finalize_verifying_key(pasta)?;
+        assert_eq!(built.digest(), app.digest());
+
+        let bytes = vk.to_bytes()?;
+        assert_eq!(built.to_bytes()?, bytes);
+        let decoded = VerifyingKey::<Pasta, R<13>, 4>::from_bytes(pasta, &bytes, app.digest())?;
+        assert_eq!(decoded.to_bytes()?, bytes);
+        assert_eq!(decoded.digest(), app.digest());
+
+        // Keys for a different application or header size are rejected.
+        let other = ApplicationBuilder::<Pasta, R<13>, 4>::new().finalize(pasta)?;
+        assert!(matches!(
+            VerifyingKey::<Pasta, R<13>, 4>::from_bytes(pasta, &bytes, other.digest()),
+            Err(Error::ApplicationMismatch)
+        ));
+        assert!(matches!(
+            VerifyingKey::<Pasta, R<13>, 5>::from_bytes(pasta, &bytes, app.digest()),
+            Err(Error::MalformedEncoding(_))
+        ));
+
+        // Changing a coefficient of the wiring of a step, or any other byte
+        // of the key, is detected by the digest.
+        for i in [bytes.len() - 4, 1 + 1 + 4 + 4 + 2] {
+            let mut tampered = bytes.clone();
+            tampered[i] ^= 1;
+            assert!(
+                VerifyingKey::<Pasta, R<13>, 4>::from_bytes(pasta, &tampered, app.digest())
+                    .is_err()
+            );
+        }
+
+        assert!(matches!(
+            VerifyingKey::<Pasta, R<13>, 4>::from_bytes(
+                pasta,
+                &bytes[..bytes.len() - 1],
+                app.digest()
+            ),
+            Err(Error::MalformedEncoding(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue},
+    gadgets::{GadgetKind, Kind},
+};
+use ragu_pasta::{Fp, Pasta};
+use ragu_pcd::{
+    ApplicationBuilder,
+    header::{Header, Suffix},
+    mock::MockApplication,
+    step::{BoxedStep, Dynamic, DynamicDriver, HeaderWires},
+};
+use ragu_primitives::Element;
+use rand::{SeedableRng, rngs::StdRng};
+
+struct LeafNode;
+
+impl<F: Field> Header<F> for LeafNode {
+    const SUFFIX: Suffix = Suffix::new(0);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+}
+
+struct InternalNode;
+
+impl<F: Field> Header<F> for InternalNode {
+    const SUFFIX: Suffix = Suffix::new(1);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+}
+
+type Square = Dynamic<Pasta, (), (), LeafNode, Fp, Fp>;
+type Sum = Dynamic<Pasta, LeafNode, LeafNode, InternalNode, (), Fp>;
+
+/// A leaf whose header is the square of its witness, or of its witness plus
+/// `error` if `error` is nonzero.
+fn square(index: usize, error: u64) -> Square {
+    Dynamic::new(
+        index,
+        move |witness: &Fp, _| {
+            let square = witness.square() + Fp::from(error);
+            Ok((square, square))
+        },
+        |dr: &mut dyn DynamicDriver<Fp>, witness: Option<&Fp>, headers: &HeaderWires| {
+            let (a, b, c) = dr.mul(&|| {
+                let witness = *witness.expect("witness");
+                Ok((witness, witness, witness.square()))
+            })?;
+            dr.enforce_zero(&[(a, Fp::ONE), (b, -Fp::ONE)])?;
+            dr.enforce_zero(&[(headers.output[0], Fp::ONE), (c, -Fp::ONE)])
+        },
+    )
+}
+
+fn sum(index: usize) -> Sum {
+    Dynamic::new(
+        index,
+        |_, children| {
+            let sum = children.left + children.right;
+            Ok((sum, sum))
+        },
+        |dr: &mut dyn DynamicDriver<Fp>, _, headers: &HeaderWires| {
+            let sum = dr.add(&[(headers.left[0], Fp::ONE), (headers.right[0], Fp::ONE)]);
+            assert_eq!(dr.value(sum), dr.value(headers.output[0]));
+            dr.enforce_zero(&[(headers.output[0], Fp::ONE), (sum, -Fp::ONE)])
+        },
+    )
+}
+
+#[test]
+fn dynamic_steps() -> Result<()> {
+    let pasta = Pasta::baked();
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register(square(0, 0))?
+        .register(sum(1))?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let leaf = app.seed(&mut rng, square(0, 0), Fp::from(3u64))?;
+    let leaf = leaf.0.carry::<LeafNode>(leaf.1);
+    assert_eq!(leaf.data, Fp::from(9u64));
+
+    let node = app.fuse(&mut rng, sum(1), (), leaf.clone(), leaf)?;
+    let node = node.0.carry::<InternalNode>(node.1);
+    assert_eq!(node.data, Fp::from(18u64));
+    assert!(app.verify(&node, &mut rng)?);
+
+    Ok(())
+}
+
+#[test]
+fn boxed_steps() -> Result<()> {
+    // Steps with different header types can be registered from a single
+    // collection, in the order of their runtime indices.
+    let steps = vec![BoxedStep::new(square(0, 0)), BoxedStep::new(sum(1))];
+
+    let pasta = Pasta::baked();
+    let app = steps
+        .into_iter()
+        .try_fold(ApplicationBuilder::<Pasta, R<13>, 4>::new(), |app, step| {
+            app.register_boxed(step)
+        })?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+    let leaf = app.seed(&mut rng, square(0, 0), Fp::from(3u64))?;
+    let leaf = leaf.0.carry::<LeafNode>(leaf.1);
+    let node = app.fuse(&mut rng, sum(1), (), leaf.clone(), leaf)?;
+    assert!(app.verify(&node.0.carry::<InternalNode>(node.1), &mut rng)?);
+
+    Ok(())
+}
+
+#[test]
+fn dynamic_steps_are_checked() -> Result<()> {
+    let app = MockApplication::<Pasta, R<13>, 4>::new()
+        .register(square(0, 0))?
+        .register(sum(1))?;
+
+    let leaf = app.seed(square(0, 0), Fp::from(3u64))?;
+    let leaf = leaf.0.carry::<LeafNode>(leaf.1);
+    let node = app.fuse(sum(1), (), leaf.clone(), leaf)?;
+    assert!(app.verify(&node.0.carry::<InternalNode>(node.1))?);
+
+    // The constraints of a step are checked against its native function.
+    assert!(matches!(
+        app.seed(square(0, 1), Fp::from(3u64)),
+        Err(Error::InvalidWitness(_))
+    ));
+
+    // Steps must still be registered in order of their runtime indices.
+    assert!(matches!(
+        MockApplication::<Pasta, R<13>, 4>::new().register(sum(1)),
+        Err(Error::Initialization(_))
+    ));
+    assert!(matches!(
+        app.seed(square(2, 0), Fp::from(3u64)),
+        Err(Error::Initialization(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue},
+    maybe::Maybe,
+};
+use ragu_pasta::{Fp, Pasta};
+use ragu_pcd::{
+    header::Header,
+    mock::MockApplication,
+    step::{Encoded, Index, UnaryStep},
+};
+use ragu_primitives::{Boolean, Element};
+
+/// The state of a counter that may be stopped.
+#[derive(Clone, Debug, PartialEq, Header)]
+#[ragu(suffix = 0)]
+struct Counter<F: Field> {
+    count: F,
+    stopped: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Header)]
+#[ragu(suffix = 1, field = Fp)]
+struct Total {
+    sum: Fp,
+}
+
+/// Starts a counter at zero.
+struct Start;
+
+impl<C: Cycle> UnaryStep<C> for Start {
+    const INDEX: Index = Index::new(0);
+    type Witness<'source> = ();
+    type Aux<'source> = ();
+    type Input = ();
+    type Output = Counter<C::CircuitField>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, ()>,
+        input: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, ()>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let input = Encoded::new(dr, input)?;
+        let output = Encoded::new(
+            dr,
+            D::just(|| Counter {
+                count: C::CircuitField::ZERO,
+                stopped: false,
+            }),
+        )?;
+
+        Ok(((input, output), D::just(|| ())))
+    }
+}
+
+/// Increments a counter, and stops it if the witness is `true`.
+struct Increment;
+
+impl<C: Cycle> UnaryStep<C> for Increment {
+    const INDEX: Index = Index::new(1);
+    type Witness<'source> = bool;
+    type Aux<'source> = Counter<C::CircuitField>;
+    type Input = Counter<C::CircuitField>;
+    type Output = Counter<C::CircuitField>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, bool>,
+        input: DriverValue<D, Counter<C::CircuitField>>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Counter<C::CircuitField>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let input = Encoded::new(dr, input)?;
+        let counter: &CounterGadget<'dr, D> = input.as_gadget();
+        let output = CounterGadget {
+            count: counter.count.add(dr, &Element::one()),
+            stopped: Boolean::alloc(dr, witness)?,
+        };
+        let aux = D::just(|| Counter {
+            count: *output.count.value().take(),
+            stopped: output.stopped.value().take(),
+        });
+
+        Ok(((input, Encoded::from_gadget(output)), aux))
+    }
+}
+
+#[test]
+fn derived_headers_encode_fields_in_order() -> Result<()> {
+    let app = MockApplication::<Pasta, R<13>, 4>::new()
+        .register_unary(Start)?
+        .register_unary(Increment)?;
+
+    let (proof, ()) = app.seed_unary(Start, ())?;
+    let counter = proof.carry::<Counter<Fp>>(Counter {
+        count: Fp::ZERO,
+        stopped: false,
+    });
+    assert!(app.verify(&counter)?);
+
+    let (proof, aux) = app.extend(Increment, true, counter)?;
+    assert_eq!(
+        aux,
+        Counter {
+            count: Fp::ONE,
+            stopped: true,
+        }
+    );
+    assert!(app.verify(&proof.carry::<Counter<Fp>>(aux))?);
+
+    Ok(())
+}
+
+#[test]
+fn derived_headers_decode() -> Result<()> {
+    assert_eq!(
+        Counter::<Fp>::decode(&[Fp::from(3), Fp::ONE, Fp::ZERO])?,
+        Counter {
+            count: Fp::from(3),
+            stopped: true,
+        }
+    );
+    assert_eq!(Total::decode(&[Fp::from(5)])?, Total { sum: Fp::from(5) });
+
+    assert!(matches!(
+        Counter::<Fp>::decode(&[Fp::from(3), Fp::from(2)]),
+        Err(Error::MalformedEncoding(_))
+    ));
+    assert!(matches!(
+        Counter::<Fp>::decode(&[Fp::from(3)]),
+        Err(Error::MalformedEncoding(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Result,
+    drivers::{Driver, DriverValue},
+    maybe::Maybe,
+};
+use ragu_pasta::{Fp, Pasta};
+use ragu_pcd::{
+    ApplicationBuilder,
+    header::Header,
+    ivc::{Iterate, Ivc, Seed},
+    step::{Encoded, Index, UnaryStep},
+};
+use ragu_primitives::Element;
+use rand::{SeedableRng, rngs::StdRng};
+
+/// The running total of a chain.
+#[derive(Clone, Header)]
+#[ragu(suffix = 0)]
+struct Total<F: Field> {
+    sum: F,
+}
+
+/// Starts a total from its witness.
+struct Start;
+
+impl<C: Cycle> UnaryStep<C> for Start {
+    const INDEX: Index = Index::new(0);
+    type Witness<'source> = C::CircuitField;
+    type Aux<'source> = ();
+    type Input = ();
+    type Output = Total<C::CircuitField>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, C::CircuitField>,
+        input: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, ()>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let input = Encoded::new(dr, input)?;
+        let output = Encoded::new(dr, witness.map(|sum| Total { sum }))?;
+
+        Ok(((input, output), D::just(|| ())))
+    }
+}
+
+/// Adds its witness to the total, returning the new total.
+#[derive(Clone)]
+struct Add;
+
+impl<C: Cycle> UnaryStep<C> for Add {
+    const INDEX: Index = Index::new(1);
+    type Witness<'source> = C::CircuitField;
+    type Aux<'source> = C::CircuitField;
+    type Input = Total<C::CircuitField>;
+    type Output = Total<C::CircuitField>;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, C::CircuitField>,
+        input: DriverValue<D, Total<C::CircuitField>>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, C::CircuitField>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let input = Encoded::new(dr, input)?;
+        let total: &TotalGadget<'dr, D> = input.as_gadget();
+        let addend = Element::alloc(dr, witness)?;
+        let sum = total.sum.add(dr, &addend);
+        let aux = sum.value().map(|sum| *sum);
+
+        Ok(((input, Encoded::from_gadget(TotalGadget { sum })), aux))
+    }
+}
+
+#[test]
+fn ivc_chain() -> Result<()> {
+    let pasta = Pasta::baked();
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register_unary(Seed::new(Start))?
+        .register_unary(Iterate::new(Add))?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let (mut ivc, ()) = Ivc::seed(&app, &mut rng, Start, Fp::from(2), Add)?;
+    assert_eq!(ivc.iterations(), 0);
+    assert_eq!(ivc.state().sum, Fp::from(2));
+
+    assert_eq!(ivc.step(&mut rng, Fp::from(3))?, Fp::from(5));
+    assert_eq!(ivc.step(&mut rng, Fp::from(4))?, Fp::from(9));
+    assert_eq!(ivc.iterations(), 2);
+    assert_eq!(ivc.state().sum, Fp::from(9));
+    assert_eq!(ivc.proof().data.0, Fp::from(2));
+    assert!(ivc.verify(&mut rng)?);
+
+    let exported = ivc.export(&mut rng)?;
+    assert_eq!(exported.data.0, Fp::from(2));
+    assert!(app.verify(&exported, &mut rng)?);
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue},
+    gadgets::{GadgetKind, Kind},
+    maybe::Maybe,
+};
+use ragu_pasta::{Fp, Pasta, PoseidonFp};
+use ragu_pcd::{
+    header::{Header, Suffix},
+    mock::MockApplication,
+    step::{Encoded, Index, Step},
+};
+use ragu_primitives::{Element, poseidon::Sponge};
+
+struct LeafNode;
+
+impl<F: Field> Header<F> for LeafNode {
+    const SUFFIX: Suffix = Suffix::new(0);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        let Some((value, _)) = elements.split_first() else {
+            return Err(Error::MalformedEncoding("header is empty".into()));
+        };
+
+        Ok(*value)
+    }
+}
+
+struct InternalNode;
+
+impl<F: Field> Header<F> for InternalNode {
+    const SUFFIX: Suffix = Suffix::new(1);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        let Some((value, _)) = elements.split_first() else {
+            return Err(Error::MalformedEncoding("header is empty".into()));
+        };
+
+        Ok(*value)
+    }
+}
+
+struct Hash2<'params, C: Cycle> {
+    poseidon_params: &'params C::CircuitPoseidon,
+}
+
+impl<C: Cycle> Step<C> for Hash2<'_, C> {
+    const INDEX: Index = Index::new(1);
+    type Witness<'source> = ();
+    type Aux<'source> = C::CircuitField;
+    type Left = LeafNode;
+    type Right = LeafNode;
+    type Output = InternalNode;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, Self::Witness<'source>>,
+        left: DriverValue<D, C::CircuitField>,
+        right: DriverValue<D, C::CircuitField>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let left = Encoded::new(dr, left)?;
+        let right = Encoded::new(dr, right)?;
+
+        let mut sponge = Sponge::new(dr, self.poseidon_params);
+        sponge.absorb(dr, left.as_gadget())?;
+        sponge.absorb(dr, right.as_gadget())?;
+        let output = sponge.squeeze(dr)?;
+        let output_value = output.value().map(|v| *v);
+        let output = Encoded::from_gadget(output);
+
+        Ok(((left, right, output), output_value))
+    }
+}
+
+struct WitnessLeaf<'params, C: Cycle> {
+    poseidon_params: &'params C::CircuitPoseidon,
+}
+
+impl<C: Cycle> Step<C> for WitnessLeaf<'_, C> {
+    const INDEX: Index = Index::new(0);
+    type Witness<'source> = C::CircuitField;
+    type Aux<'source> = C::CircuitField;
+    type Left = ();
+    type Right = ();
+    type Output = LeafNode;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        _left: DriverValue<D, ()>,
+        _right: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let leaf = Element::alloc(dr, witness)?;
+        let mut sponge = Sponge::new(dr, self.poseidon_params);
+        sponge.absorb(dr, &leaf)?;
+        let leaf = sponge.squeeze(dr)?;
+        let leaf_value = leaf.value().map(|v| *v);
+        let leaf_encoded = Encoded::from_gadget(leaf);
+
+        Ok((
+            (
+                Encoded::from_gadget(()),
+                Encoded::from_gadget(()),
+                leaf_encoded,
+            ),
+            leaf_value,
+        ))
+    }
+}
+
+#[test]
+fn mock_merging_operations() -> Result<()> {
+    let app = MockApplication::<Pasta, R<13>, 4>::new()
+        .register(WitnessLeaf {
+            poseidon_params: &PoseidonFp,
+        })?
+        .register(Hash2 {
+            poseidon_params: &PoseidonFp,
+        })?;
+
+    let leaf = |value: u64| -> Result<_> {
+        let (proof, data) = app.seed(
+            WitnessLeaf {
+                poseidon_params: &PoseidonFp,
+            },
+            Fp::from(value),
+        )?;
+        Ok(proof.carry::<LeafNode>(data))
+    };
+    let leaf1 = leaf(42)?;
+    let leaf2 = leaf(43)?;
+    assert!(app.verify(&leaf1)?);
+
+    let node = app.fuse(
+        Hash2 {
+            poseidon_params: &PoseidonFp,
+        },
+        (),
+        leaf1.clone(),
+        leaf2.clone(),
+    )?;
+    let node = node.0.carry::<InternalNode>(node.1);
+    assert!(app.verify(&node)?);
+    assert!(
+        !app.verify(
+            &node
+                .proof
+                .clone()
+                .carry::<InternalNode>(node.data + Fp::ONE)
+        )?
+    );
+
+    // Children are only accepted with the data their proofs were created with.
+    let forged = leaf2.proof.carry::<LeafNode>(leaf2.data + Fp::ONE);
+    assert!(matches!(
+        app.fuse(
+            Hash2 {
+                poseidon_params: &PoseidonFp,
+            },
+            (),
+            leaf1,
+            forged,
+        ),
+        Err(Error::InvalidWitness(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn mock_register_out_of_order_should_fail() {
+    let result = MockApplication::<Pasta, R<13>, 4>::new().register(Hash2 {
+        poseidon_params: &PoseidonFp,
+    });
+    assert!(matches!(result, Err(Error::Initialization(_))));
+}
@@ -2,20 +2,32 @@ use arithmetic::Cycle;
 use ff::Field;
 use ragu_circuits::polynomials::R;
 use ragu_core::{
-    Result,
+    Error, Result,
     drivers::{Driver, DriverValue},
     gadgets::{GadgetKind, Kind},
     maybe::Maybe,
 };
 use ragu_pasta::{Fp, Pasta};
 use ragu_pcd::{
-    ApplicationBuilder,
+    Application, ApplicationBuilder, CompressedPcd, CompressedProof, ErasedPcd, Pcd, Proof,
+    VerifyingKey,
     header::{Header, Suffix},
     step::{Encoded, Index, Step},
 };
 use ragu_primitives::{Element, poseidon::Sponge};
 use rand::{SeedableRng, rngs::StdRng};
 
+use std::sync::OnceLock;
+
+/// Decodes a header consisting of a single element.
+fn decode_element<F: Field>(elements: &[F]) -> Result<F> {
+    let Some((value, _)) = elements.split_first() else {
+        return Err(Error::MalformedEncoding("header is empty".into()));
+    };
+
+    Ok(*value)
+}
+
 struct LeafNode;
 
 impl<F: Field> Header<F> for LeafNode {
@@ -29,6 +41,10 @@ impl<F: Field> Header<F> for LeafNode {
     ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
         Element::alloc(dr, witness)
     }
+
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        decode_element(elements)
+    }
 }
 
 struct InternalNode;
@@ -44,6 +60,10 @@ impl<F: Field> Header<F> for InternalNode {
     ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
         Element::alloc(dr, witness)
     }
+
+    fn decode<'source>(elements: &[F]) -> Result<Self::Data<'source>> {
+        decode_element(elements)
+    }
 }
 
 struct Hash2<'params, C: Cycle> {
@@ -136,6 +156,66 @@ impl<C: Cycle> Step<C> for WitnessLeaf<'_, C> {
     }
 }
 
+type TestApplication = Application<'static, Pasta, R<13>, 4>;
+type TestPcd<H> = Pcd<'static, Pasta, R<13>, H>;
+
+/// An application with a leaf and an internal node merged from two leaves,
+/// which is proven once and shared by the tests below.
+struct Fixture {
+    app: TestApplication,
+    leaf: TestPcd<LeafNode>,
+    node: TestPcd<InternalNode>,
+}
+
+fn fixture() -> &'static Fixture {
+    static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+
+    FIXTURE.get_or_init(|| prove_fixture().expect("fixture should be proven"))
+}
+
+fn prove_fixture() -> Result<Fixture> {
+    let pasta = Pasta::baked();
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register(WitnessLeaf {
+            poseidon_params: Pasta::circuit_poseidon(pasta),
+        })?
+        .register(Hash2 {
+            poseidon_params: Pasta::circuit_poseidon(pasta),
+        })?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut leaf = || -> Result<_> {
+        let (proof, data) = app.seed(
+            &mut rng,
+            WitnessLeaf {
+                poseidon_params: Pasta::circuit_poseidon(pasta),
+            },
+            Fp::from(42u64),
+        )?;
+        Ok(proof.carry::<LeafNode>(data))
+    };
+    let leaf1 = leaf()?;
+    let leaf2 = leaf()?;
+
+    let (proof, data) = app.fuse(
+        &mut rng,
+        Hash2 {
+            poseidon_params: Pasta::circuit_poseidon(pasta),
+        },
+        (),
+        leaf1.clone(),
+        leaf2,
+    )?;
+    let node = proof.carry::<InternalNode>(data);
+
+    Ok(Fixture {
+        app,
+        leaf: leaf1,
+        node,
+    })
+}
+
 #[test]
 fn various_merging_operations() -> Result<()> {
     let pasta = Pasta::baked();
@@ -185,3 +265,140 @@ fn various_merging_operations() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn proof_encoding_round_trip() -> Result<()> {
+    let Fixture { app, node, .. } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // Proofs survive a round trip through their canonical encoding.
+    let bytes = node.proof.to_bytes();
+    let decoded = Proof::<Pasta, R<13>>::from_bytes::<4>(&bytes)?;
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert!(app.verify(&decoded.carry::<InternalNode>(node.data), &mut rng)?);
+
+    Ok(())
+}
+
+#[test]
+fn compressed_proofs() -> Result<()> {
+    let Fixture { app, node, .. } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // Compressed proofs verify only for the data they were created with.
+    let compressed = app.compress(node.clone(), &mut rng)?;
+    assert!(app.verify_compressed(&compressed)?);
+    let mut wrong_data = compressed.clone();
+    wrong_data.data += Fp::ONE;
+    assert!(!app.verify_compressed(&wrong_data)?);
+
+    // Compressed proofs are much smaller than proofs, survive a round trip
+    // through their canonical encoding and are rejected if tampered with.
+    let bytes = compressed.proof.to_bytes();
+    assert!(bytes.len() * 100 < node.proof.to_bytes().len());
+    let decoded = CompressedProof::<Pasta, R<13>>::from_bytes::<4>(&bytes)?;
+    assert_eq!(decoded.to_bytes(), bytes);
+    let mut tampered = bytes.clone();
+    // The lowest byte of the final inner product argument's folded scalar.
+    tampered[bytes.len() - 64] ^= 1;
+    let tampered = CompressedProof::<Pasta, R<13>>::from_bytes::<4>(&tampered)?;
+    for (proof, valid) in [(decoded, true), (tampered, false)] {
+        let pcd = CompressedPcd::<_, _, InternalNode> {
+            proof,
+            data: node.data,
+        };
+        assert_eq!(app.verify_compressed(&pcd)?, valid);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn batch_verification() -> Result<()> {
+    let Fixture { app, leaf, node } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // Proofs for different headers can be verified together, and invalid
+    // proofs in a batch are identified.
+    let wrong_data = node
+        .proof
+        .clone()
+        .carry::<InternalNode>(node.data + Fp::ONE);
+    let batch = [
+        ErasedPcd::new(leaf)?,
+        ErasedPcd::new(node)?,
+        ErasedPcd::new(&wrong_data)?,
+        ErasedPcd::new(node)?,
+    ];
+    assert!(app.verify_batch(&batch[..2], &mut rng)?.is_empty());
+    assert_eq!(app.verify_batch(&batch, &mut rng)?, [2]);
+
+    Ok(())
+}
+
+#[test]
+fn verifying_key() -> Result<()> {
+    let Fixture { app, node, .. } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // A verifying key loaded without any steps verifies the same proofs.
+    let vk_bytes = app.verifying_key()?.to_bytes()?;
+    let vk = VerifyingKey::<Pasta, R<13>, 4>::from_bytes(Pasta::baked(), &vk_bytes, app.digest())?;
+    assert!(vk.verify(node, &mut rng)?);
+    let wrong_data = node
+        .proof
+        .clone()
+        .carry::<InternalNode>(node.data + Fp::ONE);
+    let batch = [ErasedPcd::new(node)?, ErasedPcd::new(&wrong_data)?];
+    assert_eq!(vk.verify_batch(&batch, &mut rng)?, [1]);
+
+    // It also reveals the header data that a bare proof attests to.
+    assert_eq!(
+        vk.verified_header::<_, InternalNode>(&node.proof, &mut rng)?,
+        Some(node.data)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn verified_header() -> Result<()> {
+    let Fixture { app, node, .. } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // A bare proof reveals the header data it attests to, but only when read
+    // back with the header it was created for.
+    assert_eq!(
+        app.verified_header::<_, InternalNode>(&node.proof, &mut rng)?,
+        Some(node.data)
+    );
+    assert!(matches!(
+        app.verified_header::<_, LeafNode>(&node.proof, &mut rng),
+        Err(Error::MalformedEncoding(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn deterministic_verification() -> Result<()> {
+    let Fixture { app, leaf, node } = fixture();
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    // Verification without a random number generator agrees with randomized
+    // verification, including on invalid proofs.
+    let wrong_data = node
+        .proof
+        .clone()
+        .carry::<InternalNode>(node.data + Fp::ONE);
+    assert!(app.verify_deterministic(leaf)?);
+    assert!(app.verify_deterministic(node)?);
+    assert!(!app.verify_deterministic(&wrong_data)?);
+    assert!(!app.verify(&wrong_data, &mut rng)?);
+
+    let vk = app.verifying_key()?;
+    assert!(vk.verify_deterministic(node)?);
+    assert!(!vk.verify_deterministic(&wrong_data)?);
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Error, Result,
+    drivers::{Driver, DriverValue},
+    gadgets::{GadgetKind, Kind},
+    maybe::Maybe,
+};
+use ragu_pasta::{Fp, Pasta};
+use ragu_pcd::{
+    ApplicationBuilder,
+    header::{Header, Suffix},
+    step::{Encoded, Index, Step},
+};
+use ragu_primitives::{Element, poseidon::Sponge};
+use rand::{SeedableRng, rngs::StdRng};
+
+struct LeafNode;
+
+impl<F: Field> Header<F> for LeafNode {
+    const SUFFIX: Suffix = Suffix::new(0);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+}
+
+struct InternalNode;
+
+impl<F: Field> Header<F> for InternalNode {
+    const SUFFIX: Suffix = Suffix::new(1);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+}
+
+struct Hash2<'params, C: Cycle> {
+    poseidon_params: &'params C::CircuitPoseidon,
+}
+
+impl<C: Cycle> Step<C> for Hash2<'_, C> {
+    const INDEX: Index = Index::new(1);
+    type Witness<'source> = ();
+    type Aux<'source> = C::CircuitField;
+    type Left = LeafNode;
+    type Right = LeafNode;
+    type Output = InternalNode;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, Self::Witness<'source>>,
+        left: DriverValue<D, C::CircuitField>,
+        right: DriverValue<D, C::CircuitField>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let left = Encoded::new(dr, left)?;
+        let right = Encoded::new(dr, right)?;
+
+        let mut sponge = Sponge::new(dr, self.poseidon_params);
+        sponge.absorb(dr, left.as_gadget())?;
+        sponge.absorb(dr, right.as_gadget())?;
+        let output = sponge.squeeze(dr)?;
+        let output_value = output.value().map(|v| *v);
+        let output = Encoded::from_gadget(output);
+
+        Ok(((left, right, output), output_value))
+    }
+}
+
+struct WitnessLeaf<'params, C: Cycle> {
+    poseidon_params: &'params C::CircuitPoseidon,
+}
+
+impl<C: Cycle> Step<C> for WitnessLeaf<'_, C> {
+    const INDEX: Index = Index::new(0);
+    type Witness<'source> = C::CircuitField;
+    type Aux<'source> = C::CircuitField;
+    type Left = ();
+    type Right = ();
+    type Output = LeafNode;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        witness: DriverValue<D, Self::Witness<'source>>,
+        _left: DriverValue<D, ()>,
+        _right: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let leaf = Element::alloc(dr, witness)?;
+        let mut sponge = Sponge::new(dr, self.poseidon_params);
+        sponge.absorb(dr, &leaf)?;
+        let leaf = sponge.squeeze(dr)?;
+        let leaf_value = leaf.value().map(|v| *v);
+        let leaf_encoded = Encoded::from_gadget(leaf);
+
+        Ok((
+            (
+                Encoded::from_gadget(()),
+                Encoded::from_gadget(()),
+                leaf_encoded,
+            ),
+            leaf_value,
+        ))
+    }
+}
+
+/// A step that is never registered with the application, so that proving it
+/// fails.
+struct Unregistered;
+
+impl<C: Cycle> Step<C> for Unregistered {
+    const INDEX: Index = Index::new(2);
+    type Witness<'source> = ();
+    type Aux<'source> = ();
+    type Left = ();
+    type Right = ();
+    type Output = LeafNode;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, Self::Witness<'source>>,
+        _left: DriverValue<D, ()>,
+        _right: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Left, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Right, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )>
+    where
+        Self: 'dr,
+    {
+        let output = Element::alloc(dr, D::just(|| C::CircuitField::ZERO))?;
+
+        Ok((
+            (
+                Encoded::from_gadget(()),
+                Encoded::from_gadget(()),
+                Encoded::from_gadget(output),
+            ),
+            D::just(|| ()),
+        ))
+    }
+}
+
+#[test]
+fn scheduled_dag() -> Result<()> {
+    let pasta = Pasta::baked();
+    let poseidon_params = Pasta::circuit_poseidon(pasta);
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register(WitnessLeaf { poseidon_params })?
+        .register(Hash2 { poseidon_params })?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let mut scheduler = app.scheduler();
+    let leaf = scheduler.seed(WitnessLeaf { poseidon_params }, Fp::from(42u64), Ok);
+    // Nodes that the root does not depend on are never proven.
+    scheduler.seed(Unregistered, (), |()| Ok(Fp::ZERO));
+    // The same node may be used as more than one child.
+    let root = scheduler.fuse(Hash2 { poseidon_params }, (), &leaf, &leaf, Ok);
+
+    let root = scheduler.execute(&mut rng, root).expect("no node fails");
+    assert!(app.verify(&root, &mut rng)?);
+
+    Ok(())
+}
+
+#[test]
+fn scheduled_failure() -> Result<()> {
+    let pasta = Pasta::baked();
+    let poseidon_params = Pasta::circuit_poseidon(pasta);
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register(WitnessLeaf { poseidon_params })?
+        .register(Hash2 { poseidon_params })?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let mut scheduler = app.scheduler();
+    let unregistered = scheduler.seed(Unregistered, (), |()| Ok(Fp::ZERO));
+    let root = scheduler.fuse(
+        Hash2 { poseidon_params },
+        (),
+        &unregistered,
+        &unregistered,
+        Ok,
+    );
+
+    // Only the failed node is reported; its parent is skipped.
+    let errors = scheduler
+        .execute(&mut rng, root)
+        .err()
+        .expect("unregistered step fails");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].node, unregistered.index());
+    assert!(matches!(errors[0].error, Error::Initialization(_)));
+
+    Ok(())
+}
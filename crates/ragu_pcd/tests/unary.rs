@@ -0,0 +1,124 @@
+use arithmetic::Cycle;
+use ff::Field;
+use ragu_circuits::polynomials::R;
+use ragu_core::{
+    Result,
+    drivers::{Driver, DriverValue},
+    gadgets::{GadgetKind, Kind},
+    maybe::Maybe,
+};
+use ragu_pasta::{Fp, Pasta};
+use ragu_pcd::{
+    ApplicationBuilder,
+    header::{Header, Suffix},
+    step::{Encoded, Index, UnaryStep},
+};
+use ragu_primitives::Element;
+use rand::{SeedableRng, rngs::StdRng};
+
+struct Counter;
+
+impl<F: Field> Header<F> for Counter {
+    const SUFFIX: Suffix = Suffix::new(0);
+    type Data<'source> = F;
+    type Output = Kind![F; Element<'_, _>];
+
+    fn encode<'dr, 'source: 'dr, D: Driver<'dr, F = F>>(
+        dr: &mut D,
+        witness: DriverValue<D, Self::Data<'source>>,
+    ) -> Result<<Self::Output as GadgetKind<F>>::Rebind<'dr, D>> {
+        Element::alloc(dr, witness)
+    }
+}
+
+struct Start;
+
+impl<C: Cycle> UnaryStep<C> for Start {
+    const INDEX: Index = Index::new(0);
+    type Witness<'source> = ();
+    type Aux<'source> = C::CircuitField;
+    type Input = ();
+    type Output = Counter;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, Self::Witness<'source>>,
+        input: DriverValue<D, ()>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )> {
+        let input = Encoded::new(dr, input)?;
+        let output = Encoded::from_gadget(Element::zero(dr));
+
+        Ok(((input, output), D::just(|| C::CircuitField::ZERO)))
+    }
+}
+
+struct Increment;
+
+impl<C: Cycle> UnaryStep<C> for Increment {
+    const INDEX: Index = Index::new(1);
+    type Witness<'source> = ();
+    type Aux<'source> = C::CircuitField;
+    type Input = Counter;
+    type Output = Counter;
+
+    fn witness<'dr, 'source: 'dr, D: Driver<'dr, F = C::CircuitField>, const HEADER_SIZE: usize>(
+        &self,
+        dr: &mut D,
+        _: DriverValue<D, Self::Witness<'source>>,
+        input: DriverValue<D, C::CircuitField>,
+    ) -> Result<(
+        (
+            Encoded<'dr, D, Self::Input, HEADER_SIZE>,
+            Encoded<'dr, D, Self::Output, HEADER_SIZE>,
+        ),
+        DriverValue<D, Self::Aux<'source>>,
+    )> {
+        let input = Encoded::<_, Counter, HEADER_SIZE>::new(dr, input)?;
+        let output = input.as_gadget().add(dr, &Element::one());
+        let output_value = output.value().map(|v| *v);
+
+        Ok(((input, Encoded::from_gadget(output)), output_value))
+    }
+}
+
+#[test]
+fn incremental_computation() -> Result<()> {
+    let pasta = Pasta::baked();
+    let app = ApplicationBuilder::<Pasta, R<13>, 4>::new()
+        .register_unary(Start)?
+        .register_unary(Increment)?
+        .finalize(pasta)?;
+
+    let mut rng = StdRng::seed_from_u64(1234);
+
+    let (proof, count) = app.seed_unary(&mut rng, Start, ())?;
+    let mut pcd = proof.carry::<Counter>(count);
+    assert!(app.verify(&pcd, &mut rng)?);
+
+    // The input proof must attest to the data it carries for the extended
+    // proof to be valid.
+    let wrong = pcd.proof.clone().carry::<Counter>(count + Fp::ONE);
+    let (proof, count) = app.extend(&mut rng, Increment, (), wrong)?;
+    assert_eq!(count, Fp::from(2u64));
+    assert!(!app.verify(&proof.carry::<Counter>(count), &mut rng)?);
+
+    for expected in 1..=2u64 {
+        let (proof, count) = app.extend(&mut rng, Increment, (), pcd)?;
+        assert_eq!(count, Fp::from(expected));
+        pcd = proof.carry::<Counter>(count);
+        assert!(app.verify(&pcd, &mut rng)?);
+    }
+
+    // The proof is bound to the final count.
+    let wrong = pcd.proof.carry::<Counter>(Fp::from(3u64));
+    assert!(!app.verify(&wrong, &mut rng)?);
+
+    Ok(())
+}
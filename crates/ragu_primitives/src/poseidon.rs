@@ -13,7 +13,7 @@ use ragu_core::{
     routines::{Prediction, Routine},
 };
 
-use alloc::{vec, vec::Vec};
+use alloc::{format, vec, vec::Vec};
 use core::{marker::PhantomData, panic};
 
 use crate::{
@@ -111,10 +111,28 @@ impl<'dr, D: Driver<'dr>, P: arithmetic::PoseidonPermutation<D::F>> Sponge<'dr,
         }
     }
 
+    /// Applies the permutation to `state` within the `sponge/permutation`
+    /// [namespace](Driver::namespace).
+    fn permutation(
+        dr: &mut D,
+        params: &'dr P,
+        state: SpongeState<'dr, D, P>,
+    ) -> Result<SpongeState<'dr, D, P>> {
+        dr.namespace(
+            || "sponge",
+            |dr| {
+                dr.namespace(
+                    || "permutation",
+                    |dr| dr.routine(Permutation::from(params), state),
+                )
+            },
+        )
+    }
+
     fn permute(&mut self, dr: &mut D) -> Result<()> {
         match &mut self.mode {
             Mode::Squeeze { values, state } => {
-                *state = dr.routine(Permutation::from(self.params), state.clone())?;
+                *state = Self::permutation(dr, self.params, state.clone())?;
                 *values = state.get_rate();
             }
             Mode::Absorb { values, state } => {
@@ -122,7 +140,7 @@ impl<'dr, D: Driver<'dr>, P: arithmetic::PoseidonPermutation<D::F>> Sponge<'dr,
                     *state = state.add(dr, v);
                 }
                 values.clear();
-                *state = dr.routine(Permutation::from(self.params), state.clone())?;
+                *state = Self::permutation(dr, self.params, state.clone())?;
             }
         }
 
@@ -358,11 +376,12 @@ impl<F: Field, P: arithmetic::PoseidonPermutation<F>> Routine<F> for Permutation
             Ok(())
         };
 
-        for elems in core::iter::repeat_n(P::T, P::FULL_ROUNDS / 2)
+        for (i, elems) in core::iter::repeat_n(P::T, P::FULL_ROUNDS / 2)
             .chain(core::iter::repeat_n(1, P::PARTIAL_ROUNDS))
             .chain(core::iter::repeat_n(P::T, P::FULL_ROUNDS / 2))
+            .enumerate()
         {
-            round(dr, elems)?;
+            dr.namespace(|| format!("round {i}"), |dr| round(dr, elems))?;
         }
 
         Ok(state)
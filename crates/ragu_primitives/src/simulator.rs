@@ -8,13 +8,19 @@ use ragu_core::{
     routines::{Prediction, Routine},
 };
 
+use alloc::{format, string::String, vec::Vec};
+
 /// A driver that fully simulates everything that happens during synthesis,
 /// primarily for testing purposes.
+///
+/// The simulator records the current [namespace](Driver::namespace), and
+/// names it when a constraint is not satisfied.
 #[derive(Clone)]
 pub struct Simulator<F: Field> {
     num_allocations: usize,
     num_multiplications: usize,
     num_linear_constraints: usize,
+    namespaces: Vec<String>,
     _marker: core::marker::PhantomData<F>,
 }
 
@@ -31,6 +37,7 @@ impl<F: Field> Simulator<F> {
             num_allocations: 0,
             num_multiplications: 0,
             num_linear_constraints: 0,
+            namespaces: Vec::new(),
             _marker: core::marker::PhantomData,
         }
     }
@@ -57,6 +64,20 @@ impl<F: Field> Simulator<F> {
         self.num_linear_constraints
     }
 
+    /// Returns the path of the current namespace, with the name of each
+    /// enclosing namespace separated by `/`.
+    pub fn namespace_path(&self) -> String {
+        self.namespaces.join("/")
+    }
+
+    fn unsatisfied(&self, message: &str) -> Error {
+        if self.namespaces.is_empty() {
+            Error::InvalidWitness(message.into())
+        } else {
+            Error::InvalidWitness(format!("{message} at {}", self.namespace_path()).into())
+        }
+    }
+
     /// Execute the provided closure with a fresh `Simulator` driver.
     pub fn simulate<W: Send>(
         witness: W,
@@ -104,9 +125,7 @@ impl<'dr, F: Field> Driver<'dr> for Simulator<F> {
         let c = c.value();
 
         if a * b != c {
-            return Err(Error::InvalidWitness(
-                "multiplication constraint failed".into(),
-            ));
+            return Err(self.unsatisfied("multiplication constraint failed"));
         }
 
         self.num_multiplications += 1;
@@ -123,12 +142,20 @@ impl<'dr, F: Field> Driver<'dr> for Simulator<F> {
         self.num_linear_constraints += 1;
 
         if lc.value != F::ZERO {
-            return Err(Error::InvalidWitness("linear constraint failed".into()));
+            return Err(self.unsatisfied("linear constraint failed"));
         }
 
         Ok(())
     }
 
+    fn push_namespace<N: Into<String>>(&mut self, name: impl FnOnce() -> N) {
+        self.namespaces.push(name().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        self.namespaces.pop();
+    }
+
     fn routine<R: Routine<Self::F> + 'dr>(
         &mut self,
         routine: R,
@@ -149,3 +176,51 @@ impl<'dr, F: Field> Driver<'dr> for Simulator<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ragu_core::{Error, Result, drivers::Driver};
+    use ragu_pasta::Fp;
+
+    use alloc::{format, string::ToString};
+
+    use super::Simulator;
+    use crate::Element;
+
+    #[test]
+    fn test_namespace_path() -> Result<()> {
+        let result = Simulator::<Fp>::simulate(Fp::from(3), |dr, value| {
+            let value = Element::alloc(dr, value)?;
+            dr.namespace(
+                || "outer",
+                |dr| {
+                    // Only the second round is unsatisfied.
+                    for round in 3..5 {
+                        dr.namespace(
+                            || format!("round {round}"),
+                            |dr| {
+                                let round = Element::constant(dr, Fp::from(round));
+                                value.sub(dr, &round).enforce_zero(dr)
+                            },
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+
+            Ok(())
+        });
+
+        match result {
+            Err(Error::InvalidWitness(error)) => {
+                assert_eq!(
+                    error.to_string(),
+                    "linear constraint failed at outer/round 4"
+                )
+            }
+            _ => panic!("an unsatisfied constraint should be rejected"),
+        }
+
+        Ok(())
+    }
+}